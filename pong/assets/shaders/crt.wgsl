@@ -0,0 +1,31 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+@group(1) @binding(0) var screen_texture: texture_2d<f32>;
+@group(1) @binding(1) var screen_sampler: sampler;
+
+const CURVATURE: f32 = 0.06;
+const SCANLINE_STRENGTH: f32 = 0.22;
+const VIGNETTE_STRENGTH: f32 = 0.3;
+const PI: f32 = 3.14159265;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // bulge the picture out like the glass of an old tube
+    let centered = in.uv * 2.0 - 1.0;
+    let bent = centered * (1.0 + CURVATURE * dot(centered, centered));
+    let uv = bent * 0.5 + 0.5;
+
+    // sample before masking, texture reads have to stay in uniform control flow
+    var color = textureSample(screen_texture, screen_sampler, clamp(uv, vec2<f32>(0.0), vec2<f32>(1.0))).rgb;
+
+    let inside = step(0.0, uv.x) * step(uv.x, 1.0) * step(0.0, uv.y) * step(uv.y, 1.0);
+
+    let rows = f32(textureDimensions(screen_texture).y);
+    let scanline = sin(uv.y * rows * PI) * 0.5 + 0.5;
+    color *= 1.0 - SCANLINE_STRENGTH * scanline;
+
+    let vignette = 1.0 - VIGNETTE_STRENGTH * dot(bent, bent) * 0.5;
+    color *= max(vignette, 0.0) * inside;
+
+    return vec4<f32>(color, 1.0);
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, ecs::event::event_update_system};
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+use pong::{Collider, Velocity, ball::{Ball, BallCollided, BallSpeed, ball_collision}, gameplay::GameplayConfig, paddle::velocity_movement};
+
+const COLLIDER_COUNTS: [usize; 3] = [1, 10, 1000];
+const FRAME_SECONDS: f64 = 1. / 60.;
+// blocks laid out in rows across the arena, a thousand of them without any overlapping
+const GRID_COLUMNS: usize = 40;
+const GRID_SPACING: f32 = 45.;
+const BLOCK_SIZE: f32 = 20.;
+
+// nothing but what the systems read and `count` colliders, so they're all that's measured; every run moves it on a frame
+fn world_with_colliders(count: usize, velocity: Vec2) -> World {
+    let mut world = World::new();
+    let mut time = Time::<()>::default();
+    time.advance_by(Duration::from_secs_f64(FRAME_SECONDS));
+    world.insert_resource(time);
+    world.insert_resource(GameplayConfig::default());
+    world.init_resource::<Events<BallCollided>>();
+
+    for index in 0..count {
+        let position = Vec2::new((index % GRID_COLUMNS) as f32, (index / GRID_COLUMNS) as f32) * GRID_SPACING - Vec2::new(900., 500.);
+        world.spawn((Collider::cuboid(BLOCK_SIZE, BLOCK_SIZE), Transform::from_translation(position.extend(0.)), Velocity(velocity)));
+    }
+    world
+}
+
+// one ball swept against every collider each frame, the way a match with obstacles or a multi-ball power-up plays
+fn bench_ball_collision(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("ball_collision");
+    for count in COLLIDER_COUNTS {
+        let mut world = world_with_colliders(count, Vec2::ZERO);
+        world.spawn((Ball, Collider::cuboid(30., 30.), Transform::default(), Velocity(Vec2::new(700., 300.)), BallSpeed(700.)));
+        let mut schedule = Schedule::default();
+        schedule.add_systems((ball_collision, event_update_system::<BallCollided>).chain());
+
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |bencher, _| bencher.iter(|| schedule.run(&mut world)));
+    }
+    group.finish();
+}
+
+fn bench_velocity_movement(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("velocity_movement");
+    for count in COLLIDER_COUNTS {
+        let mut world = world_with_colliders(count, Vec2::new(0., 300.));
+        let mut schedule = Schedule::default();
+        schedule.add_systems(velocity_movement);
+
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |bencher, _| bencher.iter(|| schedule.run(&mut world)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_ball_collision, bench_velocity_movement);
+criterion_main!(benches);
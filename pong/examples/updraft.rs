@@ -0,0 +1,31 @@
+// a mod mutator built as a library of its own; `cargo build --example updraft --features dynamic_mods`, then copy it from target/debug/examples into the mods folder of the data directory
+use std::ffi::{CStr, c_char};
+
+use pong::mods::{MOD_API, ModBall};
+
+// the opposite of the built in gravity, lifting the ball in units per second per second
+const LIFT: f32 = 600.;
+const NAME: &[u8] = b"Updraft\0";
+
+#[no_mangle]
+pub extern "C" fn pong_mod_api() -> u32 {
+    MOD_API
+}
+
+#[no_mangle]
+pub extern "C" fn pong_mod_name() -> *const c_char {
+    CStr::from_bytes_with_nul(NAME).unwrap().as_ptr()
+}
+
+/// # Safety
+///
+/// `balls` has to point to `count` balls, all free to change until this returns; the game always hands them over that way.
+#[no_mangle]
+pub unsafe extern "C" fn pong_mod_update(balls: *mut ModBall, count: usize, seconds: f32) {
+    for ball in std::slice::from_raw_parts_mut(balls, count) {
+        // a ball waiting to be served stays where it is
+        if ball.velocity != [0., 0.] {
+            ball.velocity[1] += LIFT * seconds;
+        }
+    }
+}
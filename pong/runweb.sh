@@ -0,0 +1 @@
+cargo build --release --target wasm32-unknown-unknown && wasm-bindgen --no-typescript --target web --out-dir web/pkg --out-name pong ../target/wasm32-unknown-unknown/release/pong.wasm && python3 -m http.server
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+
+use crate::{GameMode, GameSet, GameState, ai::Difficulty, ball::BallDestroyed, score::{Rally, count_rally}};
+
+// how far one point moves the ai, on a scale from easy (-1) through normal (0) to hard (1)
+const POINT_STEP: f32 = 0.08;
+// extra pull for every point one side is ahead, so a runaway match closes up faster than a tight one drifts
+const LEAD_PULL: f32 = 0.03;
+// a point over with hardly a return says the sides are badly matched, a long rally that they're close
+const QUICK_POINT_HITS: u32 = 1;
+const LONG_RALLY_HITS: u32 = 10;
+
+pub struct AdaptivePlugin;
+
+impl Plugin for AdaptivePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<AdaptiveSkill>()
+        .add_systems(OnExit(GameState::Menu), reset_skill)
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, reset_skill)
+        // the rally has to be read before the goal clears it
+        .add_systems(FixedUpdate, adapt_to_points.before(count_rally).run_if(resource_equals(Difficulty::Adaptive)).run_if(resource_equals(GameMode::VersusComputer)).in_set(GameSet::Scoring));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct AdaptiveSkill {
+    level: f32,
+    // points the player is ahead by, or behind by when negative
+    lead: i32,
+}
+
+impl AdaptiveSkill {
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+}
+
+fn reset_skill(mut skill: ResMut<AdaptiveSkill>) {
+    *skill = AdaptiveSkill::default();
+}
+
+// the ai gets better when the player takes a point and eases off when it takes one
+fn adapt_to_points(mut skill: ResMut<AdaptiveSkill>, mut goals: EventReader<BallDestroyed>, rally: Res<Rally>) {
+    for goal in goals.read() {
+        let toward_player = if goal.player_scored { 1. } else { -1. };
+        skill.lead += toward_player as i32;
+
+        let weight = if rally.hits <= QUICK_POINT_HITS {
+            1.5
+        } else if rally.hits >= LONG_RALLY_HITS {
+            0.5
+        } else {
+            1.
+        };
+        skill.level = (skill.level + toward_player * POINT_STEP * weight + skill.lead as f32 * LEAD_PULL).clamp(-1., 1.);
+    }
+}
@@ -0,0 +1,394 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use rand::Rng;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ArenaConfig, Collider, GameSet, Velocity, adaptive::AdaptiveSkill, ball::{Ball, BallCollided, BallDestroyed}, console::ConsoleAppExt, paddle::{Paddle, PaddleMotion}, rng::GameRng};
+
+const MAX_PREDICTED_BOUNCES: usize = 16;
+
+pub struct AiPlugin;
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Difficulty>()
+        .init_resource::<AiPersonality>()
+        .register_type::<Difficulty>()
+        .register_type::<AiPersonality>()
+        .register_console_command("ai.difficulty", "ai.difficulty <easy|normal|hard|expert|adaptive>", difficulty_command)
+        .add_systems(FixedUpdate, (
+            perceive_balls,
+            aim_ai,
+            (
+                computer_movement_control.run_if(not(predictive_ai)),
+                predictive_movement_control.run_if(predictive_ai),
+            ),
+        ).chain().run_if(any_with_component::<AiControlled>()).in_set(GameSet::Ai));
+    }
+}
+
+#[derive(Component)]
+pub struct AiControlled;
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Expert,
+    // moves between easy and hard as the match goes, see `AdaptiveSkill`
+    Adaptive,
+}
+
+pub struct AiTuning {
+    pub speed: f32,
+    // how close to the ball the paddle has to be before it stops adjusting
+    pub deadzone: f32,
+    // how far past the center line the ball is when the ai starts tracking it
+    pub reaction_reach: f32,
+    // simulate the ball's path instead of chasing it
+    pub predictive: bool,
+    // seconds old the ball's position is by the time the ai acts on it
+    pub reaction_time: f32,
+    // spread of how far off the ai judges where to be, each time the ball changes course
+    pub aim_error: f32,
+    // the largest fraction of a move the paddle carries on past where it meant to stop
+    pub overshoot: f32,
+}
+
+impl AiTuning {
+    fn lerp(&self, other: &AiTuning, t: f32) -> AiTuning {
+        let mix = |from: f32, to: f32| from + (to - from) * t;
+        AiTuning {
+            speed: mix(self.speed, other.speed),
+            deadzone: mix(self.deadzone, other.deadzone),
+            reaction_reach: mix(self.reaction_reach, other.reaction_reach),
+            predictive: self.predictive,
+            reaction_time: mix(self.reaction_time, other.reaction_time),
+            aim_error: mix(self.aim_error, other.aim_error),
+            overshoot: mix(self.overshoot, other.overshoot),
+        }
+    }
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 5] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Expert, Difficulty::Adaptive];
+
+    pub fn tuning(&self, adaptive: &AdaptiveSkill) -> AiTuning {
+        match self {
+            Difficulty::Easy => AiTuning { speed: 380., deadzone: 40., reaction_reach: -250., predictive: false, reaction_time: 0.25, aim_error: 60., overshoot: 0.35 },
+            Difficulty::Normal => AiTuning { speed: 500., deadzone: 20., reaction_reach: 0., predictive: false, reaction_time: 0.15, aim_error: 35., overshoot: 0.2 },
+            Difficulty::Hard => AiTuning { speed: 650., deadzone: 8., reaction_reach: 350., predictive: false, reaction_time: 0.08, aim_error: 15., overshoot: 0.1 },
+            Difficulty::Expert => AiTuning { speed: 650., deadzone: 6., reaction_reach: 0., predictive: true, reaction_time: 0.05, aim_error: 6., overshoot: 0.05 },
+            // normal in the middle, easy at the bottom and hard at the top
+            Difficulty::Adaptive if adaptive.level() < 0. => Difficulty::Normal.tuning(adaptive).lerp(&Difficulty::Easy.tuning(adaptive), -adaptive.level()),
+            Difficulty::Adaptive => Difficulty::Normal.tuning(adaptive).lerp(&Difficulty::Hard.tuning(adaptive), adaptive.level()),
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Expert,
+            Difficulty::Expert => Difficulty::Adaptive,
+            Difficulty::Adaptive => Difficulty::Easy,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Expert => "Expert",
+            Difficulty::Adaptive => "Adaptive",
+        }
+    }
+}
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+pub enum AiPersonality {
+    #[default]
+    Balanced,
+    Aggressive,
+    Defensive,
+    Erratic,
+}
+
+impl AiPersonality {
+    pub const ALL: [AiPersonality; 4] = [AiPersonality::Balanced, AiPersonality::Aggressive, AiPersonality::Defensive, AiPersonality::Erratic];
+
+    pub fn next(&self) -> Self {
+        match self {
+            AiPersonality::Balanced => AiPersonality::Aggressive,
+            AiPersonality::Aggressive => AiPersonality::Defensive,
+            AiPersonality::Defensive => AiPersonality::Erratic,
+            AiPersonality::Erratic => AiPersonality::Balanced,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AiPersonality::Balanced => "Balanced",
+            AiPersonality::Aggressive => "Aggressive",
+            AiPersonality::Defensive => "Defensive",
+            AiPersonality::Erratic => "Erratic",
+        }
+    }
+
+    // added to the difficulty's reaction reach
+    pub fn reach_bonus(&self) -> f32 {
+        match self {
+            AiPersonality::Aggressive => 200.,
+            AiPersonality::Defensive => -200.,
+            AiPersonality::Balanced | AiPersonality::Erratic => 0.,
+        }
+    }
+
+    // fraction of full speed used to drift back to center while the ball is away
+    pub fn recenter_speed(&self) -> f32 {
+        match self {
+            AiPersonality::Aggressive => 0.35,
+            AiPersonality::Defensive => 1.,
+            AiPersonality::Balanced | AiPersonality::Erratic => 0.5,
+        }
+    }
+}
+
+// where on its paddle the ai is trying to take the ball, from -1 (bottom edge) to 1 (top edge)
+#[derive(Component)]
+pub struct AiAim {
+    pub offset: f32,
+    pub speed_factor: f32,
+    pub retarget: Timer,
+}
+
+impl Default for AiAim {
+    fn default() -> Self {
+        Self {
+            offset: 0.,
+            speed_factor: 1.,
+            retarget: Timer::from_seconds(0.6, TimerMode::Repeating),
+        }
+    }
+}
+
+// what the ai believes about the balls, a little late and a little wrong
+#[derive(Component)]
+pub struct AiPerception {
+    // when each look was taken, and every ball's position and velocity at the time
+    sightings: VecDeque<(f32, Vec<(Vec2, Vec2)>)>,
+    error: f32,
+    overshoot: f32,
+    // the ball has changed course, so the next target gets judged afresh
+    rethink: bool,
+    // where the paddle was last sent
+    pub target: f32,
+}
+
+impl Default for AiPerception {
+    fn default() -> Self {
+        Self {
+            sightings: VecDeque::new(),
+            error: 0.,
+            overshoot: 0.,
+            rethink: true,
+            target: 0.,
+        }
+    }
+}
+
+impl AiPerception {
+    // the oldest look still kept, which is the one the reaction time has caught up with
+    pub fn balls(&self) -> &[(Vec2, Vec2)] {
+        self.sightings.front().map_or(&[], |(_, balls)| balls.as_slice())
+    }
+
+    // where the paddle actually heads for `target`, off by the error and carried on past it by the overshoot
+    fn judge(&mut self, target: f32, paddle_y: f32, tuning: &AiTuning, random: &mut GameRng) -> f32 {
+        if self.rethink {
+            self.rethink = false;
+            self.error = random.normal() * tuning.aim_error;
+            self.overshoot = (target + self.error - paddle_y) * tuning.overshoot * random.gen_range(0.0..1.0);
+        }
+
+        let judged = target + self.error + self.overshoot;
+        // it notices once it gets there and comes back
+        if (judged - paddle_y).abs() < tuning.deadzone {
+            self.overshoot = 0.;
+        }
+        judged
+    }
+}
+
+fn perceive_balls(mut ai: Query<&mut AiPerception>, balls: Query<(&Transform, &Velocity), With<Ball>>, mut collisions: EventReader<BallCollided>, mut goals: EventReader<BallDestroyed>, difficulty: Res<Difficulty>, adaptive: Res<AdaptiveSkill>, time: Res<Time>) {
+    let now = time.elapsed_seconds();
+    let seen_at = now - difficulty.tuning(&adaptive).reaction_time;
+    let balls: Vec<(Vec2, Vec2)> = balls.iter().map(|(transform, velocity)| (transform.translation.truncate(), velocity.0)).collect();
+    let changed_course = collisions.read().count() + goals.read().count() > 0;
+
+    for mut perception in ai.iter_mut() {
+        perception.sightings.push_back((now, balls.clone()));
+        while perception.sightings.get(1).is_some_and(|(taken, _)| *taken <= seen_at) {
+            perception.sightings.pop_front();
+        }
+        perception.rethink |= changed_course;
+    }
+}
+
+fn aim_ai(mut ai: Query<(Entity, &mut AiAim)>, paddles: Query<(Entity, &Transform), With<Paddle>>, personality: Res<AiPersonality>, mut random: ResMut<GameRng>, time: Res<Time>) {
+    for (entity, mut aim) in ai.iter_mut() {
+        let opponent_y = paddles.iter()
+            .find(|(paddle, _)| *paddle != entity)
+            .map_or(0., |(_, transform)| transform.translation.y);
+
+        match *personality {
+            AiPersonality::Balanced | AiPersonality::Defensive => {
+                aim.offset = 0.;
+                aim.speed_factor = 1.;
+            }
+            // angle the return away from wherever the opponent is standing
+            AiPersonality::Aggressive => {
+                aim.offset = if opponent_y > 0. { -0.75 } else { 0.75 };
+                aim.speed_factor = 1.;
+            }
+            AiPersonality::Erratic => {
+                if aim.retarget.tick(time.delta()).just_finished() {
+                    aim.offset = random.gen_range(-0.8..0.8);
+                    aim.speed_factor = random.gen_range(0.6..1.2);
+                }
+            }
+        }
+    }
+}
+
+fn computer_movement_control(mut computer: Query<(&mut PaddleMotion, &mut AiPerception, &Transform, &Collider, &AiAim)>, difficulty: Res<Difficulty>, adaptive: Res<AdaptiveSkill>, personality: Res<AiPersonality>, arena: Res<ArenaConfig>, mut random: ResMut<GameRng>) {
+    let tuning = difficulty.tuning(&adaptive);
+
+    for (mut motion, mut perception, computer_transform, collider, aim) in computer.iter_mut() {
+        // with several balls in play, worry about the closest one
+        let ball_position = if let Some((position, _)) = perception.balls().iter().min_by(|a, b| {
+            let a = (a.0.x - computer_transform.translation.x).abs();
+            let b = (b.0.x - computer_transform.translation.x).abs();
+            a.total_cmp(&b)
+        }) { *position } else { continue; };
+
+        // +1 when the paddle guards the left goal, -1 for the right
+        let facing = -computer_transform.translation.x.signum();
+        let ball_x = ball_position.x * facing;
+
+        if ball_x > tuning.reaction_reach + personality.reach_bonus() {
+            let recenter_speed = tuning.speed * personality.recenter_speed();
+            if computer_transform.translation.y.abs() < tuning.deadzone {motion.target = 0.;}
+            else if 0. > computer_transform.translation.y {motion.target = recenter_speed}
+            else if 0. < computer_transform.translation.y {motion.target = -recenter_speed}
+            perception.target = 0.;
+            continue;
+        }
+
+        let speed = if ball_x > -(arena.width * 0.275) {tuning.speed * 0.7} else {tuning.speed};
+
+        let target = ball_position.y - aim.offset * collider.0.y / 2.;
+        let target = perception.judge(target, computer_transform.translation.y, &tuning, &mut random);
+        perception.target = target;
+        let distance = target - computer_transform.translation.y;
+        if distance.abs() < tuning.deadzone {
+            motion.target = 0.;
+        } else {
+            motion.target = speed * aim.speed_factor * distance.signum();
+        }
+    }
+}
+
+fn predictive_ai(difficulty: Res<Difficulty>, adaptive: Res<AdaptiveSkill>) -> bool {
+    difficulty.tuning(&adaptive).predictive
+}
+
+fn predictive_movement_control(mut computer: Query<(&mut PaddleMotion, &mut AiPerception, &Transform, &Collider, &AiAim)>, difficulty: Res<Difficulty>, adaptive: Res<AdaptiveSkill>, personality: Res<AiPersonality>, arena: Res<ArenaConfig>, mut random: ResMut<GameRng>) {
+    let tuning = difficulty.tuning(&adaptive);
+
+    for (mut motion, mut perception, computer_transform, collider, aim) in computer.iter_mut() {
+        let facing = -computer_transform.translation.x.signum();
+        let contact_x = computer_transform.translation.x + facing * (collider.0.x / 2. + 15.);
+
+        // go for whichever incoming ball arrives first
+        let intercept = perception.balls().iter()
+            .filter_map(|&(ball_position, ball_velocity)| {
+                let arrival = (contact_x - ball_position.x) / ball_velocity.x;
+                predict_intercept(ball_position, ball_velocity, contact_x, arena.half_height() - 15.)
+                    .map(|intercept| (arrival, intercept))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, intercept)| intercept);
+
+        let (target, speed) = match intercept {
+            Some(intercept) => (perception.judge(intercept - aim.offset * collider.0.y / 2., computer_transform.translation.y, &tuning, &mut random), tuning.speed * aim.speed_factor),
+            None => (0., tuning.speed * personality.recenter_speed()),
+        };
+        perception.target = target;
+
+        let distance = target - computer_transform.translation.y;
+        if distance.abs() < tuning.deadzone {
+            motion.target = 0.;
+        } else {
+            motion.target = speed * distance.signum();
+        }
+    }
+}
+
+// the ball's path to `target_x`: where it starts, each wall bounce along the way, and where it crosses
+pub fn predict_path(position: Vec2, velocity: Vec2, target_x: f32, half_height: f32) -> Option<Vec<Vec2>> {
+    let intercept = predict_intercept(position, velocity, target_x, half_height)?;
+
+    let mut points = vec![position];
+    let (mut position, mut velocity) = (position, velocity);
+    // a nearly vertical ball could bounce forever before it gets there
+    for _ in 0..MAX_PREDICTED_BOUNCES {
+        if velocity.y == 0. {
+            break;
+        }
+
+        let wall = half_height * velocity.y.signum();
+        let x = position.x + velocity.x * (wall - position.y) / velocity.y;
+        if (x - target_x) * velocity.x.signum() >= 0. {
+            break;
+        }
+
+        position = Vec2::new(x, wall);
+        velocity.y = -velocity.y;
+        points.push(position);
+    }
+    points.push(Vec2::new(target_x, intercept));
+    Some(points)
+}
+
+// where the ball will cross `target_x`, folding the path back on itself for each wall bounce
+pub fn predict_intercept(position: Vec2, velocity: Vec2, target_x: f32, half_height: f32) -> Option<f32> {
+    let time = (target_x - position.x) / velocity.x;
+    if !time.is_finite() || time < 0. {
+        return None;
+    }
+
+    let span = half_height * 2.;
+    let mut y = (position.y + velocity.y * time + half_height).rem_euclid(span * 2.);
+    if y > span {
+        y = span * 2. - y;
+    }
+    Some(y - half_height)
+}
+
+fn difficulty_command(world: &mut World, arguments: &[&str]) -> Result<String, String> {
+    let name = *arguments.first().ok_or("missing difficulty")?;
+    let difficulty = Difficulty::ALL.into_iter().find(|difficulty| difficulty.label().eq_ignore_ascii_case(name)).ok_or_else(|| format!("{name} isn't a difficulty"))?;
+
+    *world.resource_mut::<Difficulty>() = difficulty;
+    Ok(format!("difficulty set to {}", difficulty.label()))
+}
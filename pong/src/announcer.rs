@@ -0,0 +1,130 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{GameMode, GameState, MatchRules, is_match, mixer::AudioMixer, score::Scoreboard};
+
+// anything further back than this is stale by the time it would play
+const MAX_QUEUED: usize = 2;
+
+pub struct AnnouncerPlugin;
+
+impl Plugin for AnnouncerPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<AnnouncerQueue>()
+        .add_event::<Announce>()
+        .add_systems(OnExit(GameState::Menu), announce_match_start.run_if(is_match))
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, announce_match_start.run_if(is_match))
+        .add_systems(OnEnter(GameState::GameOver), announce_victory)
+        .add_systems(Update, announce_score_situations.run_if(in_state(GameState::Playing)).run_if(is_match).run_if(resource_changed::<Scoreboard>()))
+        .add_systems(Update, play_announcements.after(announce_score_situations));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnnouncerLine {
+    MatchStart,
+    MatchPoint,
+    Deuce,
+    Victory,
+}
+
+impl AnnouncerLine {
+    fn clip(&self) -> &'static str {
+        match self {
+            AnnouncerLine::MatchStart => "sounds/announcer/match_start.wav",
+            AnnouncerLine::MatchPoint => "sounds/announcer/match_point.wav",
+            AnnouncerLine::Deuce => "sounds/announcer/deuce.wav",
+            AnnouncerLine::Victory => "sounds/announcer/victory.wav",
+        }
+    }
+
+    // a line cuts off anything lower that's playing, and waits behind anything as high or higher
+    fn priority(&self) -> u8 {
+        match self {
+            AnnouncerLine::MatchStart => 0,
+            AnnouncerLine::MatchPoint | AnnouncerLine::Deuce => 1,
+            AnnouncerLine::Victory => 2,
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct Announce(pub AnnouncerLine);
+
+// the line currently being spoken; there is only ever one
+#[derive(Component)]
+pub struct AnnouncerVoice(AnnouncerLine);
+
+// lines waiting their turn, highest priority first
+#[derive(Resource, Default)]
+pub struct AnnouncerQueue(VecDeque<AnnouncerLine>);
+
+impl AnnouncerQueue {
+    fn push(&mut self, line: AnnouncerLine) {
+        if self.0.contains(&line) {
+            return;
+        }
+
+        let index = self.0.iter().position(|queued| queued.priority() < line.priority()).unwrap_or(self.0.len());
+        self.0.insert(index, line);
+        self.0.truncate(MAX_QUEUED);
+    }
+}
+
+fn announce_match_start(mut announcements: EventWriter<Announce>) {
+    announcements.send(Announce(AnnouncerLine::MatchStart));
+}
+
+fn announce_victory(mut announcements: EventWriter<Announce>, scoreboard: Res<Scoreboard>, rules: Res<MatchRules>, mode: Res<GameMode>) {
+    if mode.is_match() && scoreboard.winner(&rules).is_some() {
+        announcements.send(Announce(AnnouncerLine::Victory));
+    }
+}
+
+fn announce_score_situations(mut announcements: EventWriter<Announce>, scoreboard: Res<Scoreboard>, rules: Res<MatchRules>, mut last_score: Local<(u32, u32)>) {
+    let score = (scoreboard.player, scoreboard.computer);
+    if score == *last_score {
+        return;
+    }
+    *last_score = score;
+
+    if rules.time_limit.is_some() {
+        return;
+    }
+
+    let set_point = rules.point_target.saturating_sub(1);
+    if scoreboard.player == set_point && scoreboard.computer == set_point {
+        announcements.send(Announce(AnnouncerLine::Deuce));
+    } else if scoreboard.match_point(&rules) {
+        announcements.send(Announce(AnnouncerLine::MatchPoint));
+    }
+}
+
+fn play_announcements(mut commands: Commands, asset_server: Res<AssetServer>, mixer: Res<AudioMixer>, mut announcements: EventReader<Announce>, mut queue: ResMut<AnnouncerQueue>, voices: Query<(Entity, &AnnouncerVoice)>) {
+    let mut speaking = voices.get_single().ok().map(|(entity, voice)| (entity, voice.0));
+
+    for Announce(line) in announcements.read() {
+        if let Some((entity, current)) = speaking {
+            if line.priority() > current.priority() {
+                commands.entity(entity).despawn();
+                speaking = None;
+            }
+        }
+        queue.push(*line);
+    }
+
+    if speaking.is_some() {
+        return;
+    }
+
+    let line = if let Some(line) = queue.0.pop_front() { line } else { return; };
+    commands.spawn((
+        AnnouncerVoice(line),
+        AudioBundle {
+            source: asset_server.load(line.clip()),
+            settings: mixer.sfx(),
+        },
+    ));
+}
@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+use crate::{ArenaConfig, Collider, GameState, Velocity, ai::predict_path, ball::Ball, paddle::HumanControlled, settings::trajectory_assist_enabled};
+
+const PATH_COLOR: Color = Color::rgba(1., 1., 1., 0.25);
+
+pub struct AssistPlugin;
+
+impl Plugin for AssistPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, draw_trajectories.run_if(in_state(GameState::Playing)).run_if(trajectory_assist_enabled));
+    }
+}
+
+// show where each ball heading for a human player is going to end up
+fn draw_trajectories(mut gizmos: Gizmos, balls: Query<(&Transform, &Velocity), With<Ball>>, paddles: Query<(&Transform, &Collider), With<HumanControlled>>, arena: Res<ArenaConfig>) {
+    for (paddle_transform, collider) in paddles.iter() {
+        let side = paddle_transform.translation.x.signum();
+        let contact_x = paddle_transform.translation.x - side * (collider.0.x / 2. + 15.);
+
+        for (ball_transform, velocity) in balls.iter() {
+            if velocity.0.x.signum() != side {
+                continue;
+            }
+
+            if let Some(path) = predict_path(ball_transform.translation.truncate(), velocity.0, contact_x, arena.half_height() - 15.) {
+                gizmos.linestrip_2d(path, PATH_COLOR);
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use bevy::{prelude::*, audio::Volume};
+
+use rand::Rng;
+
+use crate::{GameSet, MatchRules, ball::{BallCollided, BallDestroyed}, is_match, mixer::AudioMixer, net::replaying, score::{Rally, Scoreboard, award_points, check_winner}};
+
+const BOUNCE_PITCH_STEP: f32 = 0.02;
+const BOUNCE_PITCH_MAX_RALLY: u32 = 25;
+const MATCH_POINT_CUE_GAIN: f32 = 1.5;
+
+// the sounds of the match itself; the menus, music and announcer have their own
+pub struct AudioPlugin;
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, collision_sounds.in_set(GameSet::Presentation))
+        // before the winner's checked, which clears the points a match point is judged on
+        .add_systems(FixedUpdate, point_sounds.after(award_points).before(check_winner).run_if(is_match).run_if(not(replaying)).in_set(GameSet::Scoring));
+    }
+}
+
+fn collision_sounds(mut events: EventReader<BallCollided>, mut commands: Commands, asset_server: Res<AssetServer>, mixer: Res<AudioMixer>, rally: Res<Rally>) {
+    let mut random = rand::thread_rng();
+
+    for event in events.read() {
+        // long rallies climb in pitch, and a little jitter keeps repeats from sounding identical
+        let pitch = 1. + rally.hits.min(BOUNCE_PITCH_MAX_RALLY) as f32 * BOUNCE_PITCH_STEP + random.gen_range(-0.04..0.04);
+        commands.spawn(AudioBundle {
+            source: asset_server.load(if event.is_smash() { "sounds/smash.wav" } else { "sounds/bounce.ogg" }),
+            settings: mixer.sfx().with_speed(pitch),
+        });
+    }
+}
+
+fn point_sounds(mut events: EventReader<BallDestroyed>, scoreboard: Res<Scoreboard>, rules: Res<MatchRules>, mut commands: Commands, asset_server: Res<AssetServer>, mixer: Res<AudioMixer>) {
+    let mut scored = false;
+    for event in events.read() {
+        scored = true;
+        commands.spawn(AudioBundle {
+            source: asset_server.load(if event.player_scored { "sounds/score.ogg" } else { "sounds/concede.wav" }),
+            settings: mixer.sfx(),
+        });
+    }
+
+    if scored && scoreboard.match_point(&rules) {
+        commands.spawn(AudioBundle {
+            source: asset_server.load("sounds/match_point.wav"),
+            settings: mixer.sfx().with_volume(Volume::new_relative(mixer.sfx_volume() * MATCH_POINT_CUE_GAIN)),
+        });
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::{ArenaConfig, GameState, MatchEntity, ball::{BallCollided, BallDestroyed}, theme::Theme};
+
+const GRID_SPACING: f32 = 120.;
+const GRID_LINE_WIDTH: f32 = 2.;
+// well behind the divider, paddles and ball trail
+const GRID_DEPTH: f32 = -10.;
+const BASE_BRIGHTNESS: f32 = 0.08;
+const PULSE_BRIGHTNESS: f32 = 0.12;
+const PULSE_DECAY: f32 = 6.;
+const FLASH_DECAY: f32 = 2.5;
+
+// the paddle sprites' colors
+
+pub struct BackgroundPlugin;
+
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<BackgroundGlow>()
+        .add_systems(OnExit(GameState::Menu), spawn_grid)
+        .add_systems(Update, (react_to_events, update_grid).chain());
+    }
+}
+
+#[derive(Resource)]
+pub struct BackgroundGlow {
+    // brief brightening on every bounce
+    pub pulse: f32,
+    // the scorer's color washing over the grid after a goal
+    pub flash: f32,
+    pub flash_color: Color,
+}
+
+impl Default for BackgroundGlow {
+    fn default() -> Self {
+        Self { pulse: 0., flash: 0., flash_color: Color::WHITE }
+    }
+}
+
+#[derive(Component)]
+pub struct GridLine;
+
+// the lines sit this far from the background toward the text color
+fn grid_color(theme: &Theme, brightness: f32) -> Vec3 {
+    let palette = theme.palette();
+    let (background, text) = (palette.background, palette.text);
+    Vec3::new(background.r(), background.g(), background.b()).lerp(Vec3::new(text.r(), text.g(), text.b()), brightness)
+}
+
+fn spawn_grid(mut commands: Commands, arena: Res<ArenaConfig>, theme: Res<Theme>) {
+    let base = grid_color(&theme, BASE_BRIGHTNESS);
+    let mut spawn_line = |position: Vec2, size: Vec2| {
+        commands.spawn((
+            MatchEntity,
+            GridLine,
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgb(base.x, base.y, base.z),
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(GRID_DEPTH)),
+                ..default()
+            },
+        ));
+    };
+
+    let columns = (arena.half_width() / GRID_SPACING) as i32;
+    for column in -columns..=columns {
+        spawn_line(Vec2::new(column as f32 * GRID_SPACING, 0.), Vec2::new(GRID_LINE_WIDTH, arena.height));
+    }
+
+    let rows = (arena.half_height() / GRID_SPACING) as i32;
+    for row in -rows..=rows {
+        spawn_line(Vec2::new(0., row as f32 * GRID_SPACING), Vec2::new(arena.width, GRID_LINE_WIDTH));
+    }
+}
+
+fn react_to_events(mut glow: ResMut<BackgroundGlow>, mut collisions: EventReader<BallCollided>, mut goals: EventReader<BallDestroyed>, theme: Res<Theme>, time: Res<Time>) {
+    let delta = time.delta_seconds();
+    glow.pulse = (glow.pulse - PULSE_DECAY * delta).max(0.);
+    glow.flash = (glow.flash - FLASH_DECAY * delta).max(0.);
+
+    if collisions.read().count() > 0 {
+        glow.pulse = 1.;
+    }
+
+    if let Some(goal) = goals.read().last() {
+        glow.flash = 1.;
+        let palette = theme.palette();
+        glow.flash_color = if goal.player_scored { palette.player } else { palette.opponent };
+    }
+}
+
+fn update_grid(glow: Res<BackgroundGlow>, theme: Res<Theme>, mut lines: Query<&mut Sprite, With<GridLine>>) {
+    if !glow.is_changed() {
+        return;
+    }
+
+    let brightness = BASE_BRIGHTNESS + PULSE_BRIGHTNESS * glow.pulse;
+    let base = grid_color(&theme, brightness);
+    let flash = Vec3::new(glow.flash_color.r(), glow.flash_color.g(), glow.flash_color.b()) * 0.5;
+    let color = base.lerp(flash, glow.flash);
+
+    for mut sprite in lines.iter_mut() {
+        sprite.color = Color::rgb(color.x, color.y, color.z);
+    }
+}
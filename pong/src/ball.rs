@@ -0,0 +1,458 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use rand::Rng;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ArenaConfig, Collider, GameMode, GameSet, GameState, MatchEntity, MatchRules, Velocity, console::{ConsoleAppExt, argument}, gameplay::GameplayConfig, offline_match, paddle::{Paddle, clamp_to_bounds, velocity_movement}, rng::GameRng, score::{Scoreboard, check_winner}};
+
+// hard bounds no ball leaves whatever sped it up or slowed it down; the upper one is well past the normal cap, but slow enough for the sweep to keep up
+pub const MIN_BALL_SPEED: f32 = 300.;
+pub const BALL_SPEED_LIMIT: f32 = 2600.;
+const MAX_BOUNCES_PER_FRAME: usize = 4;
+
+const SERVE_COUNTDOWN: f32 = 3.;
+// steeper serves can bounce between the walls for ages before reaching anyone
+const MAX_SERVE_ANGLE: f32 = 35.;
+// how far past the arena's edge the ball has to get to count as a goal
+const GOAL_MARGIN: f32 = 10.;
+
+pub const MAX_BOUNCE_ANGLE: f32 = std::f32::consts::FRAC_PI_3;
+// the outer share of each half of a paddle that smashes the ball back, steep and quicker than the rally speed for that one return
+const SMASH_ZONE: f32 = 0.2;
+const SMASH_SPEED_FACTOR: f32 = 1.3;
+
+pub struct BallPlugin;
+
+impl Plugin for BallPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Serve>()
+        .add_event::<BallDestroyed>()
+        .add_event::<BallCollided>()
+        .register_type::<BallSpeed>()
+        .register_console_command("set ball_speed", "set ball_speed <speed> - every ball in play", set_ball_speed_command)
+        .register_console_command("spawn ball", "spawn ball - another ball served from the middle", spawn_ball_command)
+        .add_systems(OnExit(GameState::Menu), spawn_ball.run_if(not(resource_equals(GameMode::Practice))))
+        .add_systems(FixedUpdate, (
+            serve_ball.before(velocity_movement),
+            // after the paddles have moved, so the ball is swept against where they are now
+            (
+                ball_collision.run_if(hand_rolled_physics),
+                limit_ball_velocity,
+                despawn_ball,
+            ).chain().after(clamp_to_bounds).run_if(any_with_component::<Ball>()),
+        ).in_set(GameSet::Physics))
+        .add_systems(FixedUpdate, reset_ball.after(check_winner).run_if(not(resource_equals(GameMode::Practice))).in_set(GameSet::Scoring));
+    }
+}
+
+#[derive(Component)]
+pub struct Ball;
+
+#[derive(Event)]
+pub struct BallDestroyed {
+    pub player_scored: bool,
+    // how fast the ball was going when it crossed the goal line
+    pub speed: f32,
+}
+
+#[derive(Event)]
+pub struct BallCollided {
+    pub ball: Entity,
+    pub other: Entity,
+    // where the ball struck a paddle, from -1 (bottom edge) to 1 (top edge); None for walls
+    pub paddle_offset: Option<f32>,
+}
+
+impl BallCollided {
+    pub fn is_paddle_hit(&self) -> bool {
+        self.paddle_offset.is_some()
+    }
+
+    pub fn is_smash(&self) -> bool {
+        self.paddle_offset.is_some_and(is_smash)
+    }
+}
+
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct BallSpeed(pub f32);
+
+impl Default for BallSpeed {
+    fn default() -> Self {
+        Self(GameplayConfig::default().ball_speed)
+    }
+}
+
+// how much the serve's angle changes from one point to the next, always up or down at random
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, Reflect)]
+pub enum ServeAngle {
+    Fixed,
+    #[default]
+    Varied,
+    // anything in the cone, dead level included
+    Wide,
+}
+
+impl ServeAngle {
+    // degrees from the horizontal
+    fn range(&self) -> (f32, f32) {
+        match self {
+            ServeAngle::Fixed => (30., 30.),
+            ServeAngle::Varied => (20., MAX_SERVE_ANGLE),
+            ServeAngle::Wide => (0., MAX_SERVE_ANGLE),
+        }
+    }
+
+    pub fn direction(&self, horizontal: f32, random: &mut GameRng) -> Vec2 {
+        let (min, max) = self.range();
+        let angle = if min < max { random.gen_range(min..=max) } else { min };
+        Vec2::new(horizontal, random.coin_flip() * angle.to_radians().tan()).normalize()
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ServeAngle::Fixed => ServeAngle::Varied,
+            ServeAngle::Varied => ServeAngle::Wide,
+            ServeAngle::Wide => ServeAngle::Fixed,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ServeAngle::Fixed => "Fixed",
+            ServeAngle::Varied => "Varied",
+            ServeAngle::Wide => "Wide",
+        }
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct Serve {
+    pub countdown: Timer,
+    pub direction: f32,
+}
+
+impl Default for Serve {
+    fn default() -> Self {
+        Self {
+            countdown: Timer::from_seconds(SERVE_COUNTDOWN, TimerMode::Once),
+            direction: 1.,
+        }
+    }
+}
+
+impl Serve {
+    pub fn start(&mut self, direction: f32) {
+        self.countdown.reset();
+        self.direction = direction;
+    }
+
+    pub fn is_counting_down(&self) -> bool {
+        !self.countdown.finished()
+    }
+
+    // true on the tick the countdown runs out and the ball should go
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        self.is_counting_down() && self.countdown.tick(delta).just_finished()
+    }
+
+    // runs the countdown out so the ball goes on the next tick
+    pub fn hurry(&mut self) {
+        if self.is_counting_down() {
+            let duration = self.countdown.duration();
+            self.countdown.set_elapsed(duration);
+        }
+    }
+}
+
+// moves the ball itself, sweeping it against every collider so fast balls can't skip past thin ones
+pub fn ball_collision(mut ball: Query<(Entity, &mut Transform, &mut Velocity, &mut BallSpeed, &Collider), With<Ball>>, others: Query<(Entity, &Collider, &Transform, Option<&Velocity>, Has<Paddle>), Without<Ball>>, mut events: EventWriter<BallCollided>, config: Res<GameplayConfig>, time: Res<Time>) {
+    for (ball, mut ball_transform, mut velocity, mut speed, ball_collider) in ball.iter_mut() {
+        let mut remaining = time.delta_seconds();
+
+        for _ in 0..MAX_BOUNCES_PER_FRAME {
+            let displacement = velocity.0 * remaining;
+            let nearest = others.iter()
+                .filter_map(|(other, other_collider, other_transform, other_velocity, is_paddle)| {
+                    sweep_aabb(ball_transform.translation.truncate(), ball_collider.0, displacement, other_transform.translation.truncate(), other_collider.0)
+                        .map(|hit| (hit, other, other_collider, other_transform, other_velocity, is_paddle))
+                })
+                .min_by(|a, b| a.0.time.total_cmp(&b.0.time));
+
+            let (hit, other, other_collider, other_transform, other_velocity, is_paddle) = if let Some(nearest) = nearest {
+                nearest
+            } else {
+                ball_transform.translation += displacement.extend(0.);
+                break;
+            };
+
+            ball_transform.translation += (displacement * hit.time).extend(0.);
+            remaining *= 1. - hit.time;
+
+            let paddle_offset = is_paddle.then(|| hit_offset(ball_transform.translation, other_transform.translation, other_collider.0));
+            events.send(BallCollided { ball, other, paddle_offset });
+
+            velocity.0 = bounce(velocity.0, &mut speed, &hit, paddle_offset, other_velocity.map_or(Vec2::ZERO, |other_velocity| other_velocity.0), &config);
+
+            // a paddle that moved this frame can end up inside the ball, which would just hit it again next frame
+            let depth = penetration_depth(ball_transform.translation.truncate(), ball_collider.0, other_transform.translation.truncate(), other_collider.0, hit.normal);
+            ball_transform.translation += (hit.normal * depth).extend(0.);
+        }
+    }
+}
+
+// which way the ball comes away from whatever it hit; paddles speed the rally up as they return it
+pub fn bounce(velocity: Vec2, speed: &mut BallSpeed, hit: &SweepHit, paddle_offset: Option<f32>, other_velocity: Vec2, config: &GameplayConfig) -> Vec2 {
+    match paddle_offset {
+        // a paddle corner that's mostly face still plays like the face
+        Some(offset) if hit.normal.x.abs() >= hit.normal.y.abs() => {
+            let smash = is_smash(offset);
+            let angle = if smash { offset.signum() * MAX_BOUNCE_ANGLE } else { offset * MAX_BOUNCE_ANGLE };
+            // never slows a ball something else has pushed past the cap
+            speed.0 = (speed.0 + config.ball_speed_increment).min(config.max_ball_speed.max(speed.0));
+
+            // a moving paddle drags the ball along with it
+            let spin = other_velocity.y * config.spin_transfer;
+            let bounce = Vec2::new(angle.cos(), angle.sin()) * speed.0 + Vec2::Y * spin;
+            let angle = bounce.y.atan2(bounce.x).clamp(-MAX_BOUNCE_ANGLE, MAX_BOUNCE_ANGLE);
+            // the smash doesn't carry into the rally speed, so the next return settles back down
+            let return_speed = if smash { speed.0 * SMASH_SPEED_FACTOR } else { speed.0 };
+            Vec2::new(hit.normal.x.signum() * angle.cos(), angle.sin()) * return_speed
+        }
+        // off the corner, so it comes away at whatever angle the corner sends it
+        _ if hit.is_corner() => {
+            let into = velocity.dot(hit.normal);
+            if into < 0. { velocity - 2. * into * hit.normal } else { velocity }
+        }
+        _ if hit.normal.x != 0. => Vec2::new(velocity.x.abs() * hit.normal.x, velocity.y),
+        _ => Vec2::new(velocity.x, velocity.y.abs() * hit.normal.y),
+    }
+}
+
+// after everything that bounces or deflects the ball, so nothing leaves it crawling, too fast to sweep, or bouncing between the walls forever
+pub fn limit_ball_velocity(mut balls: Query<(&mut Velocity, &mut BallSpeed), With<Ball>>) {
+    for (mut velocity, mut speed) in balls.iter_mut() {
+        // still, waiting to be served
+        if velocity.0 == Vec2::ZERO {
+            continue;
+        }
+
+        let length = velocity.0.length();
+        let angle = velocity.0.y.atan2(velocity.0.x.abs());
+        if !(MIN_BALL_SPEED..=BALL_SPEED_LIMIT).contains(&length) || angle.abs() > MAX_BOUNCE_ANGLE {
+            let angle = angle.clamp(-MAX_BOUNCE_ANGLE, MAX_BOUNCE_ANGLE);
+            let direction = if velocity.0.x < 0. { -1. } else { 1. };
+            velocity.0 = Vec2::new(direction * angle.cos(), angle.sin()) * length.clamp(MIN_BALL_SPEED, BALL_SPEED_LIMIT);
+        }
+
+        if !(MIN_BALL_SPEED..=BALL_SPEED_LIMIT).contains(&speed.0) {
+            speed.0 = speed.0.clamp(MIN_BALL_SPEED, BALL_SPEED_LIMIT);
+        }
+    }
+}
+
+pub struct SweepHit {
+    // fraction of the displacement travelled before contact
+    pub time: f32,
+    // along one axis for a face, or pointing out from the corner when it's only the corners that touch
+    pub normal: Vec2,
+}
+
+impl SweepHit {
+    pub fn is_corner(&self) -> bool {
+        self.normal.x != 0. && self.normal.y != 0.
+    }
+}
+
+// ray-casts the moving box's center against the other box grown by the moving box's size
+pub fn sweep_aabb(position: Vec2, size: Vec2, displacement: Vec2, other_position: Vec2, other_size: Vec2) -> Option<SweepHit> {
+    let half_extents = (size + other_size) / 2.;
+    let min = other_position - half_extents;
+    let max = other_position + half_extents;
+
+    let mut entry = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = Vec2::ZERO;
+
+    for axis in 0..2 {
+        if displacement[axis] == 0. {
+            if position[axis] <= min[axis] || position[axis] >= max[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let first = (min[axis] - position[axis]) / displacement[axis];
+        let second = (max[axis] - position[axis]) / displacement[axis];
+        let (near, far) = (first.min(second), first.max(second));
+
+        if near > entry {
+            entry = near;
+            normal = Vec2::ZERO;
+            normal[axis] = -displacement[axis].signum();
+        }
+        exit = exit.min(far);
+    }
+
+    // already overlapping, missed, or too far away this frame
+    if entry > exit || !(0. ..=1.).contains(&entry) {
+        return None;
+    }
+
+    // past the end of the face it reached, so it's the corner that was hit;
+    // unless it's already heading away from the corner, when only the face it's moving into can turn it
+    let contact = position + displacement * entry;
+    let offset = contact - other_position;
+    let other_half = other_size / 2.;
+    let side = if normal.x != 0. { 1 } else { 0 };
+    if offset[side].abs() > other_half[side] {
+        let corner = other_position + other_half * offset.signum();
+        let corner_normal = (contact - corner).normalize_or_zero();
+        if corner_normal.x != 0. && corner_normal.y != 0. && corner_normal.dot(displacement) < 0. {
+            normal = corner_normal;
+        }
+    }
+
+    Some(SweepHit { time: entry, normal })
+}
+
+// how far along the normal the box has to move before it stops overlapping the other one
+pub fn penetration_depth(position: Vec2, size: Vec2, other_position: Vec2, other_size: Vec2, normal: Vec2) -> f32 {
+    let overlap = (size + other_size) / 2. - (position - other_position).abs();
+    if overlap.x <= 0. || overlap.y <= 0. {
+        return 0.;
+    }
+
+    // clearing either axis is enough to separate them
+    (0..2)
+        .filter(|&axis| normal[axis] != 0.)
+        .map(|axis| overlap[axis] / normal[axis].abs())
+        .fold(f32::INFINITY, f32::min)
+}
+
+fn is_smash(paddle_offset: f32) -> bool {
+    paddle_offset.abs() >= 1. - SMASH_ZONE
+}
+
+pub fn hit_offset(ball: Vec3, paddle: Vec3, paddle_size: Vec2) -> f32 {
+    ((ball.y - paddle.y) / (paddle_size.y / 2.)).clamp(-1., 1.)
+}
+
+// the rapier backend takes over moving and bouncing things when it's enabled
+pub fn hand_rolled_physics() -> bool {
+    !cfg!(feature = "rapier")
+}
+
+fn past_goal_line(transform: &Transform, arena: &ArenaConfig) -> Option<f32> {
+    let goal_line = arena.half_width() + GOAL_MARGIN;
+    if transform.translation.x > goal_line {
+        Some(1.)
+    } else if transform.translation.x < -goal_line {
+        Some(-1.)
+    } else {
+        None
+    }
+}
+
+// the last ball off the field is kept for `reset_ball` to serve again, practice fires fresh ones instead
+pub fn despawn_ball(mut events: EventWriter<BallDestroyed>, mut commands: Commands, query: Query<(&Transform, &Velocity, Entity), With<Ball>>, scoreboard: Res<Scoreboard>, arena: Res<ArenaConfig>, mode: Res<GameMode>) {
+    let scored: Vec<(f32, f32, Entity)> = query.iter()
+        .filter_map(|(transform, velocity, entity)| past_goal_line(transform, &arena).map(|goal_side| (goal_side, velocity.0.length(), entity)))
+        .collect();
+    let keep_last = scored.len() == query.iter().len() && *mode != GameMode::Practice;
+
+    for (index, (goal_side, speed, entity)) in scored.iter().enumerate() {
+        let player_scored = *goal_side != scoreboard.player_side();
+        events.send(BallDestroyed { player_scored, speed: *speed });
+        if !(keep_last && index + 1 == scored.len()) {
+            commands.entity(*entity).despawn_recursive();
+        }
+    }
+}
+
+pub fn ball_bundle(asset_server: &AssetServer, translation: Vec3, velocity: Vec2) -> impl Bundle {
+    (
+        MatchEntity,
+        SpriteBundle {
+            texture: asset_server.load("sprites/ball.png"),
+            transform: Transform::from_translation(translation),
+            ..default()
+        },
+        Ball,
+        Collider::circle(15.),
+        Velocity(velocity),
+        BallSpeed::default(),
+    )
+}
+
+pub fn spawn_ball(mut commands: Commands, asset_server: ResMut<AssetServer>, mut serve: ResMut<Serve>, mut random: ResMut<GameRng>) {
+    serve.start(random.coin_flip());
+
+    // ball
+    commands.spawn(ball_bundle(&asset_server, Vec3::ZERO, Vec2::ZERO));
+}
+
+// back to the middle, still, at serving speed
+pub fn center_ball(transform: &mut Transform, velocity: &mut Velocity, speed: &mut BallSpeed) {
+    transform.translation = Vec3::ZERO;
+    velocity.0 = Vec2::ZERO;
+    *speed = BallSpeed::default();
+}
+
+// brings back the ball `despawn_ball` kept, rather than spawning a new one
+fn reset_ball(mut events: EventReader<BallDestroyed>, mut serve: ResMut<Serve>, mut balls: Query<(&mut Transform, &mut Velocity, &mut BallSpeed), With<Ball>>, scoreboard: Res<Scoreboard>, arena: Res<ArenaConfig>) {
+    let last = if let Some(last) = events.read().last() { last } else { return; };
+
+    // other balls are still in play
+    if balls.iter().any(|(transform, _, _)| past_goal_line(transform, &arena).is_none()) {
+        return;
+    }
+
+    // serve toward the side that just conceded
+    serve.start(if last.player_scored { -scoreboard.player_side() } else { scoreboard.player_side() });
+
+    for (mut transform, mut velocity, mut speed) in balls.iter_mut() {
+        center_ball(&mut transform, &mut velocity, &mut speed);
+    }
+}
+
+fn serve_ball(mut serve: ResMut<Serve>, rules: Res<MatchRules>, config: Res<GameplayConfig>, mut random: ResMut<GameRng>, time: Res<Time>, mut ball: Query<(&mut Velocity, &mut BallSpeed), With<Ball>>) {
+    if !serve.tick(time.delta()) {
+        return;
+    }
+
+    for (mut velocity, mut speed) in ball.iter_mut() {
+        speed.0 = config.ball_speed;
+        velocity.0 = rules.serve_angle.direction(serve.direction, &mut random) * speed.0;
+    }
+}
+
+fn set_ball_speed_command(world: &mut World, arguments: &[&str]) -> Result<String, String> {
+    offline_match(world)?;
+    let speed = argument::<f32>(arguments, 0, "speed")?.clamp(MIN_BALL_SPEED, BALL_SPEED_LIMIT);
+
+    let mut balls = world.query_filtered::<(&mut Velocity, &mut BallSpeed), With<Ball>>();
+    for (mut velocity, mut ball_speed) in balls.iter_mut(world) {
+        ball_speed.0 = speed;
+        velocity.0 = velocity.0.normalize_or_zero() * speed;
+    }
+    Ok(format!("ball speed set to {speed}"))
+}
+
+fn spawn_ball_command(world: &mut World, _: &[&str]) -> Result<String, String> {
+    offline_match(world)?;
+
+    let serve_angle = world.resource::<MatchRules>().serve_angle;
+    let ball_speed = world.resource::<GameplayConfig>().ball_speed;
+    let mut random = world.resource_mut::<GameRng>();
+    let horizontal = random.coin_flip();
+    let velocity = serve_angle.direction(horizontal, &mut random) * ball_speed;
+
+    let asset_server = world.resource::<AssetServer>().clone();
+    world.spawn(ball_bundle(&asset_server, Vec3::ZERO, velocity));
+    Ok("ball spawned".to_string())
+}
@@ -0,0 +1,199 @@
+use bevy::{prelude::*, input::keyboard::KeyboardInput, utils::HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::GameState;
+
+pub struct BindingsPlugin;
+
+impl Plugin for BindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Bindings>()
+        .init_resource::<RebindCapture>()
+        .init_resource::<KeyboardLayout>()
+        .add_systems(OnExit(GameState::Menu), cancel_capture)
+        .add_systems(Update, (learn_layout, capture_binding.run_if(in_state(GameState::Menu))));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BindingAction {
+    PlayerUp,
+    PlayerDown,
+    PlayerTwoUp,
+    PlayerTwoDown,
+    Pause,
+    Reset,
+}
+
+impl BindingAction {
+    pub const ALL: [BindingAction; 6] = [BindingAction::PlayerUp, BindingAction::PlayerDown, BindingAction::PlayerTwoUp, BindingAction::PlayerTwoDown, BindingAction::Pause, BindingAction::Reset];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BindingAction::PlayerUp => "Player 1 Up",
+            BindingAction::PlayerDown => "Player 1 Down",
+            BindingAction::PlayerTwoUp => "Player 2 Up",
+            BindingAction::PlayerTwoDown => "Player 2 Down",
+            BindingAction::Pause => "Pause",
+            BindingAction::Reset => "Reset",
+        }
+    }
+
+    // paddle movement goes by where the keys sit rather than what's printed on them, so the defaults land under the same fingers on any layout
+    pub fn is_movement(&self) -> bool {
+        matches!(self, BindingAction::PlayerUp | BindingAction::PlayerDown | BindingAction::PlayerTwoUp | BindingAction::PlayerTwoDown)
+    }
+}
+
+// movement keys are named for where they'd be on a us qwerty keyboard and matched by scan code, whose numbers differ between platforms;
+// anything not listed is matched by the key itself, which is everything in a browser since those already report keys by position
+#[cfg(not(any(target_arch = "wasm32", target_os = "macos")))]
+const POSITIONS: &[(KeyCode, u32)] = &[
+    (KeyCode::Key1, 2), (KeyCode::Key2, 3), (KeyCode::Key3, 4), (KeyCode::Key4, 5), (KeyCode::Key5, 6),
+    (KeyCode::Key6, 7), (KeyCode::Key7, 8), (KeyCode::Key8, 9), (KeyCode::Key9, 10), (KeyCode::Key0, 11),
+    (KeyCode::Q, 16), (KeyCode::W, 17), (KeyCode::E, 18), (KeyCode::R, 19), (KeyCode::T, 20),
+    (KeyCode::Y, 21), (KeyCode::U, 22), (KeyCode::I, 23), (KeyCode::O, 24), (KeyCode::P, 25),
+    (KeyCode::A, 30), (KeyCode::S, 31), (KeyCode::D, 32), (KeyCode::F, 33), (KeyCode::G, 34),
+    (KeyCode::H, 35), (KeyCode::J, 36), (KeyCode::K, 37), (KeyCode::L, 38),
+    (KeyCode::Z, 44), (KeyCode::X, 45), (KeyCode::C, 46), (KeyCode::V, 47), (KeyCode::B, 48),
+    (KeyCode::N, 49), (KeyCode::M, 50),
+];
+#[cfg(target_os = "macos")]
+const POSITIONS: &[(KeyCode, u32)] = &[
+    (KeyCode::Key1, 18), (KeyCode::Key2, 19), (KeyCode::Key3, 20), (KeyCode::Key4, 21), (KeyCode::Key5, 23),
+    (KeyCode::Key6, 22), (KeyCode::Key7, 26), (KeyCode::Key8, 28), (KeyCode::Key9, 25), (KeyCode::Key0, 29),
+    (KeyCode::Q, 12), (KeyCode::W, 13), (KeyCode::E, 14), (KeyCode::R, 15), (KeyCode::T, 17),
+    (KeyCode::Y, 16), (KeyCode::U, 32), (KeyCode::I, 34), (KeyCode::O, 31), (KeyCode::P, 35),
+    (KeyCode::A, 0), (KeyCode::S, 1), (KeyCode::D, 2), (KeyCode::F, 3), (KeyCode::G, 5),
+    (KeyCode::H, 4), (KeyCode::J, 38), (KeyCode::K, 40), (KeyCode::L, 37),
+    (KeyCode::Z, 6), (KeyCode::X, 7), (KeyCode::C, 8), (KeyCode::V, 9), (KeyCode::B, 11),
+    (KeyCode::N, 45), (KeyCode::M, 46),
+];
+#[cfg(target_arch = "wasm32")]
+const POSITIONS: &[(KeyCode, u32)] = &[];
+
+fn scan_code(key: KeyCode) -> Option<ScanCode> {
+    POSITIONS.iter().find(|(position, _)| *position == key).map(|(_, scan)| ScanCode(*scan))
+}
+
+fn position_key(scan: ScanCode) -> Option<KeyCode> {
+    POSITIONS.iter().find(|(_, position)| *position == scan.0).map(|(key, _)| *key)
+}
+
+pub fn movement_pressed(keys: &Input<KeyCode>, scans: &Input<ScanCode>, key: KeyCode) -> bool {
+    match scan_code(key) {
+        Some(scan) => scans.pressed(scan),
+        None => keys.pressed(key),
+    }
+}
+
+// what each scan code turned out to type on this keyboard, picked up as keys are pressed, so movement keys can be shown by the letter on them
+#[derive(Resource, Default)]
+pub struct KeyboardLayout(HashMap<u32, KeyCode>);
+
+impl KeyboardLayout {
+    pub fn binding_name(&self, bindings: &Bindings, action: BindingAction) -> String {
+        let key = bindings.key(action);
+        if !action.is_movement() {
+            return key_name(key);
+        }
+
+        let typed = scan_code(key).and_then(|scan| self.0.get(&scan.0).copied());
+        key_name(typed.unwrap_or(key))
+    }
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Bindings {
+    pub player_up: KeyCode,
+    pub player_down: KeyCode,
+    pub player_two_up: KeyCode,
+    pub player_two_down: KeyCode,
+    pub pause: KeyCode,
+    pub reset: KeyCode,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            player_up: KeyCode::W,
+            player_down: KeyCode::S,
+            player_two_up: KeyCode::Up,
+            player_two_down: KeyCode::Down,
+            pause: KeyCode::P,
+            reset: KeyCode::R,
+        }
+    }
+}
+
+impl Bindings {
+    pub fn key(&self, action: BindingAction) -> KeyCode {
+        match action {
+            BindingAction::PlayerUp => self.player_up,
+            BindingAction::PlayerDown => self.player_down,
+            BindingAction::PlayerTwoUp => self.player_two_up,
+            BindingAction::PlayerTwoDown => self.player_two_down,
+            BindingAction::Pause => self.pause,
+            BindingAction::Reset => self.reset,
+        }
+    }
+
+    fn key_mut(&mut self, action: BindingAction) -> &mut KeyCode {
+        match action {
+            BindingAction::PlayerUp => &mut self.player_up,
+            BindingAction::PlayerDown => &mut self.player_down,
+            BindingAction::PlayerTwoUp => &mut self.player_two_up,
+            BindingAction::PlayerTwoDown => &mut self.player_two_down,
+            BindingAction::Pause => &mut self.pause,
+            BindingAction::Reset => &mut self.reset,
+        }
+    }
+
+    // a key can only do one thing, so whatever had it before takes over the old key
+    pub fn rebind(&mut self, action: BindingAction, key: KeyCode) {
+        let previous = self.key(action);
+        if let Some(clash) = BindingAction::ALL.into_iter().find(|other| *other != action && self.key(*other) == key) {
+            *self.key_mut(clash) = previous;
+        }
+        *self.key_mut(action) = key;
+    }
+}
+
+// the action waiting for its next key press on the controls screen
+#[derive(Resource, Default)]
+pub struct RebindCapture(pub Option<BindingAction>);
+
+pub fn key_name(key: KeyCode) -> String {
+    format!("{key:?}")
+}
+
+pub fn reset_pressed(input: Res<Input<KeyCode>>, bindings: Res<Bindings>) -> bool {
+    input.just_pressed(bindings.reset)
+}
+
+fn cancel_capture(mut capture: ResMut<RebindCapture>) {
+    capture.0 = None;
+}
+
+fn learn_layout(mut events: EventReader<KeyboardInput>, mut layout: ResMut<KeyboardLayout>) {
+    for event in events.read() {
+        let key = if let Some(key) = event.key_code { key } else { continue; };
+        // only written when it's news, since the controls screen redraws on every change
+        if layout.0.get(&event.scan_code) != Some(&key) {
+            layout.0.insert(event.scan_code, key);
+        }
+    }
+}
+
+fn capture_binding(input: Res<Input<KeyCode>>, scans: Res<Input<ScanCode>>, mut capture: ResMut<RebindCapture>, mut bindings: ResMut<Bindings>) {
+    let action = if let Some(action) = capture.0 { action } else { return; };
+    let key = if let Some(key) = input.get_just_pressed().next() { *key } else { return; };
+    // movement keeps the key's position, whatever it types
+    let key = if action.is_movement() { scans.get_just_pressed().find_map(|scan| position_key(*scan)).unwrap_or(key) } else { key };
+
+    bindings.rebind(action, key);
+    capture.0 = None;
+}
@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use bevy::{prelude::*, input::InputSystem};
+
+use crate::theme::Theme;
+
+const MAX_LINES: usize = 14;
+const MAX_INPUT_LENGTH: usize = 80;
+
+// runs a command against the whole game, given whatever words followed its name; the text that comes back is printed either way
+pub type ConsoleHandler = fn(&mut World, &[&str]) -> Result<String, String>;
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Console>()
+        .init_resource::<ConsoleCommands>()
+        .register_console_command("help", "help - list every command", help)
+        .register_console_command("clear", "clear - empty the console", clear)
+        .add_systems(Startup, spawn_console)
+        // straight after the input is read, so nothing else sees keys typed into the console
+        .add_systems(PreUpdate, type_into_console.after(InputSystem))
+        .add_systems(Update, (run_console_commands, update_console).chain());
+    }
+}
+
+pub trait ConsoleAppExt {
+    // `name` can be more than one word, and the longest name that starts a line is the one that runs
+    fn register_console_command(&mut self, name: &str, usage: &str, handler: ConsoleHandler) -> &mut Self;
+}
+
+impl ConsoleAppExt for App {
+    fn register_console_command(&mut self, name: &str, usage: &str, handler: ConsoleHandler) -> &mut Self {
+        self.init_resource::<ConsoleCommands>();
+        self.world.resource_mut::<ConsoleCommands>().0.insert(name.to_string(), (usage.to_string(), handler));
+        self
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ConsoleCommands(BTreeMap<String, (String, ConsoleHandler)>);
+
+impl ConsoleCommands {
+    fn find<'a>(&self, words: &'a [&'a str]) -> Option<(ConsoleHandler, &'a [&'a str])> {
+        (1..=words.len()).rev().find_map(|length| {
+            self.0.get(&words[..length].join(" ")).map(|(_, handler)| (*handler, &words[length..]))
+        })
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Console {
+    pub open: bool,
+    input: String,
+    lines: Vec<String>,
+    // entered but not run yet, since running needs the whole world
+    pending: Vec<String>,
+}
+
+impl Console {
+    pub fn print(&mut self, line: impl Into<String>) {
+        self.lines.push(line.into());
+        let overflow = self.lines.len().saturating_sub(MAX_LINES);
+        self.lines.drain(..overflow);
+    }
+}
+
+#[derive(Component)]
+struct ConsoleWindow;
+
+#[derive(Component)]
+struct ConsoleText;
+
+fn spawn_console(mut commands: Commands) {
+    commands.spawn((
+        ConsoleWindow,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(40.),
+                padding: UiRect::all(Val::Px(16.)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::FlexEnd,
+                ..default()
+            },
+            background_color: Color::rgba(0., 0., 0., 0.85).into(),
+            visibility: Visibility::Hidden,
+            // over the menus and the hud alike
+            z_index: ZIndex::Global(100),
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn((
+            ConsoleText,
+            TextBundle::from_section("", TextStyle {
+                font_size: 24.,
+                ..default()
+            }),
+        ));
+    });
+}
+
+// ` opens and closes it, the same key as ~ on most layouts
+fn type_into_console(mut console: ResMut<Console>, mut input: ResMut<Input<KeyCode>>, mut scans: ResMut<Input<ScanCode>>, mut characters: EventReader<ReceivedCharacter>) {
+    if input.just_pressed(KeyCode::Grave) {
+        console.open = !console.open;
+        input.reset(KeyCode::Grave);
+        characters.clear();
+        return;
+    }
+    if !console.open {
+        return;
+    }
+
+    for character in characters.read() {
+        if !character.char.is_control() && !matches!(character.char, '`' | '~') && console.input.len() < MAX_INPUT_LENGTH {
+            console.input.push(character.char);
+        }
+    }
+    if input.just_pressed(KeyCode::Back) {
+        console.input.pop();
+    }
+    if input.just_pressed(KeyCode::Return) {
+        let line = std::mem::take(&mut console.input);
+        if !line.trim().is_empty() {
+            console.pending.push(line);
+        }
+    }
+
+    input.reset_all();
+    // the paddles read movement keys by scan code
+    scans.reset_all();
+}
+
+fn run_console_commands(world: &mut World) {
+    let pending = std::mem::take(&mut world.resource_mut::<Console>().pending);
+    for line in pending {
+        world.resource_mut::<Console>().print(format!("> {line}"));
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let found = world.resource::<ConsoleCommands>().find(&words);
+        let output = match found {
+            Some((handler, arguments)) => handler(world, arguments).unwrap_or_else(|error| format!("error: {error}")),
+            None => format!("unknown command {}, try help", words[0]),
+        };
+        for output_line in output.lines() {
+            world.resource_mut::<Console>().print(output_line);
+        }
+    }
+}
+
+fn update_console(console: Res<Console>, theme: Res<Theme>, mut windows: Query<&mut Visibility, With<ConsoleWindow>>, mut texts: Query<&mut Text, With<ConsoleText>>) {
+    if !console.is_changed() {
+        return;
+    }
+
+    for mut visibility in windows.iter_mut() {
+        *visibility = if console.open { Visibility::Visible } else { Visibility::Hidden };
+    }
+    for mut text in texts.iter_mut() {
+        let mut lines = console.lines.clone();
+        lines.push(format!("> {}_", console.input));
+        text.sections[0].value = lines.join("\n");
+        text.sections[0].style.color = theme.palette().text;
+    }
+}
+
+fn help(world: &mut World, _: &[&str]) -> Result<String, String> {
+    Ok(world.resource::<ConsoleCommands>().0.values().map(|(usage, _)| usage.as_str()).collect::<Vec<_>>().join("\n"))
+}
+
+fn clear(world: &mut World, _: &[&str]) -> Result<String, String> {
+    world.resource_mut::<Console>().lines.clear();
+    Ok(String::new())
+}
+
+// for commands to read their arguments with
+pub fn argument<T: std::str::FromStr>(arguments: &[&str], index: usize, name: &str) -> Result<T, String> {
+    let word = arguments.get(index).ok_or_else(|| format!("missing {name}"))?;
+    word.parse().map_err(|_| format!("{word} isn't a valid {name}"))
+}
@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{collections::VecDeque, fs::{self, File}, io::{self, Write}, process::Command, sync::Mutex, time::{SystemTime, UNIX_EPOCH}};
+
+use bevy::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GameState, menu::MenuScreen, storage};
+
+const PENDING_PATH: &str = "crash.ron";
+#[cfg(not(target_arch = "wasm32"))]
+const CRASHES_DIR: &str = "crashes";
+#[cfg(not(target_arch = "wasm32"))]
+const LOG_PATH: &str = "logs/pong.log";
+// the current log and the runs before it, oldest dropped first
+#[cfg(not(target_arch = "wasm32"))]
+const LOG_FILES: usize = 5;
+// a log this big starts over in a fresh file, so a long session can't fill the disk
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_LOG_BYTES: u64 = 4 * 1024 * 1024;
+// how much of the log goes into a crash report
+#[cfg(not(target_arch = "wasm32"))]
+const RECENT_LINES: usize = 200;
+// the same as bevy's own logging
+#[cfg(not(target_arch = "wasm32"))]
+const LOG_FILTER: &str = "info,wgpu=error,naga=warn";
+
+// the tail of the log, kept where the panic hook can get at it
+#[cfg(not(target_arch = "wasm32"))]
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+// natively this takes the place of bevy's LogPlugin, so everything logged goes to a file as well as the terminal; the browser keeps bevy's
+pub struct CrashPlugin;
+
+impl Plugin for CrashPlugin {
+    fn build(&self, app: &mut App) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            install_logging();
+            install_panic_hook();
+        }
+
+        // shown the once, whatever the player does about it
+        let pending = storage::load::<PendingCrash>(PENDING_PATH);
+        if pending.report.is_some() {
+            storage::save(PENDING_PATH, &PendingCrash::default());
+        }
+        app
+        .insert_resource(LastCrash(pending.report))
+        .add_systems(OnEnter(GameState::Menu), show_crash_dialog.after(crate::menu::show_main_screen).run_if(crashed))
+        .add_systems(Update, crash_actions.run_if(in_state(GameState::Menu)));
+    }
+}
+
+// where the panic hook leaves word of a report, for the next run to pick up
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct PendingCrash {
+    report: Option<PathBuf>,
+}
+
+// the report from a run that crashed, if the one before this did
+#[derive(Resource, Default)]
+pub struct LastCrash(pub Option<PathBuf>);
+
+#[derive(Component, Clone, Copy)]
+pub enum CrashButton {
+    Open,
+    Dismiss,
+}
+
+pub fn crash_summary(crash: &LastCrash) -> String {
+    let report = crash.0.as_ref().map_or(String::new(), |report| report.display().to_string());
+    format!("Pong closed unexpectedly last time.\nA report of what went wrong was saved to\n{report}")
+}
+
+fn crashed(crash: Res<LastCrash>) -> bool {
+    crash.0.is_some()
+}
+
+// only ahead of the first menu of the run
+fn show_crash_dialog(mut screen: ResMut<MenuScreen>, mut shown: Local<bool>) {
+    if !*shown {
+        *screen = MenuScreen::Crashed;
+    }
+    *shown = true;
+}
+
+fn crash_actions(query: Query<(&Interaction, &CrashButton), Changed<Interaction>>, crash: Res<LastCrash>, mut screen: ResMut<MenuScreen>) {
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            CrashButton::Open => {
+                if let Some(report) = &crash.0 {
+                    open_report(report);
+                }
+            }
+            CrashButton::Dismiss => *screen = MenuScreen::Main,
+        }
+    }
+}
+
+// with whatever the platform opens text files in
+#[cfg(not(target_arch = "wasm32"))]
+fn open_report(report: &Path) {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut start = Command::new("cmd");
+        start.args(["/C", "start", ""]);
+        start
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+    if let Err(error) = command.arg(report).spawn() {
+        warn!("couldn't open {}: {error}", report.display());
+    }
+}
+
+// there's never a report to open in a browser
+#[cfg(target_arch = "wasm32")]
+fn open_report(_report: &Path) {}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn install_logging() {
+    use tracing_subscriber::{EnvFilter, fmt, prelude::*, util::SubscriberInitExt};
+
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(LOG_FILTER));
+    let terminal = fmt::layer().with_writer(io::stderr);
+    let file = fmt::layer().with_ansi(false).with_writer(Mutex::new(LogFile::open()));
+    if tracing_subscriber::registry().with(filter).with(terminal).with(file).try_init().is_err() {
+        warn!("couldn't set up logging as something else already has");
+    }
+}
+
+// the log in the data directory, moved aside each run and whenever it grows too big
+#[cfg(not(target_arch = "wasm32"))]
+struct LogFile {
+    file: Option<File>,
+    written: u64,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl LogFile {
+    fn open() -> Self {
+        let path = storage::path(LOG_PATH);
+        for index in (1..LOG_FILES).rev() {
+            let _ = fs::rename(rotated_path(&path, index - 1), rotated_path(&path, index));
+        }
+        // without a file the log still reaches the terminal and the crash reports
+        let file = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| File::create(&path)).ok();
+        Self { file, written: 0 }
+    }
+}
+
+// pong.log, then pong.1.log, pong.2.log and so on back
+#[cfg(not(target_arch = "wasm32"))]
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().map_or(String::new(), |stem| stem.to_string_lossy().into_owned());
+    path.with_file_name(format!("{stem}.{index}.log"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Write for LogFile {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        let mut recent = RECENT.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for line in String::from_utf8_lossy(bytes).lines() {
+            if recent.len() == RECENT_LINES {
+                recent.pop_front();
+            }
+            recent.push_back(line.to_string());
+        }
+        drop(recent);
+
+        if self.written > MAX_LOG_BYTES {
+            *self = Self::open();
+        }
+        self.written += bytes.len() as u64;
+        self.file.as_mut().map_or(Ok(()), |file| file.write_all(bytes)).map(|_| bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.as_mut().map_or(Ok(()), |file| file.flush())
+    }
+}
+
+// written before the usual message, so the report's there however the rest of the panic goes
+#[cfg(not(target_arch = "wasm32"))]
+fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info.payload().downcast_ref::<&str>().map(|message| message.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "no message".to_string());
+        let location = info.location().map_or(String::new(), |location| format!(" at {location}"));
+        let thread = std::thread::current().name().unwrap_or("unnamed").to_string();
+        let report = format!("{message}{location}, on the {thread} thread");
+        match write_report(&report) {
+            Ok(path) => eprintln!("crash report saved to {}", path.display()),
+            Err(error) => eprintln!("couldn't save a crash report: {error}"),
+        }
+        previous(info);
+    }));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_report(panic: &str) -> io::Result<PathBuf> {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    // whatever was holding the log when it panicked might never let go of it
+    let recent = RECENT.try_lock().map_or_else(|_| "(unavailable)".to_string(), |recent| recent.iter().cloned().collect::<Vec<_>>().join("\n"));
+    // the settings are saved the moment they change, so the file is what the game was running with
+    let settings = fs::read_to_string(storage::path(crate::settings::CONFIG_PATH)).unwrap_or_else(|_| "(defaults, never saved)".to_string());
+    let contents = format!(
+        "Pong {} crashed\n\n{panic}\n\nBacktrace:\n{}\n\nRecent log:\n{recent}\n\nSettings:\n{settings}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::backtrace::Backtrace::force_capture(),
+    );
+
+    let path = storage::path(CRASHES_DIR).join(format!("crash-{seconds}.txt"));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, contents)?;
+    // straight to the file rather than through storage, which would log any trouble to the log that may be what broke
+    let pending = ron::to_string(&PendingCrash { report: Some(path.clone()) }).map_err(io::Error::other)?;
+    fs::write(storage::path(PENDING_PATH), pending)?;
+    Ok(path)
+}
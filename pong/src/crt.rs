@@ -0,0 +1,118 @@
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{AsBindGroup, Extent3d, ShaderRef, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+    sprite::{Material2d, Material2dPlugin, MaterialMesh2dBundle},
+    window::{PrimaryWindow, WindowRef, WindowResized},
+};
+
+use crate::{settings::Settings, ui::MainCamera};
+
+// the screen quad lives on its own layer so the main camera never draws it
+const CRT_LAYER: u8 = 1;
+
+pub struct CrtPlugin;
+
+impl Plugin for CrtPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_plugins(Material2dPlugin::<CrtMaterial>::default())
+        .add_systems(Startup, spawn_crt_screen)
+        .add_systems(Update, apply_crt_setting.run_if(resource_changed::<Settings>().or_else(on_event::<WindowResized>())));
+    }
+}
+
+// draws the main camera's output, ui included, through the crt shader
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub struct CrtMaterial {
+    #[texture(0)]
+    #[sampler(1)]
+    pub screen: Handle<Image>,
+}
+
+impl Material2d for CrtMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/crt.wgsl".into()
+    }
+}
+
+#[derive(Resource)]
+pub struct CrtScreen(Handle<Image>);
+
+#[derive(Component)]
+pub struct CrtCamera;
+
+#[derive(Component)]
+pub struct CrtQuad;
+
+fn spawn_crt_screen(mut commands: Commands, mut images: ResMut<Assets<Image>>, mut meshes: ResMut<Assets<Mesh>>, mut materials: ResMut<Assets<CrtMaterial>>) {
+    let size = Extent3d { width: 1, height: 1, ..default() };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    let screen = images.add(image);
+
+    commands.spawn((
+        CrtCamera,
+        Camera2dBundle {
+            camera: Camera {
+                order: 1,
+                is_active: false,
+                ..default()
+            },
+            ..default()
+        },
+        UiCameraConfig { show_ui: false },
+        RenderLayers::layer(CRT_LAYER),
+    ));
+
+    commands.spawn((
+        CrtQuad,
+        MaterialMesh2dBundle {
+            mesh: meshes.add(shape::Quad::new(Vec2::ONE).into()).into(),
+            material: materials.add(CrtMaterial { screen: screen.clone() }),
+            ..default()
+        },
+        RenderLayers::layer(CRT_LAYER),
+    ));
+
+    commands.insert_resource(CrtScreen(screen));
+}
+
+fn apply_crt_setting(settings: Res<Settings>, screen: Res<CrtScreen>, mut images: ResMut<Assets<Image>>, windows: Query<&Window, With<PrimaryWindow>>, mut main_cameras: Query<&mut Camera, (With<MainCamera>, Without<CrtCamera>)>, mut crt_cameras: Query<&mut Camera, With<CrtCamera>>, mut quads: Query<&mut Transform, With<CrtQuad>>) {
+    let window = if let Ok(window) = windows.get_single() { window } else { return; };
+
+    if settings.crt {
+        if let Some(image) = images.get_mut(&screen.0) {
+            image.resize(Extent3d {
+                width: window.physical_width().max(1),
+                height: window.physical_height().max(1),
+                ..default()
+            });
+        }
+        for mut transform in quads.iter_mut() {
+            transform.scale = Vec3::new(window.width(), window.height(), 1.);
+        }
+    }
+
+    for mut camera in main_cameras.iter_mut() {
+        camera.target = if settings.crt { RenderTarget::Image(screen.0.clone()) } else { RenderTarget::Window(WindowRef::Primary) };
+    }
+    for mut camera in crt_cameras.iter_mut() {
+        camera.is_active = settings.crt;
+    }
+}
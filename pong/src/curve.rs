@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+
+use crate::{GameSet, MatchRules, Velocity, ball::{Ball, BallCollided, MAX_BOUNCE_ANGLE, ball_collision}};
+
+// how fast a paddle moving at one unit per second turns the ball, in radians per second
+const CURVE_TRANSFER: f32 = 0.0015;
+// the fraction of its spin a ball keeps after a second
+const SPIN_RETENTION: f32 = 0.3;
+
+pub struct CurvePlugin;
+
+impl Plugin for CurvePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(FixedUpdate, (spin_from_paddles, curve_balls).chain().before(ball_collision).run_if(curve_ball_enabled).in_set(GameSet::Physics));
+    }
+}
+
+// radians per second the ball's path turns, counterclockwise
+#[derive(Component)]
+pub struct Spin(f32);
+
+fn curve_ball_enabled(rules: Res<MatchRules>) -> bool {
+    rules.curve_ball
+}
+
+// the ball leaves with the paddle's drag and then bends back against it
+fn spin_from_paddles(mut commands: Commands, mut events: EventReader<BallCollided>, balls: Query<&Velocity, With<Ball>>, paddles: Query<&Velocity, Without<Ball>>) {
+    for event in events.read().filter(|event| event.is_paddle_hit()) {
+        let (ball, paddle) = if let (Ok(ball), Ok(paddle)) = (balls.get(event.ball), paddles.get(event.other)) { (ball, paddle) } else { continue; };
+        commands.entity(event.ball).insert(Spin(-paddle.0.y * ball.0.x.signum() * CURVE_TRANSFER));
+    }
+}
+
+fn curve_balls(mut balls: Query<(&mut Velocity, &mut Spin), With<Ball>>, time: Res<Time>) {
+    for (mut velocity, mut spin) in balls.iter_mut() {
+        let curved = Vec2::from_angle(spin.0 * time.delta_seconds()).rotate(velocity.0);
+
+        // never bent so steep it would bounce between the walls without reaching a paddle
+        let angle = curved.y.atan2(curved.x.abs());
+        if angle.abs() > MAX_BOUNCE_ANGLE {
+            spin.0 = 0.;
+            continue;
+        }
+
+        velocity.0 = curved;
+        spin.0 *= SPIN_RETENTION.powf(time.delta_seconds());
+    }
+}
@@ -0,0 +1,175 @@
+use bevy::prelude::*;
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GameMode, GameState, Handicap, MatchRules, ai::{AiPersonality, Difficulty}, gameplay::{GameplayBase, GameplayOverride, GameplayRuntimeLayer}, menu::MenuScreen, rng::GameRng, score::Scoreboard, storage};
+
+const DAILY_PATH: &str = "daily.ron";
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+// kept apart from the day itself, so the serves don't line up with a `--seed` someone typed in
+const SEED_SALT: u64 = 0x5eed_da11;
+const SPEED_CURVES: [(&str, f32); 4] = [("Gentle", 0.5), ("Steady", 1.), ("Steep", 1.6), ("Frantic", 2.2)];
+const PADDLE_SIZES: [(&str, f32); 3] = [("Small", 0.75), ("Regular", 1.), ("Large", 1.25)];
+
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<DailyChallenge>()
+        .insert_resource(storage::load::<DailyRecord>(DAILY_PATH))
+        .add_systems(Update, daily_actions.run_if(in_state(GameState::Menu)))
+        .add_systems(OnEnter(GameState::GameOver), record_result.run_if(daily_active))
+        .add_systems(OnEnter(GameState::Menu), end_challenge.run_if(daily_active))
+        .add_systems(Update, save_record.run_if(resource_changed::<DailyRecord>()).run_if(not(resource_added::<DailyRecord>())));
+    }
+}
+
+// whole days since the unix epoch, in utc so everyone gets the same challenge at the same moment
+#[cfg(not(target_arch = "wasm32"))]
+pub fn today() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs() / SECONDS_PER_DAY)
+}
+
+// the browser has no system clock to ask, only its own
+#[cfg(target_arch = "wasm32")]
+pub fn today() -> u64 {
+    (js_sys::Date::now() / 1000.) as u64 / SECONDS_PER_DAY
+}
+
+// year-month-day from days since the epoch, after howard hinnant's `civil_from_days`
+pub fn date_label(day: u64) -> String {
+    let shifted = day as i64 + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day_of_month:02}")
+}
+
+// everything about a day's match that comes from the date, the same for everyone playing it
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DailyTwists {
+    pub seed: u64,
+    pub speed_curve: (&'static str, f32),
+    pub paddle_size: (&'static str, f32),
+    pub personality: AiPersonality,
+}
+
+impl DailyTwists {
+    pub fn for_day(day: u64) -> Self {
+        let seed = day ^ SEED_SALT;
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self {
+            seed,
+            speed_curve: SPEED_CURVES[rng.gen_range(0..SPEED_CURVES.len())],
+            paddle_size: PADDLE_SIZES[rng.gen_range(0..PADDLE_SIZES.len())],
+            personality: AiPersonality::ALL[rng.gen_range(0..AiPersonality::ALL.len())],
+        }
+    }
+}
+
+// what the player had chosen before the challenge took over, put back once they're on the menu again
+#[derive(Clone)]
+struct Overridden {
+    rules: MatchRules,
+    difficulty: Difficulty,
+    personality: AiPersonality,
+    seed: Option<u64>,
+}
+
+#[derive(Resource, Default)]
+pub struct DailyChallenge {
+    overridden: Option<Overridden>,
+}
+
+impl DailyChallenge {
+    pub fn is_active(&self) -> bool {
+        self.overridden.is_some()
+    }
+}
+
+pub fn daily_active(daily: Res<DailyChallenge>) -> bool {
+    daily.is_active()
+}
+
+// one attempt a day: the day is written down the moment the match starts, so quitting out of a bad one doesn't earn another go
+#[derive(Resource, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DailyRecord {
+    pub day: Option<u64>,
+    // the player's points then the computer's, for a match that got as far as a result
+    pub result: Option<(u32, u32)>,
+}
+
+impl DailyRecord {
+    pub fn played_today(&self) -> bool {
+        self.day == Some(today())
+    }
+}
+
+#[derive(Component)]
+pub struct DailyButton;
+
+pub fn daily_label(record: &DailyRecord) -> String {
+    let date = date_label(today());
+    match (record.played_today(), record.result) {
+        (false, _) => format!("Daily {date}"),
+        (true, Some((player, computer))) => format!("Daily {date}: {} {player} - {computer}", if player > computer { "Won" } else { "Lost" }),
+        (true, None) => format!("Daily {date}: Abandoned"),
+    }
+}
+
+fn daily_actions(query: Query<&Interaction, (Changed<Interaction>, With<DailyButton>)>, mut daily: ResMut<DailyChallenge>, mut record: ResMut<DailyRecord>, mut mode: ResMut<GameMode>, (mut rules, mut difficulty, mut personality, mut rng): (ResMut<MatchRules>, ResMut<Difficulty>, ResMut<AiPersonality>, ResMut<GameRng>), (base, mut runtime): (Res<GameplayBase>, ResMut<GameplayRuntimeLayer>), mut next_state: ResMut<NextState<GameState>>) {
+    for interaction in query.iter() {
+        if *interaction != Interaction::Pressed || record.played_today() {
+            continue;
+        }
+
+        let day = today();
+        let twists = DailyTwists::for_day(day);
+        daily.overridden = Some(Overridden {
+            rules: rules.clone(),
+            difficulty: *difficulty,
+            personality: *personality,
+            seed: rng.seed,
+        });
+        info!("daily challenge {}: {} ball, {} paddles, {} opponent", date_label(day), twists.speed_curve.0, twists.paddle_size.0, twists.personality.label());
+
+        let size = Handicap { size: twists.paddle_size.1, ..default() };
+        *rules = MatchRules { player_handicap: size, opponent_handicap: size, ..default() };
+        *difficulty = Difficulty::Normal;
+        *personality = twists.personality;
+        rng.seed = Some(twists.seed);
+        runtime.0 = Some(GameplayOverride { ball_speed_increment: Some(base.0.ball_speed_increment * twists.speed_curve.1), ..default() });
+        *record = DailyRecord { day: Some(day), result: None };
+
+        *mode = GameMode::VersusComputer;
+        next_state.set(GameState::Playing);
+    }
+}
+
+fn record_result(scoreboard: Res<Scoreboard>, mut record: ResMut<DailyRecord>) {
+    record.result = Some((scoreboard.player, scoreboard.computer));
+}
+
+fn end_challenge(mut daily: ResMut<DailyChallenge>, mut rules: ResMut<MatchRules>, mut difficulty: ResMut<Difficulty>, mut personality: ResMut<AiPersonality>, mut rng: ResMut<GameRng>, mut runtime: ResMut<GameplayRuntimeLayer>, mut screen: ResMut<MenuScreen>) {
+    let overridden = if let Some(overridden) = daily.overridden.take() { overridden } else { return; };
+    *rules = overridden.rules;
+    *difficulty = overridden.difficulty;
+    *personality = overridden.personality;
+    rng.seed = overridden.seed;
+    runtime.0 = None;
+    // the daily button has a result to show now
+    screen.set_changed();
+}
+
+fn save_record(record: Res<DailyRecord>) {
+    storage::save(DAILY_PATH, &*record);
+}
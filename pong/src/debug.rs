@@ -0,0 +1,95 @@
+use bevy::{prelude::*, diagnostic::{DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin}};
+
+use crate::{Collider, Velocity, ai::AiPerception, ball::Ball, console::ConsoleAppExt};
+
+const COLLIDER_COLOR: Color = Color::rgb(0.2, 1., 0.4);
+const VELOCITY_COLOR: Color = Color::rgb(1., 0.3, 0.3);
+const TARGET_COLOR: Color = Color::rgb(1., 0.85, 0.2);
+// the velocity line shows where the ball will be this far ahead
+const VELOCITY_SECONDS: f32 = 0.25;
+const TARGET_WIDTH: f32 = 40.;
+
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        if !app.is_plugin_added::<EntityCountDiagnosticsPlugin>() {
+            app.add_plugins(EntityCountDiagnosticsPlugin);
+        }
+
+        app
+        .init_resource::<DebugOverlay>()
+        .register_console_command("debug.overlay", "debug.overlay - colliders, ball velocity, ai targets and fps", toggle_overlay)
+        .add_systems(Update, (draw_colliders, draw_ball_velocity, draw_ai_targets, update_debug_text).run_if(resource_equals(DebugOverlay(true))))
+        .add_systems(Update, show_debug_text.run_if(resource_changed::<DebugOverlay>()));
+    }
+}
+
+#[derive(Resource, Default, PartialEq, Eq)]
+pub struct DebugOverlay(pub bool);
+
+#[derive(Component)]
+struct DebugText;
+
+fn toggle_overlay(world: &mut World, _: &[&str]) -> Result<String, String> {
+    let mut overlay = world.resource_mut::<DebugOverlay>();
+    overlay.0 = !overlay.0;
+    Ok(format!("debug overlay {}", if overlay.0 { "on" } else { "off" }))
+}
+
+fn draw_colliders(mut gizmos: Gizmos, colliders: Query<(&Transform, &Collider)>) {
+    for (transform, collider) in colliders.iter() {
+        gizmos.rect_2d(transform.translation.truncate(), 0., collider.0, COLLIDER_COLOR);
+    }
+}
+
+fn draw_ball_velocity(mut gizmos: Gizmos, balls: Query<(&Transform, &Velocity), With<Ball>>) {
+    for (transform, velocity) in balls.iter() {
+        let position = transform.translation.truncate();
+        gizmos.line_2d(position, position + velocity.0 * VELOCITY_SECONDS, VELOCITY_COLOR);
+    }
+}
+
+fn draw_ai_targets(mut gizmos: Gizmos, ai: Query<(&Transform, &AiPerception)>) {
+    for (transform, perception) in ai.iter() {
+        let target = Vec2::new(transform.translation.x, perception.target);
+        gizmos.line_2d(target - Vec2::X * TARGET_WIDTH / 2., target + Vec2::X * TARGET_WIDTH / 2., TARGET_COLOR);
+        gizmos.circle_2d(target, 4., TARGET_COLOR);
+    }
+}
+
+fn show_debug_text(mut commands: Commands, overlay: Res<DebugOverlay>, texts: Query<Entity, With<DebugText>>) {
+    for entity in texts.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if !overlay.0 {
+        return;
+    }
+
+    commands.spawn((
+        DebugText,
+        TextBundle::from_section("", TextStyle {
+            font_size: 24.,
+            color: COLLIDER_COLOR,
+            ..default()
+        }).with_style(Style {
+            // out of the way of the console, which drops down from the top
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.),
+            right: Val::Px(8.),
+            ..default()
+        }),
+        ZIndex::Global(100),
+    ));
+}
+
+fn update_debug_text(diagnostics: Res<DiagnosticsStore>, mut texts: Query<&mut Text, With<DebugText>>) {
+    let fps = diagnostics.get(FrameTimeDiagnosticsPlugin::FPS).and_then(|fps| fps.smoothed()).unwrap_or(0.);
+    let entities = diagnostics.get(EntityCountDiagnosticsPlugin::ENTITY_COUNT).and_then(|count| count.value()).unwrap_or(0.);
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = format!("{fps:.0} fps\n{entities:.0} entities");
+    }
+}
@@ -0,0 +1,329 @@
+use bevy::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ArenaConfig, GameMode, GameSet, GameState, MatchRules, ResetRequested, ai::{AiPersonality, Difficulty}, console::{ConsoleAppExt, argument}, gameplay::{GameplayConfig, GameplayOverride, GameplayRuntimeLayer}, mouse::mouse_paddle, mutators::{Mutator, Mutators}, paddle::{HumanControlled, PaddleMotion, Player}, replay::ReplayState, rng::{GameRng, restart_rng}, score::Scoreboard, settings::Settings, storage, suspend::Resuming, touch::touch_paddle};
+
+const MAGIC: &[u8] = b"PONGDEMO";
+const VERSION: u8 = 2;
+// the last match played is always kept, `demo save` copies it somewhere it won't be overwritten
+const LAST_DEMO: &str = "demos/last.demo";
+
+// a match kept as its seed and the inputs from every tick, doom demo style, and played back by running the match again on them;
+// nothing to do with `GameMode::Demo`, which is the computer playing itself on the title screen
+pub struct DemoPlugin;
+
+impl Plugin for DemoPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<DemoRecorder>()
+        .init_resource::<DemoPlayer>()
+        .register_console_command("demo save", "demo save <name> - keep the last match as demos/<name>.demo", save_demo_command)
+        .register_console_command("demo play", "demo play <file> - play a demo back, from the menu", play_demo_command)
+        .add_systems(Startup, play_demo_argument)
+        .add_systems(OnExit(GameState::Menu), start_recording.after(restart_rng))
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, start_recording.after(restart_rng))
+        .add_systems(OnEnter(GameState::GameOver), (finish_recording, check_playback))
+        .add_systems(OnEnter(GameState::Menu), end_playback.run_if(demo_playing))
+        // after every controller has had its say, so what's recorded and played back is what the paddles actually went on
+        .add_systems(FixedUpdate, (
+            record_tick.run_if(recording),
+            play_tick.run_if(demo_playing),
+        ).after(mouse_paddle).after(touch_paddle).run_if(in_state(ReplayState::Off)).in_set(GameSet::Input));
+    }
+}
+
+// everything a match plays out from besides the inputs
+#[derive(Clone, Serialize, Deserialize, Debug)]
+struct DemoHeader {
+    seed: u64,
+    mode: GameMode,
+    difficulty: Difficulty,
+    personality: AiPersonality,
+    rules: MatchRules,
+    arena: ArenaConfig,
+    gameplay: GameplayConfig,
+    left_handed: bool,
+    mutators: Vec<Mutator>,
+    // the human paddles each tick has an input for, the player's first
+    paddles: u8,
+    // the ticks the match was reset on
+    resets: Vec<u32>,
+    // the score it finished on, to tell whether playing it back kept in step
+    result: (u32, u32),
+}
+
+// a byte per paddle each tick, ticks being the same length wherever it's played
+#[derive(Clone, Debug)]
+struct Demo {
+    header: DemoHeader,
+    inputs: Vec<u8>,
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &mut impl Iterator<Item = u8>) -> Option<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = bytes.next()?;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+impl Demo {
+    fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let header = ron::to_string(&self.header).map_err(|error| error.to_string())?;
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        write_varint(&mut bytes, header.len() as u64);
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(&self.inputs);
+        Ok(bytes)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let rest = bytes.strip_prefix(MAGIC).ok_or("not a pong demo")?;
+        let (version, rest) = rest.split_first().ok_or("the demo is cut short")?;
+        if *version != VERSION {
+            return Err(format!("demo version {version} isn't one this game can play"));
+        }
+
+        let mut iter = rest.iter().copied();
+        let length = read_varint(&mut iter).ok_or("the demo is cut short")? as usize;
+        let rest = &rest[rest.len() - iter.len()..];
+        let header = rest.get(..length).ok_or("the demo is cut short")?;
+        let header: DemoHeader = ron::de::from_bytes(header).map_err(|error| format!("unreadable demo header: {error}"))?;
+        let inputs = rest[length..].to_vec();
+        if header.paddles == 0 || inputs.len() % header.paddles as usize != 0 {
+            return Err("the demo is cut short".to_string());
+        }
+        Ok(Self { header, inputs })
+    }
+
+    fn ticks(&self) -> usize {
+        self.inputs.len() / self.header.paddles as usize
+    }
+}
+
+#[derive(Resource, Default)]
+struct DemoRecorder {
+    // none when this match isn't one that can be recorded
+    demo: Option<Demo>,
+    // the last finished match, for `demo save`
+    last: Option<Demo>,
+}
+
+// what the player had set before a demo took over, put back once they're on the menu again
+struct Overridden {
+    difficulty: Difficulty,
+    personality: AiPersonality,
+    rules: MatchRules,
+    arena: ArenaConfig,
+    gameplay: Option<GameplayOverride>,
+    left_handed: bool,
+    mutators: Vec<Mutator>,
+    seed: Option<u64>,
+}
+
+#[derive(Resource, Default)]
+pub struct DemoPlayer {
+    demo: Option<Demo>,
+    next: usize,
+    // whether the tick being played reset the match
+    reset: bool,
+    expected: (u32, u32),
+    overridden: Option<Overridden>,
+}
+
+impl DemoPlayer {
+    pub fn is_playing(&self) -> bool {
+        self.overridden.is_some()
+    }
+}
+
+pub fn demo_playing(player: Res<DemoPlayer>) -> bool {
+    player.is_playing()
+}
+
+// stands in for the reset key while a demo plays, which is the only time the key itself is ignored
+pub fn demo_reset(player: Res<DemoPlayer>) -> bool {
+    player.is_playing() && player.reset
+}
+
+fn recording(recorder: Res<DemoRecorder>) -> bool {
+    recorder.demo.is_some()
+}
+
+// the player's paddle first, so the inputs line up with the same paddles when played back
+fn human_paddles<'a>(paddles: impl Iterator<Item = (Mut<'a, PaddleMotion>, bool)>) -> Vec<Mut<'a, PaddleMotion>> {
+    let mut paddles: Vec<_> = paddles.collect();
+    paddles.sort_by_key(|(_, player)| !player);
+    paddles.into_iter().map(|(motion, _)| motion).collect()
+}
+
+// online the other machine's inputs never pass through here, and the other modes don't have a match worth keeping
+fn start_recording(mut recorder: ResMut<DemoRecorder>, player: Res<DemoPlayer>, mode: Res<GameMode>, rng: Res<GameRng>, (difficulty, personality, rules, arena, gameplay): (Res<Difficulty>, Res<AiPersonality>, Res<MatchRules>, Res<ArenaConfig>, Res<GameplayConfig>), (settings, mutators): (Res<Settings>, Res<Mutators>), resuming: Option<Res<Resuming>>) {
+    // a resumed match starts from wherever it was left, which no seed can get back to
+    let recordable = matches!(*mode, GameMode::VersusComputer | GameMode::TwoPlayer | GameMode::Survival) && resuming.is_none();
+    recorder.demo = (recordable && !player.is_playing()).then(|| Demo {
+        header: DemoHeader {
+            seed: rng.match_seed,
+            mode: *mode,
+            difficulty: *difficulty,
+            personality: *personality,
+            rules: rules.clone(),
+            arena: *arena,
+            gameplay: gameplay.clone(),
+            left_handed: settings.left_handed,
+            mutators: mutators.enabled().to_vec(),
+            paddles: if *mode == GameMode::TwoPlayer { 2 } else { 1 },
+            resets: Vec::new(),
+            result: (0, 0),
+        },
+        inputs: Vec::new(),
+    });
+}
+
+// rounds each paddle's input to what the file can hold before it's used, so the match plays out on exactly what gets saved
+fn record_tick(mut recorder: ResMut<DemoRecorder>, reset: Res<ResetRequested>, mut paddles: Query<(&mut PaddleMotion, Has<Player>), With<HumanControlled>>) {
+    let demo = if let Some(demo) = recorder.demo.as_mut() { demo } else { return; };
+    if reset.0 {
+        let tick = demo.ticks() as u32;
+        demo.header.resets.push(tick);
+    }
+
+    let mut paddles = human_paddles(paddles.iter_mut());
+    for index in 0..demo.header.paddles as usize {
+        let input = paddles.get_mut(index).map_or(0, |motion| {
+            let input = motion.input();
+            motion.set_input(input);
+            input
+        });
+        demo.inputs.push(input as u8);
+    }
+}
+
+fn finish_recording(mut recorder: ResMut<DemoRecorder>, scoreboard: Res<Scoreboard>) {
+    let mut demo = if let Some(demo) = recorder.demo.take() { demo } else { return; };
+    demo.header.result = (scoreboard.player, scoreboard.computer);
+    match demo.to_bytes() {
+        Ok(bytes) => storage::save_bytes(LAST_DEMO, &bytes),
+        Err(error) => warn!("couldn't save the demo: {error}"),
+    }
+    recorder.last = Some(demo);
+}
+
+fn save_demo_command(world: &mut World, arguments: &[&str]) -> Result<String, String> {
+    let name = argument::<String>(arguments, 0, "name")?;
+    let demo = world.resource::<DemoRecorder>().last.as_ref().ok_or("no match has finished yet")?;
+    let path = format!("demos/{name}.demo");
+    storage::save_bytes(&path, &demo.to_bytes()?);
+    Ok(format!("saved {path}"))
+}
+
+fn play_demo_command(world: &mut World, arguments: &[&str]) -> Result<String, String> {
+    let file = argument::<String>(arguments, 0, "file")?;
+    if *world.resource::<State<GameState>>().get() != GameState::Menu {
+        return Err("demos can only be played from the menu".to_string());
+    }
+
+    start_playback(world, &file)?;
+    Ok(format!("playing {file}"))
+}
+
+// `--demo recording.demo` on the command line plays it straight away
+fn play_demo_argument(world: &mut World) {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg != "--demo" {
+            continue;
+        }
+        let file = if let Some(file) = args.next() { file } else { return; };
+        if let Err(error) = start_playback(world, &file) {
+            warn!("couldn't play {file}: {error}");
+        }
+        return;
+    }
+}
+
+fn start_playback(world: &mut World, file: &str) -> Result<(), String> {
+    let bytes = storage::load_bytes(file).or_else(|| storage::load_bytes(&format!("demos/{file}.demo"))).ok_or_else(|| format!("couldn't read {file}"))?;
+    let demo = Demo::from_bytes(&bytes)?;
+    if demo.ticks() == 0 {
+        return Err("the demo has no ticks".to_string());
+    }
+    let header = demo.header.clone();
+
+    let overridden = Overridden {
+        difficulty: std::mem::replace(&mut *world.resource_mut::<Difficulty>(), header.difficulty),
+        personality: std::mem::replace(&mut *world.resource_mut::<AiPersonality>(), header.personality),
+        rules: std::mem::replace(&mut *world.resource_mut::<MatchRules>(), header.rules),
+        arena: std::mem::replace(&mut *world.resource_mut::<ArenaConfig>(), header.arena),
+        // every number the match was played with, whatever the file and the layers under it say now
+        gameplay: world.resource_mut::<GameplayRuntimeLayer>().0.replace(GameplayOverride::all(&header.gameplay)),
+        left_handed: std::mem::replace(&mut world.resource_mut::<Settings>().left_handed, header.left_handed),
+        mutators: world.resource_mut::<Mutators>().replace_enabled(header.mutators),
+        seed: world.resource_mut::<GameRng>().seed.replace(header.seed),
+    };
+    *world.resource_mut::<DemoPlayer>() = DemoPlayer { demo: Some(demo), next: 0, reset: false, expected: header.result, overridden: Some(overridden) };
+    *world.resource_mut::<GameMode>() = header.mode;
+    world.resource_mut::<NextState<GameState>>().set(GameState::Playing);
+    Ok(())
+}
+
+fn play_tick(mut player: ResMut<DemoPlayer>, mut paddles: Query<(&mut PaddleMotion, Has<Player>), With<HumanControlled>>) {
+    let player = &mut *player;
+    let demo = if let Some(demo) = player.demo.as_ref() { demo } else { return; };
+    let paddles_per_tick = demo.header.paddles as usize;
+    let inputs = if let Some(inputs) = demo.inputs.get(player.next * paddles_per_tick..(player.next + 1) * paddles_per_tick) { inputs } else {
+        // the paddles are left to whoever's at the controls, and this is only said the once
+        if player.next == demo.ticks() {
+            warn!("the demo ran out before its match ended, so it's fallen out of step");
+            player.next += 1;
+        }
+        player.reset = false;
+        return;
+    };
+
+    for (mut motion, input) in human_paddles(paddles.iter_mut()).into_iter().zip(inputs.iter()) {
+        motion.set_input(*input as i8);
+    }
+    player.reset = demo.header.resets.binary_search(&(player.next as u32)).is_ok();
+    player.next += 1;
+}
+
+fn check_playback(player: Res<DemoPlayer>, scoreboard: Res<Scoreboard>) {
+    if !player.is_playing() {
+        return;
+    }
+
+    let (played, expected) = ((scoreboard.player, scoreboard.computer), player.expected);
+    if played == expected {
+        info!("demo finished {} - {}, as recorded", played.0, played.1);
+    } else {
+        warn!("demo finished {} - {} but was recorded ending {} - {}", played.0, played.1, expected.0, expected.1);
+    }
+}
+
+fn end_playback(mut player: ResMut<DemoPlayer>, (mut difficulty, mut personality, mut rules, mut arena, mut gameplay): (ResMut<Difficulty>, ResMut<AiPersonality>, ResMut<MatchRules>, ResMut<ArenaConfig>, ResMut<GameplayRuntimeLayer>), mut settings: ResMut<Settings>, mut mutators: ResMut<Mutators>, mut rng: ResMut<GameRng>) {
+    let overridden = if let Some(overridden) = player.overridden.take() { overridden } else { return; };
+    *player = DemoPlayer::default();
+    *difficulty = overridden.difficulty;
+    *personality = overridden.personality;
+    *rules = overridden.rules;
+    *arena = overridden.arena;
+    gameplay.0 = overridden.gameplay;
+    settings.left_handed = overridden.left_handed;
+    mutators.replace_enabled(overridden.mutators);
+    rng.seed = overridden.seed;
+}
@@ -0,0 +1,43 @@
+use std::path::Path;
+
+use bevy::{prelude::*, asset::io::{AssetSource, AssetSourceId, memory::{Dir, MemoryAssetReader}}};
+
+// everything the game loads, compiled in so the executable can ship without an assets folder; a new asset file has to be listed here too
+const ASSETS: &[(&str, &[u8])] = &[
+    ("fonts/DejaVuSans.ttf", include_bytes!("../assets/fonts/DejaVuSans.ttf")),
+    ("gameplay.config.ron", include_bytes!("../assets/gameplay.config.ron")),
+    ("music/gameplay.wav", include_bytes!("../assets/music/gameplay.wav")),
+    ("music/menu.wav", include_bytes!("../assets/music/menu.wav")),
+    ("shaders/crt.wgsl", include_bytes!("../assets/shaders/crt.wgsl")),
+    ("sounds/announcer/deuce.wav", include_bytes!("../assets/sounds/announcer/deuce.wav")),
+    ("sounds/announcer/match_point.wav", include_bytes!("../assets/sounds/announcer/match_point.wav")),
+    ("sounds/announcer/match_start.wav", include_bytes!("../assets/sounds/announcer/match_start.wav")),
+    ("sounds/announcer/victory.wav", include_bytes!("../assets/sounds/announcer/victory.wav")),
+    ("sounds/bounce.ogg", include_bytes!("../assets/sounds/bounce.ogg")),
+    ("sounds/concede.wav", include_bytes!("../assets/sounds/concede.wav")),
+    ("sounds/match_point.wav", include_bytes!("../assets/sounds/match_point.wav")),
+    ("sounds/score.ogg", include_bytes!("../assets/sounds/score.ogg")),
+    ("sounds/smash.wav", include_bytes!("../assets/sounds/smash.wav")),
+    ("sounds/ui_back.wav", include_bytes!("../assets/sounds/ui_back.wav")),
+    ("sounds/ui_confirm.wav", include_bytes!("../assets/sounds/ui_confirm.wav")),
+    ("sounds/ui_focus.wav", include_bytes!("../assets/sounds/ui_focus.wav")),
+    ("sounds/ui_slider.wav", include_bytes!("../assets/sounds/ui_slider.wav")),
+    ("sprites/ball.png", include_bytes!("../assets/sprites/ball.png")),
+    ("sprites/paddle.png", include_bytes!("../assets/sprites/paddle.png")),
+    ("sprites/paddle_grooved.png", include_bytes!("../assets/sprites/paddle_grooved.png")),
+    ("sprites/paddle_rounded.png", include_bytes!("../assets/sprites/paddle_rounded.png")),
+];
+
+pub struct EmbeddedAssetsPlugin;
+
+impl Plugin for EmbeddedAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        let root = Dir::default();
+        for (path, bytes) in ASSETS {
+            root.insert_asset(Path::new(path), *bytes);
+        }
+
+        // takes the place of the assets folder, so this has to be added before the asset plugin is
+        app.register_asset_source(AssetSourceId::Default, AssetSource::build().with_reader(move || Box::new(MemoryAssetReader { root: root.clone() })));
+    }
+}
@@ -0,0 +1,29 @@
+use bevy::{prelude::*, audio::GlobalVolume, window::WindowFocused};
+
+use crate::{GameMode, GameState, net::in_charge};
+
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, (
+            mute_when_unfocused,
+            pause_when_unfocused.run_if(in_state(GameState::Playing)).run_if(in_charge),
+        ).run_if(on_event::<WindowFocused>()));
+    }
+}
+
+// everything goes quiet while the player is in another window or has minimized this one
+fn mute_when_unfocused(mut events: EventReader<WindowFocused>, mut volume: ResMut<GlobalVolume>) {
+    if let Some(event) = events.read().last() {
+        *volume = GlobalVolume::new(if event.focused { 1. } else { 0. });
+    }
+}
+
+// the pause screen is still up when they come back, so play picks up again when they're ready
+fn pause_when_unfocused(mut events: EventReader<WindowFocused>, mode: Res<GameMode>, mut next_state: ResMut<NextState<GameState>>) {
+    if *mode != GameMode::Demo && events.read().any(|event| !event.focused) {
+        next_state.set(GameState::Paused);
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::{prelude::*, ui::widget::measure_text_system};
+
+// latin, greek and cyrillic beyond the ascii the built in font covers; nothing shipped draws chinese, japanese or korean
+const EXTENDED_FONT: &str = "fonts/DejaVuSans.ttf";
+
+pub struct FontPlugin;
+
+impl Plugin for FontPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Startup, load_fonts)
+        // before the ui measures anything, so text never lays out in a font that can't draw it
+        .add_systems(PostUpdate, pick_fonts.before(measure_text_system));
+    }
+}
+
+#[derive(Resource)]
+pub struct UiFonts {
+    extended: Handle<Font>,
+}
+
+impl UiFonts {
+    // the built in font for plain ascii, which is most of the game's own text
+    fn for_text(&self, value: &str) -> Handle<Font> {
+        if value.is_ascii() {
+            Handle::default()
+        } else {
+            self.extended.clone()
+        }
+    }
+
+    // a section in any other font asked for it by name, and keeps it
+    fn is_picked(&self, font: &Handle<Font>) -> bool {
+        *font == Handle::default() || *font == self.extended
+    }
+}
+
+fn load_fonts(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(UiFonts {
+        extended: asset_server.load(EXTENDED_FONT),
+    });
+}
+
+// every section left in the default font gets one that can draw all of it, names off the leaderboard included
+fn pick_fonts(fonts: Res<UiFonts>, mut texts: Query<&mut Text, Changed<Text>>) {
+    for mut text in texts.iter_mut() {
+        for index in 0..text.sections.len() {
+            if !fonts.is_picked(&text.sections[index].style.font) {
+                continue;
+            }
+
+            let font = fonts.for_text(&text.sections[index].value);
+            // only written when it differs, or the change would bring it straight back here
+            if text.sections[index].style.font != font {
+                text.sections[index].style.font = font;
+            }
+        }
+    }
+}
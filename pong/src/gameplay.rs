@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use bevy::{prelude::*, asset::{AssetLoader, LoadContext, io::Reader, AsyncReadExt}, utils::BoxedFuture};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GameMode, ai::AiControlled, mods::ModMutators, mutators::{Mutator, Mutators}, paddle::{Paddle, PaddleMotion, PaddleSize, apply_paddle_size}};
+
+const GAMEPLAY_CONFIG: &str = "gameplay.config.ron";
+
+pub struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_asset::<GameplayConfig>()
+        .init_asset_loader::<GameplayConfigLoader>()
+        .init_resource::<GameplayConfig>()
+        .register_type::<GameplayConfig>()
+        .init_resource::<GameplayBase>()
+        .init_resource::<GameplayRuntimeLayer>()
+        .add_systems(Startup, load_gameplay_config)
+        .add_systems(Update, apply_gameplay_config)
+        // after this frame's menu presses, so a match starting next frame already has its mode's numbers
+        .add_systems(PostUpdate, (layer_gameplay_config.run_if(layers_changed), retune_paddles).chain().before(apply_paddle_size));
+    }
+}
+
+// the numbers the feel of the game comes down to, read from the asset so they can be tuned while it runs; the defaults are what ships.
+// the resource is what's in effect right now, with the overrides for the mode and mutators in play laid over the file's numbers
+#[derive(Asset, Resource, Reflect, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct GameplayConfig {
+    pub paddle_size: Vec2,
+    pub player_speed: f32,
+    pub ai_max_speed: f32,
+    pub paddle_acceleration: f32,
+    pub paddle_deceleration: f32,
+    pub ball_speed: f32,
+    pub ball_speed_increment: f32,
+    pub max_ball_speed: f32,
+    pub spin_transfer: f32,
+    // left out of demos, which only need the numbers they were played with
+    #[serde(skip_serializing)]
+    #[reflect(ignore)]
+    pub overrides: HashMap<GameplayLayer, GameplayOverride>,
+}
+
+impl Default for GameplayConfig {
+    fn default() -> Self {
+        Self {
+            paddle_size: Vec2::new(17., 120.),
+            player_speed: 550.,
+            ai_max_speed: 700.,
+            paddle_acceleration: 3500.,
+            paddle_deceleration: 5000.,
+            ball_speed: 700.,
+            ball_speed_increment: 35.,
+            max_ball_speed: 1400.,
+            spin_transfer: 0.4,
+            overrides: HashMap::from([
+                // survival speeds up harder and has no ceiling short of the game's own limit
+                (GameplayLayer::Mode(GameMode::Survival), GameplayOverride { ball_speed_increment: Some(55.), max_ball_speed: Some(2600.), ..default() }),
+            ]),
+        }
+    }
+}
+
+// something an override can be keyed on, applied in this order: the mode, then each mutator
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum GameplayLayer {
+    Mode(GameMode),
+    Mutator(Mutator),
+    // by the name it shows on the match setup page
+    ModMutator(String),
+}
+
+// any number left out keeps whatever the layers under it gave it
+#[derive(Clone, PartialEq, Default, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameplayOverride {
+    pub paddle_size: Option<Vec2>,
+    pub player_speed: Option<f32>,
+    pub ai_max_speed: Option<f32>,
+    pub paddle_acceleration: Option<f32>,
+    pub paddle_deceleration: Option<f32>,
+    pub ball_speed: Option<f32>,
+    pub ball_speed_increment: Option<f32>,
+    pub max_ball_speed: Option<f32>,
+    pub spin_transfer: Option<f32>,
+}
+
+impl GameplayOverride {
+    // every number, so nothing under it shows through
+    pub fn all(config: &GameplayConfig) -> Self {
+        Self {
+            paddle_size: Some(config.paddle_size),
+            player_speed: Some(config.player_speed),
+            ai_max_speed: Some(config.ai_max_speed),
+            paddle_acceleration: Some(config.paddle_acceleration),
+            paddle_deceleration: Some(config.paddle_deceleration),
+            ball_speed: Some(config.ball_speed),
+            ball_speed_increment: Some(config.ball_speed_increment),
+            max_ball_speed: Some(config.max_ball_speed),
+            spin_transfer: Some(config.spin_transfer),
+        }
+    }
+
+    fn apply(&self, config: &mut GameplayConfig) {
+        config.paddle_size = self.paddle_size.unwrap_or(config.paddle_size);
+        config.player_speed = self.player_speed.unwrap_or(config.player_speed);
+        config.ai_max_speed = self.ai_max_speed.unwrap_or(config.ai_max_speed);
+        config.paddle_acceleration = self.paddle_acceleration.unwrap_or(config.paddle_acceleration);
+        config.paddle_deceleration = self.paddle_deceleration.unwrap_or(config.paddle_deceleration);
+        config.ball_speed = self.ball_speed.unwrap_or(config.ball_speed);
+        config.ball_speed_increment = self.ball_speed_increment.unwrap_or(config.ball_speed_increment);
+        config.max_ball_speed = self.max_ball_speed.unwrap_or(config.max_ball_speed);
+        config.spin_transfer = self.spin_transfer.unwrap_or(config.spin_transfer);
+    }
+}
+
+// the file's numbers before any layer goes on top
+#[derive(Resource, Default)]
+pub struct GameplayBase(pub GameplayConfig);
+
+// one last layer over everything else, for whatever's running the match for a while (the daily challenge, a demo playing back)
+#[derive(Resource, Default)]
+pub struct GameplayRuntimeLayer(pub Option<GameplayOverride>);
+
+#[derive(Debug, thiserror::Error)]
+pub enum GameplayConfigError {
+    #[error("couldn't read the gameplay config: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("couldn't parse the gameplay config: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+#[derive(Default)]
+struct GameplayConfigLoader;
+
+impl AssetLoader for GameplayConfigLoader {
+    type Asset = GameplayConfig;
+    type Settings = ();
+    type Error = GameplayConfigError;
+
+    fn load<'a>(&'a self, reader: &'a mut Reader, _settings: &'a (), _load_context: &'a mut LoadContext) -> BoxedFuture<'a, Result<GameplayConfig, GameplayConfigError>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["config.ron"]
+    }
+}
+
+// kept so the asset stays loaded and its changes can be picked out of the events
+#[derive(Resource)]
+struct GameplayConfigHandle(Handle<GameplayConfig>);
+
+fn load_gameplay_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(GameplayConfigHandle(asset_server.load(GAMEPLAY_CONFIG)));
+}
+
+// the first load and, with the asset server watching for changes, every save after it; a missing or broken file just leaves whatever was last read in place
+fn apply_gameplay_config(mut events: EventReader<AssetEvent<GameplayConfig>>, handle: Res<GameplayConfigHandle>, assets: Res<Assets<GameplayConfig>>, mut base: ResMut<GameplayBase>) {
+    let loaded = events.read().any(|event| match event {
+        AssetEvent::Added { id } | AssetEvent::Modified { id } => *id == handle.0.id(),
+        _ => false,
+    });
+    if !loaded {
+        return;
+    }
+
+    let asset = if let Some(asset) = assets.get(&handle.0) { asset } else { return; };
+    // only written when it differs, since saving the file untouched still comes back as a change
+    if base.0 != *asset {
+        info!("gameplay config updated");
+        base.0 = asset.clone();
+    }
+}
+
+fn layers_changed(base: Res<GameplayBase>, runtime: Res<GameplayRuntimeLayer>, mode: Res<GameMode>, mutators: Res<Mutators>, mod_mutators: Res<ModMutators>) -> bool {
+    base.is_changed() || runtime.is_changed() || mode.is_changed() || mutators.is_changed() || mod_mutators.is_changed()
+}
+
+fn layer_gameplay_config(base: Res<GameplayBase>, runtime: Res<GameplayRuntimeLayer>, mode: Res<GameMode>, mutators: Res<Mutators>, mod_mutators: Res<ModMutators>, mut config: ResMut<GameplayConfig>) {
+    let mut layered = base.0.clone();
+    let layers = std::iter::once(GameplayLayer::Mode(*mode))
+        .chain(mutators.enabled().iter().map(|mutator| GameplayLayer::Mutator(*mutator)))
+        .chain(mod_mutators.names().iter().filter(|name| mod_mutators.is_enabled(name)).map(|name| GameplayLayer::ModMutator(name.to_string())));
+    for layer in layers {
+        if let Some(layer) = base.0.overrides.get(&layer) {
+            layer.apply(&mut layered);
+        }
+    }
+    if let Some(runtime) = &runtime.0 {
+        runtime.apply(&mut layered);
+    }
+
+    if *config != layered {
+        *config = layered;
+    }
+}
+
+// paddles already out there take the new numbers straight away, rather than waiting for the next match
+fn retune_paddles(config: Res<GameplayConfig>, mut paddles: Query<(&mut PaddleSize, Option<&mut PaddleMotion>, Has<AiControlled>), With<Paddle>>) {
+    if !config.is_changed() || config.is_added() {
+        return;
+    }
+
+    for (mut size, motion, ai) in paddles.iter_mut() {
+        size.base = config.paddle_size;
+        let mut motion = if let Some(motion) = motion { motion } else { continue; };
+        motion.max_speed = if ai { config.ai_max_speed } else { config.player_speed };
+        motion.acceleration = config.paddle_acceleration;
+        motion.deceleration = config.paddle_deceleration;
+    }
+}
@@ -0,0 +1,144 @@
+use std::{sync::{Arc, atomic::{AtomicBool, Ordering}}, time::Duration};
+
+use bevy::{prelude::*, app::{AppExit, ScheduleRunnerPlugin}, input::InputPlugin, log::{Level, LogPlugin}, render::texture::ImageLoader, time::TimeUpdateStrategy};
+
+use crate::{CorePlugin, GameMode, GameSet, GameState, MatchRules, adaptive::AdaptivePlugin, bindings::Bindings, gameplay::GameplayPlugin, mods::ModMutators, mutators::Mutators, rng::GameRng, score::{Rally, Scoreboard}, settings::Settings, skins::PaddleSkins, theme::Theme, watchdog::WatchdogPlugin};
+
+const DEFAULT_MATCHES: u32 = 10;
+// every frame moves the match on this far, however long it actually took to work out
+const FRAME_SECONDS: f64 = 1. / 60.;
+// a match still going after this long has got stuck somehow, and fails the run
+const MAX_MATCH_SECONDS: f32 = 30. * 60.;
+
+// `--headless` on the command line, optionally followed by how many matches to play
+pub fn headless_argument() -> Option<u32> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--headless" {
+            return Some(args.next().and_then(|matches| matches.parse().ok()).unwrap_or(DEFAULT_MATCHES));
+        }
+    }
+    None
+}
+
+// the computer playing itself with no window, as fast as it can, printing how each match went;
+// one seed carries on through every match, so the whole run plays out the same again from it
+pub fn run(matches: u32, seed: Option<u64>) {
+    let random = GameRng::new(seed);
+    println!("playing {matches} matches from seed {}", random.match_seed);
+    let failed = Arc::new(AtomicBool::new(false));
+
+    let mut app = headless_app(random);
+    app
+    .add_plugins(LogPlugin { level: Level::WARN, ..default() })
+    .insert_resource(GameMode::Demo)
+    .insert_resource(Simulation { matches, failed: failed.clone(), ..default() })
+    .add_systems(Update, next_match.run_if(in_state(GameState::Menu)))
+    .add_systems(Update, (track_rallies.in_set(GameSet::Presentation), give_up_on_match).run_if(in_state(GameState::Playing)))
+    .add_systems(OnEnter(GameState::Menu), record_result);
+
+    app.run();
+
+    if failed.load(Ordering::Relaxed) {
+        std::process::exit(1);
+    }
+}
+
+// the match and everything that changes how it plays, in fixed frames with nothing to see or hear it; the tests play on this too
+pub fn headless_app(random: GameRng) -> App {
+    let mut app = App::new();
+    app
+    .add_plugins((MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::ZERO)), AssetPlugin::default(), TransformPlugin, HierarchyPlugin, InputPlugin))
+    // the sprites are still asked for, they just never arrive with nothing to draw them
+    .init_asset::<Image>()
+    .preregister_asset_loader::<ImageLoader>(&["png"])
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(FRAME_SECONDS)))
+    .insert_resource(random)
+    .insert_resource(Headless)
+    .init_resource::<Settings>()
+    .init_resource::<Theme>()
+    .init_resource::<Bindings>()
+    .init_resource::<PaddleSkins>()
+    .init_resource::<Mutators>()
+    .init_resource::<ModMutators>()
+    .add_plugins((CorePlugin, GameplayPlugin, AdaptivePlugin, WatchdogPlugin));
+
+    #[cfg(feature = "rapier")]
+    app.add_plugins(crate::rapier::RapierBackendPlugin);
+
+    app
+}
+
+// there from the start of a headless app, for plugins to leave out what only matters with someone watching
+#[derive(Resource)]
+pub struct Headless;
+
+#[derive(Resource, Default)]
+struct Simulation {
+    matches: u32,
+    played: u32,
+    // when the match in play started, in simulated seconds
+    started: Option<f32>,
+    longest_rally: u32,
+    results: Vec<MatchResult>,
+    failed: Arc<AtomicBool>,
+}
+
+struct MatchResult {
+    // Some(true) for the player's side, None for a match that never finished
+    winner: Option<bool>,
+    seconds: f32,
+}
+
+fn next_match(mut simulation: ResMut<Simulation>, time: Res<Time>, mode: Res<GameMode>, mut next_state: ResMut<NextState<GameState>>, mut exit: EventWriter<AppExit>) {
+    if simulation.played < simulation.matches {
+        simulation.played += 1;
+        simulation.started = Some(time.elapsed_seconds());
+        simulation.longest_rally = 0;
+        next_state.set(GameState::Playing);
+        return;
+    }
+
+    let (player, opponent) = mode.side_names();
+    let wins = |side: Option<bool>| simulation.results.iter().filter(|result| result.winner == side).count();
+    let average = simulation.results.iter().map(|result| result.seconds).sum::<f32>() / simulation.results.len().max(1) as f32;
+    println!("{player} won {}, {opponent} won {}, {} unfinished; {} a match on average", wins(Some(true)), wins(Some(false)), wins(None), duration_label(average));
+    if wins(None) > 0 {
+        simulation.failed.store(true, Ordering::Relaxed);
+    }
+    exit.send(AppExit);
+}
+
+fn track_rallies(rally: Res<Rally>, mut simulation: ResMut<Simulation>) {
+    simulation.longest_rally = simulation.longest_rally.max(rally.hits);
+}
+
+fn give_up_on_match(simulation: Res<Simulation>, time: Res<Time>, mut next_state: ResMut<NextState<GameState>>) {
+    if simulation.started.is_some_and(|started| time.elapsed_seconds() - started > MAX_MATCH_SECONDS) {
+        next_state.set(GameState::Menu);
+    }
+}
+
+// the demo mode the matches are played in goes back to the menu once one's won
+fn record_result(mut simulation: ResMut<Simulation>, time: Res<Time>, scoreboard: Res<Scoreboard>, rules: Res<MatchRules>, mode: Res<GameMode>) {
+    let started = if let Some(started) = simulation.started.take() { started } else { return; };
+    let seconds = time.elapsed_seconds() - started;
+    let winner = scoreboard.winner(&rules);
+
+    let (player, opponent) = mode.side_names();
+    let mut sets: Vec<String> = scoreboard.sets.iter().map(|(player, opponent)| format!("{player}-{opponent}")).collect();
+    let outcome = match winner {
+        Some(player_won) => format!("{} won", if player_won { player } else { opponent }),
+        None => {
+            sets.push(format!("{}-{}", scoreboard.player, scoreboard.computer));
+            "unfinished".to_string()
+        }
+    };
+    println!("match {}: {outcome} {} in {}, longest rally {}", simulation.played, sets.join(", "), duration_label(seconds), simulation.longest_rally);
+    simulation.results.push(MatchResult { winner, seconds });
+}
+
+fn duration_label(seconds: f32) -> String {
+    let seconds = seconds.round() as u32;
+    format!("{}m {:02}s", seconds / 60, seconds % 60)
+}
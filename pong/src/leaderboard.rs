@@ -0,0 +1,267 @@
+use std::{io::{self, Read, Write}, net::{TcpStream, ToSocketAddrs}, time::Duration};
+
+use bevy::{prelude::*, tasks::{IoTaskPool, Task, block_on}};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GameMode, GameState, daily::{DailyRecord, daily_active, date_label, today}, menu::MenuScreen, score::Scoreboard, settings::Settings, storage, survival::SurvivalRecord};
+
+// scores that haven't reached the server yet, kept until they do
+const QUEUE_PATH: &str = "leaderboard_queue.ron";
+// seconds to wait after a failed submission before trying the queue again
+const RETRY_INTERVAL: f32 = 60.;
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+pub const LEADERBOARD_SIZE: usize = 100;
+// how far back the daily boards go on the leaderboard page, today included
+const DAILY_BOARDS: u64 = 7;
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(storage::load::<ScoreQueue>(QUEUE_PATH))
+        .init_resource::<Leaderboard>()
+        .init_resource::<LeaderboardRequests>()
+        .add_systems(OnEnter(GameState::GameOver), queue_survival_score.run_if(resource_equals(GameMode::Survival)))
+        .add_systems(OnEnter(GameState::GameOver), queue_daily_score.run_if(daily_active))
+        .add_systems(Update, fetch_leaderboard.run_if(resource_changed::<MenuScreen>()))
+        .add_systems(Update, leaderboard_actions.run_if(in_state(GameState::Menu)))
+        .add_systems(Update, (submit_scores, finish_submission, finish_fetch))
+        .add_systems(Update, save_queue.run_if(resource_changed::<ScoreQueue>()).run_if(not(resource_added::<ScoreQueue>())));
+    }
+}
+
+// the server takes one of these as json at `<leaderboard_url>/scores` and answers `<leaderboard_url>/scores?mode=<mode>` with a json array of them, best first
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Score {
+    pub name: String,
+    #[serde(default)]
+    pub mode: String,
+    pub score: f32,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct ScoreQueue(Vec<Score>);
+
+// the top scores as of the last time the leaderboard page was opened
+#[derive(Resource, Default)]
+pub struct Leaderboard {
+    pub board: Board,
+    pub entries: Vec<Score>,
+    pub status: String,
+}
+
+// one of the server's boards, each kept apart by the mode its scores are sent with
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Board {
+    #[default]
+    Survival,
+    // a day's challenge, in days since the epoch
+    Daily(u64),
+}
+
+impl Board {
+    pub fn mode(&self) -> String {
+        match self {
+            Board::Survival => "survival".to_string(),
+            Board::Daily(day) => format!("daily-{}", date_label(*day)),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Board::Survival => "Survival".to_string(),
+            Board::Daily(day) => format!("Daily {}", date_label(*day)),
+        }
+    }
+
+    // survival ranks how long the player lasted, the daily challenge the winning margin
+    pub fn score_label(&self, score: f32) -> String {
+        match self {
+            Board::Survival => format!("{score:.1}s"),
+            Board::Daily(_) => format!("{score:+.0}"),
+        }
+    }
+
+    // survival, then each day's challenge going back from today
+    fn next(&self, today: u64) -> Self {
+        match self {
+            Board::Survival => Board::Daily(today),
+            Board::Daily(day) if today.saturating_sub(*day) + 1 < DAILY_BOARDS && *day > 0 => Board::Daily(day - 1),
+            Board::Daily(_) => Board::Survival,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+pub enum LeaderboardButton {
+    Board,
+}
+
+#[derive(Resource)]
+struct LeaderboardRequests {
+    // resolves to how many scores from the front of the queue made it
+    submission: Option<Task<usize>>,
+    fetch: Option<Task<io::Result<Vec<Score>>>>,
+    retry: Timer,
+}
+
+impl Default for LeaderboardRequests {
+    fn default() -> Self {
+        // starts run out so a queue left over from last time goes straight away
+        let mut retry = Timer::from_seconds(RETRY_INTERVAL, TimerMode::Once);
+        retry.set_elapsed(retry.duration());
+        Self { submission: None, fetch: None, retry }
+    }
+}
+
+fn scores_url(base: &str) -> String {
+    format!("{}/scores", base.trim_end_matches('/'))
+}
+
+// just enough http/1.0 for a json endpoint; there's no tls, so it needs a plain http address
+fn http(method: &str, url: &str, body: &str) -> io::Result<String> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "only http:// addresses are supported"))?;
+    let (host, path) = rest.split_once('/').map_or((rest, "/".to_string()), |(host, path)| (host, format!("/{path}")));
+    let address = if host.contains(':') { host.to_string() } else { format!("{host}:80") };
+    let address = address.to_socket_addrs()?.next().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such host"))?;
+
+    let mut stream = TcpStream::connect_timeout(&address, HTTP_TIMEOUT)?;
+    stream.set_read_timeout(Some(HTTP_TIMEOUT))?;
+    stream.set_write_timeout(Some(HTTP_TIMEOUT))?;
+    write!(stream, "{method} {path} HTTP/1.0\r\nHost: {host}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}", body.len())?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+    let status = head.split_whitespace().nth(1).unwrap_or("");
+    if !status.starts_with('2') {
+        return Err(io::Error::other(format!("the server answered {status}")));
+    }
+    Ok(body.to_string())
+}
+
+fn queue_survival_score(survival: Res<SurvivalRecord>, settings: Res<Settings>, mut queue: ResMut<ScoreQueue>) {
+    if settings.leaderboard_url.is_none() || survival.current <= 0. {
+        return;
+    }
+
+    queue.0.push(Score { name: settings.player_name.clone(), mode: Board::Survival.mode(), score: survival.current });
+}
+
+// each day's challenge is its own board, scored by the winning margin so a close loss still ranks above a rout
+fn queue_daily_score(scoreboard: Res<Scoreboard>, record: Res<DailyRecord>, settings: Res<Settings>, mut queue: ResMut<ScoreQueue>) {
+    let day = if let Some(day) = record.day { day } else { return; };
+    if settings.leaderboard_url.is_none() {
+        return;
+    }
+
+    let margin = scoreboard.player as f32 - scoreboard.computer as f32;
+    queue.0.push(Score { name: settings.player_name.clone(), mode: Board::Daily(day).mode(), score: margin });
+}
+
+fn submit_scores(mut requests: ResMut<LeaderboardRequests>, queue: Res<ScoreQueue>, settings: Res<Settings>, time: Res<Time>) {
+    let url = if let Some(url) = &settings.leaderboard_url { scores_url(url) } else { return; };
+    if requests.submission.is_some() || queue.0.is_empty() || !requests.retry.tick(time.delta()).finished() {
+        return;
+    }
+
+    let scores = queue.0.clone();
+    requests.submission = Some(IoTaskPool::get().spawn(async move {
+        // in order, stopping at the first failure so nothing gets sent twice or out of turn
+        let mut sent = 0;
+        for score in &scores {
+            let result = serde_json::to_string(score).map_err(io::Error::other).and_then(|body| http("POST", &url, &body));
+            if let Err(error) = result {
+                warn!("couldn't submit a score, keeping it queued: {error}");
+                break;
+            }
+            sent += 1;
+        }
+        sent
+    }));
+}
+
+fn finish_submission(mut requests: ResMut<LeaderboardRequests>, mut queue: ResMut<ScoreQueue>) {
+    if !requests.submission.as_ref().is_some_and(|task| task.is_finished()) {
+        return;
+    }
+    let sent = if let Some(task) = requests.submission.take() { block_on(task) } else { return; };
+
+    // anything queued while the request was out went on the end, so the front is what was sent
+    let failed = sent < queue.0.len();
+    if sent > 0 {
+        queue.0.drain(..sent);
+    }
+    if failed {
+        requests.retry.reset();
+    }
+}
+
+// only when the page is first opened, not every time it's rebuilt
+fn fetch_leaderboard(screen: Res<MenuScreen>, mut last_screen: Local<MenuScreen>, mut requests: ResMut<LeaderboardRequests>, mut leaderboard: ResMut<Leaderboard>, settings: Res<Settings>) {
+    let opened = *screen == MenuScreen::Leaderboard && *last_screen != MenuScreen::Leaderboard;
+    *last_screen = *screen;
+    if opened {
+        fetch_board(&mut requests, &mut leaderboard, &settings);
+    }
+}
+
+fn fetch_board(requests: &mut LeaderboardRequests, leaderboard: &mut Leaderboard, settings: &Settings) {
+    leaderboard.entries.clear();
+    let url = if let Some(url) = &settings.leaderboard_url { format!("{}?mode={}", scores_url(url), leaderboard.board.mode()) } else {
+        leaderboard.status = "Set leaderboard_url in config.ron to use the leaderboard".to_string();
+        return;
+    };
+
+    leaderboard.status = "Loading...".to_string();
+    // a board picked while another was loading replaces it, so the old answer never lands on the new board
+    requests.fetch = Some(IoTaskPool::get().spawn(async move {
+        let body = http("GET", &url, "")?;
+        serde_json::from_str(&body).map_err(io::Error::other)
+    }));
+}
+
+fn leaderboard_actions(query: Query<(&Interaction, &LeaderboardButton), Changed<Interaction>>, mut requests: ResMut<LeaderboardRequests>, mut leaderboard: ResMut<Leaderboard>, settings: Res<Settings>, mut screen: ResMut<MenuScreen>) {
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            LeaderboardButton::Board => leaderboard.board = leaderboard.board.next(today()),
+        }
+        fetch_board(&mut requests, &mut leaderboard, &settings);
+        // rebuilt to show the new board
+        screen.set_changed();
+    }
+}
+
+fn finish_fetch(mut requests: ResMut<LeaderboardRequests>, mut leaderboard: ResMut<Leaderboard>, queue: Res<ScoreQueue>, mut screen: ResMut<MenuScreen>) {
+    if !requests.fetch.as_ref().is_some_and(|task| task.is_finished()) {
+        return;
+    }
+    let result = if let Some(task) = requests.fetch.take() { block_on(task) } else { return; };
+
+    match result {
+        Ok(mut entries) => {
+            entries.truncate(LEADERBOARD_SIZE);
+            leaderboard.status = if entries.is_empty() { "No scores yet".to_string() } else { String::new() };
+            leaderboard.entries = entries;
+        }
+        Err(error) => leaderboard.status = format!("Offline: {error}"),
+    }
+    if !queue.0.is_empty() {
+        leaderboard.status = format!("{}\n{} scores waiting to be sent", leaderboard.status, queue.0.len()).trim().to_string();
+    }
+
+    // the page is rebuilt from scratch to show what came back
+    if *screen == MenuScreen::Leaderboard {
+        screen.set_changed();
+    }
+}
+
+fn save_queue(queue: Res<ScoreQueue>) {
+    storage::save(QUEUE_PATH, &*queue);
+}
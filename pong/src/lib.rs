@@ -0,0 +1,543 @@
+#![allow(clippy::type_complexity)]
+
+use bevy::{prelude::*, app::AppExit};
+
+use serde::{Deserialize, Serialize};
+
+pub mod adaptive;
+pub mod ai;
+pub mod announcer;
+pub mod assist;
+pub mod audio;
+pub mod background;
+pub mod ball;
+pub mod bindings;
+pub mod console;
+pub mod crash;
+pub mod crt;
+pub mod focus;
+pub mod fonts;
+pub mod gameplay;
+pub mod headless;
+pub mod leaderboard;
+pub mod menu;
+pub mod menu_sounds;
+pub mod mouse;
+pub mod mixer;
+pub mod mods;
+pub mod music;
+pub mod navigation;
+pub mod mutators;
+pub mod net;
+pub mod curve;
+pub mod daily;
+pub mod debug;
+pub mod demos;
+#[cfg(feature = "embedded_assets")]
+pub mod embedded;
+pub mod obstacles;
+pub mod overtime;
+pub mod paddle;
+pub mod particles;
+pub mod placeholders;
+pub mod powerups;
+pub mod practice;
+pub mod profiles;
+pub mod survival;
+pub mod suspend;
+pub mod timed;
+pub mod touch;
+pub mod trail;
+pub mod ui;
+pub mod watchdog;
+#[cfg(feature = "rapier")]
+pub mod rapier;
+pub mod records;
+pub mod replay;
+pub mod rumble;
+pub mod rng;
+pub mod score;
+pub mod settings;
+pub mod shake;
+pub mod skins;
+pub mod speech;
+pub mod stats;
+pub mod streaks;
+pub mod storage;
+#[cfg(test)]
+mod tests;
+pub mod theme;
+
+use adaptive::AdaptivePlugin;
+use ai::AiPlugin;
+use announcer::AnnouncerPlugin;
+use assist::AssistPlugin;
+use audio::AudioPlugin;
+use background::BackgroundPlugin;
+use ball::{Ball, BallPlugin, BallSpeed, Serve, ServeAngle, center_ball};
+use bindings::{Bindings, BindingsPlugin, reset_pressed};
+use console::ConsolePlugin;
+use crash::CrashPlugin;
+use crt::CrtPlugin;
+use focus::FocusPlugin;
+use fonts::FontPlugin;
+use gameplay::GameplayPlugin;
+use headless::headless_argument;
+use leaderboard::LeaderboardPlugin;
+use menu::MenuPlugin;
+use menu_sounds::MenuSoundPlugin;
+use mouse::MousePlugin;
+use mods::ModPlugin;
+use music::MusicPlugin;
+use navigation::NavigationPlugin;
+use mutators::MutatorPlugin;
+use net::{NetPlugin, NetSession, in_charge, ticking};
+use curve::CurvePlugin;
+use daily::DailyPlugin;
+use debug::DebugPlugin;
+use demos::{DemoPlugin, demo_playing, demo_reset};
+use obstacles::ObstaclePlugin;
+use overtime::OvertimePlugin;
+use paddle::{Computer, PaddlePlugin, Player, Player2};
+use particles::ParticlePlugin;
+use placeholders::PlaceholderPlugin;
+use powerups::PowerUpPlugin;
+use practice::PracticePlugin;
+use profiles::ProfilePlugin;
+use records::RecordsPlugin;
+use replay::ReplayPlugin;
+use rumble::RumblePlugin;
+use rng::{GameRng, RngPlugin, seed_argument};
+use score::{Rally, ScorePlugin, Scoreboard};
+use settings::{Config, Settings, SettingsPlugin};
+use shake::ShakePlugin;
+use skins::SkinPlugin;
+use speech::SpeechPlugin;
+use stats::StatsPlugin;
+use streaks::StreakPlugin;
+use theme::{Theme, ThemePlugin};
+use survival::SurvivalPlugin;
+use suspend::SuspendPlugin;
+use timed::TimedMatchPlugin;
+use touch::TouchPlugin;
+use trail::TrailPlugin;
+use ui::UiPlugin;
+use watchdog::WatchdogPlugin;
+
+// the screen the hud and menus are laid out for; other window sizes scale it to fit
+pub const VIRTUAL_RESOLUTION: Vec2 = Vec2::new(1920., 1080.);
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    VersusComputer,
+    TwoPlayer,
+    // one player against a back wall, scored on how long they last
+    Survival,
+    // free play against a ball launcher, no opponent and no score
+    Practice,
+    // ai plays itself behind the menu's attract screen
+    Demo,
+    // against another machine on the network, with the host as paddle one
+    Online,
+}
+
+#[derive(States, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameState {
+    #[default]
+    Menu,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+// play moves on in ticks of this length whatever the frame rate, so the same inputs always play out the same, here, on another machine or from a demo
+pub const TICKS_PER_SECOND: f64 = 60.;
+
+// the stages of a tick of play, run in this order in `FixedUpdate`; new systems join whichever stage they belong to rather than naming the systems either side of them
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameSet {
+    // players, and anything standing in for them, setting where their paddles want to go
+    Input,
+    Ai,
+    // moving everything and bouncing the ball
+    Physics,
+    Scoring,
+    // the hud, sounds and effects catching up with what happened, once a frame in `Update`; these keep running out of play
+    Presentation,
+}
+
+// the reset key, held over until the next tick so a press between two ticks isn't lost
+#[derive(Resource, Default)]
+pub struct ResetRequested(pub bool);
+
+#[derive(Component)]
+pub struct MatchEntity;
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub struct Collider(Vec2);
+
+impl Collider {
+    pub fn cuboid(x: f32, y: f32) -> Self {
+        Self(Vec2{x, y})
+    }
+
+    pub fn circle(radius: f32) -> Self {
+        let diameter = radius * 2.;
+        Self(Vec2::new(diameter, diameter))
+    }
+}
+
+#[derive(Component, Default, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Velocity(pub Vec2);
+
+// the playing field in world units, centered on the origin; the camera scales to fit it
+#[derive(Resource, Clone, Copy, PartialEq, Debug, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+pub struct ArenaConfig {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for ArenaConfig {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+impl ArenaConfig {
+    pub const STANDARD: ArenaConfig = ArenaConfig { width: 1920., height: 1080. };
+    pub const WIDE: ArenaConfig = ArenaConfig { width: 2400., height: 1080. };
+    pub const COMPACT: ArenaConfig = ArenaConfig { width: 1600., height: 900. };
+
+    pub fn half_width(&self) -> f32 {
+        self.width / 2.
+    }
+
+    pub fn half_height(&self) -> f32 {
+        self.height / 2.
+    }
+
+    // where the paddles line up, a little in from each goal
+    pub fn paddle_x(&self) -> f32 {
+        self.width / 4. + self.width / 5.
+    }
+
+    pub fn next(&self) -> Self {
+        match *self {
+            ArenaConfig::STANDARD => ArenaConfig::WIDE,
+            ArenaConfig::WIDE => ArenaConfig::COMPACT,
+            _ => ArenaConfig::STANDARD,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match *self {
+            ArenaConfig::STANDARD => "Standard",
+            ArenaConfig::WIDE => "Wide",
+            ArenaConfig::COMPACT => "Compact",
+            _ => "Custom",
+        }
+    }
+}
+
+// evens out mismatched players; the player's side is paddle one, the opponent's paddle two
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize, Reflect)]
+pub struct Handicap {
+    pub size: f32,
+    pub speed: f32,
+    pub head_start: u32,
+}
+
+impl Default for Handicap {
+    fn default() -> Self {
+        Self { size: 1., speed: 1., head_start: 0 }
+    }
+}
+
+#[derive(Resource, Clone, Debug, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+pub struct MatchRules {
+    pub point_target: u32,
+    pub best_of: u32,
+    // minutes on the clock, after which the points lead decides the match instead of the point target
+    pub time_limit: Option<u32>,
+    pub power_ups: bool,
+    pub obstacles: bool,
+    // hits from a moving paddle bend the ball's path
+    pub curve_ball: bool,
+    // points in a row build up a multiplier on the next ones
+    pub streaks: bool,
+    pub serve_angle: ServeAngle,
+    pub player_handicap: Handicap,
+    pub opponent_handicap: Handicap,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            point_target: 11,
+            best_of: 1,
+            time_limit: None,
+            power_ups: false,
+            obstacles: false,
+            curve_ball: false,
+            streaks: false,
+            serve_angle: ServeAngle::default(),
+            player_handicap: Handicap::default(),
+            opponent_handicap: Handicap::default(),
+        }
+    }
+}
+
+impl MatchRules {
+    pub fn handicap(&self, player: bool) -> &Handicap {
+        if player { &self.player_handicap } else { &self.opponent_handicap }
+    }
+
+    pub fn handicap_mut(&mut self, player: bool) -> &mut Handicap {
+        if player { &mut self.player_handicap } else { &mut self.opponent_handicap }
+    }
+
+    // a timed match is a single set however many were asked for
+    pub fn uses_sets(&self) -> bool {
+        self.best_of > 1 && self.time_limit.is_none()
+    }
+
+    pub fn sets_to_win(&self) -> u32 {
+        if self.uses_sets() { self.best_of / 2 + 1 } else { 1 }
+    }
+}
+
+// quitting from the pause screen takes a second key press, so a stray one can't throw a match away
+#[derive(Resource, Default)]
+pub struct QuitConfirm(bool);
+
+impl GameMode {
+    pub fn side_names(&self) -> (&'static str, &'static str) {
+        match self {
+            GameMode::VersusComputer => ("Player", "Computer"),
+            GameMode::TwoPlayer => ("Player 1", "Player 2"),
+            GameMode::Survival => ("Player", "Wall"),
+            GameMode::Practice => ("Player", "Launcher"),
+            GameMode::Demo => ("CPU 2", "CPU 1"),
+            GameMode::Online => ("Host", "Guest"),
+        }
+    }
+
+    // two paddles playing for points, as opposed to the single paddle modes
+    pub fn is_match(&self) -> bool {
+        !matches!(self, GameMode::Survival | GameMode::Practice)
+    }
+}
+
+pub fn run() {
+    // none of the saved settings, so a run plays out the same wherever it's run
+    if let Some(matches) = headless_argument() {
+        headless::run(matches, seed_argument());
+        return;
+    }
+
+    // read before the window exists so it opens the way it was left
+    let Config { settings, mixer, difficulty, personality, theme, bindings, skins } = Config::load();
+    let seed = seed_argument().or(settings.seed);
+
+    let mut app = App::new();
+    // first, so everything after it is logged to the file too
+    app.add_plugins(CrashPlugin);
+    let default_plugins = DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: "Pong!".into(),
+            #[cfg(not(target_arch = "wasm32"))]
+            mode: settings.window_mode(),
+            // browsers get a canvas that fills the page instead
+            #[cfg(target_arch = "wasm32")]
+            canvas: Some("#pong".into()),
+            #[cfg(target_arch = "wasm32")]
+            fit_canvas_to_parent: true,
+            ..default()
+        }),
+        ..default()    
+    });
+    // the crash plugin does the logging natively
+    #[cfg(not(target_arch = "wasm32"))]
+    let default_plugins = default_plugins.disable::<bevy::log::LogPlugin>();
+    // saving a file under assets/ reloads it in a running debug build, so the gameplay numbers can be tuned without a restart; an embedded copy never changes
+    #[cfg(all(debug_assertions, not(target_arch = "wasm32"), not(feature = "embedded_assets")))]
+    let default_plugins = default_plugins.set(AssetPlugin { watch_for_changes_override: Some(true), ..default() });
+    #[cfg(feature = "embedded_assets")]
+    app.add_plugins(embedded::EmbeddedAssetsPlugin);
+    app
+    .add_plugins(default_plugins)
+    .insert_resource(ClearColor(theme.palette().background))
+    .insert_resource(settings)
+    .insert_resource(mixer)
+    .insert_resource(theme)
+    .insert_resource(bindings)
+    .insert_resource(skins)
+    .init_resource::<QuitConfirm>()
+    .insert_resource(GameRng::new(seed))
+    .insert_resource(difficulty)
+    .insert_resource(personality)
+    .add_plugins((CorePlugin, UiPlugin, AudioPlugin))
+    .add_plugins((MenuPlugin, PowerUpPlugin, ObstaclePlugin, OvertimePlugin, SurvivalPlugin, TimedMatchPlugin, PracticePlugin, MousePlugin, SettingsPlugin, RngPlugin, NetPlugin, LeaderboardPlugin, FocusPlugin, CurvePlugin, FontPlugin))
+    .add_plugins((TrailPlugin, ParticlePlugin, ShakePlugin, CrtPlugin, BackgroundPlugin, AnnouncerPlugin, MusicPlugin, StatsPlugin, RecordsPlugin, ReplayPlugin, AssistPlugin, ThemePlugin, BindingsPlugin, TouchPlugin, SkinPlugin))
+    .add_plugins((WatchdogPlugin, AdaptivePlugin, ProfilePlugin, ConsolePlugin, MutatorPlugin, DebugPlugin, GameplayPlugin, PlaceholderPlugin, NavigationPlugin, MenuSoundPlugin, RumblePlugin, SpeechPlugin, StreakPlugin, DailyPlugin))
+    .add_plugins((DemoPlugin, SuspendPlugin, ModPlugin))
+    .register_type::<Settings>()
+    .add_systems(Update, state_input.run_if(in_charge))
+    // both machines would have to reset on the same tick online, so it's left to the menu between matches there
+    .add_systems(Update, request_reset.run_if(reset_pressed).run_if(not(demo_playing)).run_if(in_state(GameState::Playing)).run_if(not(resource_equals(GameMode::Online))))
+    .add_systems(FixedUpdate, reset_match.run_if(reset_requested.or_else(demo_reset)).run_if(not(resource_equals(GameMode::Practice))).in_set(GameSet::Scoring));
+
+    #[cfg(feature = "rapier")]
+    app.add_plugins(rapier::RapierBackendPlugin);
+    #[cfg(feature = "inspector")]
+    app.add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new());
+
+    app.run();
+}
+
+// the rules and moving parts of a match with nothing to see or hear, which is all the headless simulation runs
+pub struct CorePlugin;
+
+impl Plugin for CorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<MatchRules>()
+        .init_resource::<ArenaConfig>()
+        .init_resource::<GameMode>()
+        .init_resource::<ResetRequested>()
+        .insert_resource(Time::<Fixed>::from_hz(TICKS_PER_SECOND))
+        .add_state::<GameState>()
+        .configure_sets(FixedUpdate, (GameSet::Input, GameSet::Ai, GameSet::Physics, GameSet::Scoring).chain().run_if(ticking))
+        .configure_sets(FixedUpdate, GameSet::Ai.run_if(any_with_component::<Ball>()))
+        .add_plugins((BallPlugin, PaddlePlugin, AiPlugin, ScorePlugin))
+        // so an inspector or anything else working through reflection can see and edit them live
+        .register_type::<Velocity>()
+        .register_type::<Collider>()
+        .register_type::<MatchRules>()
+        .register_type::<ArenaConfig>()
+        .add_systems(OnEnter(GameState::Menu), despawn_match)
+        .add_systems(OnExit(GameState::Menu), spawn_match)
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, reset_match);
+    }
+}
+
+fn is_match(mode: Res<GameMode>) -> bool {
+    mode.is_match()
+}
+
+fn state_input(input: Res<Input<KeyCode>>, bindings: Res<Bindings>, (gamepads, buttons): (Res<Gamepads>, Res<Input<GamepadButton>>), (state, mode): (Res<State<GameState>>, Res<GameMode>), mut quit: ResMut<QuitConfirm>, mut exit: EventWriter<AppExit>, mut next_state: ResMut<NextState<GameState>>) {
+    if *mode == GameMode::Demo {
+        return;
+    }
+
+    // escape always pauses as well as whatever pause is bound to
+    let pause = input.any_just_pressed([bindings.pause, KeyCode::Escape]) || gamepads.iter().any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::Start)));
+    let end_match = input.just_pressed(KeyCode::Q) || gamepads.iter().any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::Select)));
+    match state.get() {
+        // anything but yes backs out of quitting
+        GameState::Paused if quit.0 => {
+            if input.just_pressed(KeyCode::Y) {
+                exit.send(AppExit);
+            } else if pause || input.get_just_pressed().next().is_some() {
+                quit.0 = false;
+            }
+        }
+        GameState::Playing if pause => next_state.set(GameState::Paused),
+        GameState::Paused if pause => next_state.set(GameState::Playing),
+        GameState::Paused if end_match => next_state.set(GameState::GameOver),
+        // there's nothing to quit to in a browser tab
+        GameState::Paused if input.just_pressed(KeyCode::X) && !cfg!(target_arch = "wasm32") => quit.0 = true,
+        _ => (),
+    }
+}
+
+// online both machines have to agree on ends, so those matches keep the usual ones
+fn opening_sides_swapped(settings: &Settings, mode: &GameMode) -> bool {
+    settings.left_handed && *mode != GameMode::Online
+}
+
+fn request_reset(mut requested: ResMut<ResetRequested>) {
+    requested.0 = true;
+}
+
+fn reset_requested(requested: Res<ResetRequested>) -> bool {
+    requested.0
+}
+
+pub fn reset_match(mut requested: ResMut<ResetRequested>, (mut scoreboard, mut serve, mut random, mut rally): (ResMut<Scoreboard>, ResMut<Serve>, ResMut<GameRng>, ResMut<Rally>), (rules, arena, settings, mode): (Res<MatchRules>, Res<ArenaConfig>, Res<Settings>, Res<GameMode>), mut ball: Query<(&mut Transform, &mut Velocity, &mut BallSpeed), (With<Ball>, Without<Player>, Without<Computer>, Without<Player2>)>, mut computer: Query<&mut Transform, (Or<(With<Computer>, With<Player2>)>, Without<Ball>, Without<Player>)>, mut player: Query<&mut Transform, (With<Player>, Without<Ball>, Without<Computer>, Without<Player2>)>) {
+    requested.0 = false;
+    *scoreboard = Scoreboard { sides_swapped: opening_sides_swapped(&settings, &mode), ..default() };
+    scoreboard.start_set(&rules);
+    rally.reset();
+    for (mut ball_transform, mut ball_velocity, mut ball_speed) in ball.iter_mut() {
+        center_ball(&mut ball_transform, &mut ball_velocity, &mut ball_speed);
+    }
+    serve.start(random.coin_flip());
+    for mut computer_transform in computer.iter_mut() {
+        computer_transform.translation = Vec3::new(-scoreboard.player_side() * arena.paddle_x(), 0., 0.);
+    }
+    for mut player_transform in player.iter_mut() {
+        player_transform.translation = Vec3::new(scoreboard.player_side() * arena.paddle_x(), 0., 0.);
+    }
+}
+
+// the arena and a fresh scoreboard; the paddles and hud go in after it, once it's settled which end is whose
+fn spawn_match(mut commands: Commands, mode: Res<GameMode>, rules: Res<MatchRules>, arena: Res<ArenaConfig>, theme: Res<Theme>, settings: Res<Settings>, (mut scoreboard, mut rally): (ResMut<Scoreboard>, ResMut<Rally>)) {
+    *scoreboard = Scoreboard { sides_swapped: opening_sides_swapped(&settings, &mode), ..default() };
+    scoreboard.start_set(&rules);
+    rally.reset();
+    let palette = theme.palette();
+
+    commands.spawn((
+        MatchEntity,
+        TransformBundle {
+            local: Transform::from_translation(Vec3::new(0., arena.half_height() + 10., 0.)),
+            ..default()
+        },
+        Collider::cuboid(arena.width, 10.),
+    ));
+
+    commands.spawn((
+        MatchEntity,
+        TransformBundle {
+            local: Transform::from_translation(Vec3::new(0., -arena.half_height() - 10., 0.)),
+            ..default()
+        },
+        Collider::cuboid(arena.width, 10.),
+    ));
+
+    // divider line
+    commands.spawn((
+        MatchEntity,
+        SpriteBundle {
+            sprite: Sprite {
+                color: palette.divider,
+                custom_size: Some(Vec2::new(2., arena.height)),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+}
+
+fn despawn_match(mut commands: Commands, query: Query<Entity, With<MatchEntity>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// changing the match under an online opponent would leave the two games disagreeing
+fn offline_match(world: &World) -> Result<(), String> {
+    if world.contains_resource::<NetSession>() {
+        return Err("not during an online match".to_string());
+    }
+    if !matches!(world.resource::<State<GameState>>().get(), GameState::Playing | GameState::Paused) {
+        return Err("there's no match going".to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,931 @@
+use bevy::{prelude::*, app::AppExit, input::mouse::MouseMotion};
+
+use crate::{ArenaConfig, GameMode, GameState, Handicap, MatchEntity, MatchRules, ai::{AiPersonality, Difficulty}, bindings::{BindingAction, Bindings, KeyboardLayout, RebindCapture}, crash::{CrashButton, LastCrash, crash_summary}, daily::{DailyButton, DailyChallenge, DailyRecord, daily_label}, demos::DemoPlayer, leaderboard::{Board, Leaderboard, LeaderboardButton, Score}, mixer::{AudioMixer, MixerChannel}, mods::{ModMutatorButton, ModMutators, mod_mutator_label}, mutators::{Mutator, Mutators}, navigation::MenuFocus, net::{Lobby, LobbyButton, LobbyStatus, NetSession, is_client}, profiles::{MAX_PROFILES, Profile, ProfileButton, ProfileName, Profiles, name_label, select_label}, records::Records, replay::ReplayState, score::Scoreboard, settings::Settings, skins::{PaddleSkin, PaddleSkins, SkinPreview}, stats::MatchStats, survival::SurvivalRecord, suspend::{ContinueButton, SuspendSlot}, theme::Theme};
+
+const ATTRACT_IDLE_SECONDS: f32 = 20.;
+
+const HANDICAP_SCALES: [f32; 5] = [0.6, 0.8, 1., 1.25, 1.5];
+const MAX_HEAD_START: u32 = 5;
+// the top 100 fits on screen as five columns
+const LEADERBOARD_ROWS: usize = 20;
+
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<MenuIdle>()
+        .init_resource::<MenuScreen>()
+        .add_systems(OnEnter(GameState::Menu), (show_main_screen, reset_menu_idle))
+        .add_systems(OnExit(GameState::Menu), (despawn_main_menu, spawn_demo_overlay.run_if(resource_equals(GameMode::Demo))))
+        .add_systems(OnEnter(GameState::GameOver), spawn_game_over_screen)
+        .add_systems(OnExit(GameState::GameOver), despawn_game_over_screen)
+        .add_systems(OnEnter(ReplayState::Watching), despawn_game_over_screen)
+        .add_systems(OnExit(ReplayState::Watching), spawn_game_over_screen)
+        .add_systems(Update, button_colors)
+        .add_systems(Update, (main_menu_actions, spawn_main_menu.run_if(resource_changed::<MenuScreen>().or_else(resource_changed::<Theme>())), update_option_labels, start_attract_mode.run_if(not(resource_exists::<NetSession>()))).chain().run_if(in_state(GameState::Menu)))
+        .add_systems(Update, exit_attract_mode.run_if(in_state(GameState::Playing)).run_if(resource_equals(GameMode::Demo)))
+        .add_systems(Update, game_over_actions.run_if(in_state(GameState::GameOver)).run_if(in_state(ReplayState::Off)));
+    }
+}
+
+#[derive(Component)]
+pub struct MainMenu;
+
+#[derive(Component, Clone, Copy)]
+pub enum MenuButton {
+    OnePlayer,
+    TwoPlayer,
+    Survival,
+    Practice,
+    Online,
+    MatchSetup,
+    Settings,
+    Records,
+    Leaderboard,
+    Profiles,
+    Difficulty,
+    Personality,
+    BestOf,
+    Clock,
+    PowerUps,
+    Obstacles,
+    CurveBall,
+    Streaks,
+    ServeAngle,
+    Mutator(Mutator),
+    Arena,
+    Handicaps,
+    // true for the player's side
+    Handicap(bool, HandicapOption),
+    Theme,
+    Paddles,
+    // true for the player's side
+    SkinShape(bool),
+    SkinColor(bool),
+    UiScale,
+    BallTrail,
+    TrajectoryAssist,
+    ScreenShake,
+    Rumble,
+    SpeakScore,
+    Crt,
+    Volume(MixerChannel),
+    Video,
+    WindowMode,
+    Controls,
+    ControlScheme,
+    PlayerSide,
+    StatsExport,
+    InputDelay,
+    Rebind(BindingAction),
+    Back,
+    Quit,
+}
+
+#[derive(Clone, Copy)]
+pub enum HandicapOption {
+    Size,
+    Speed,
+    HeadStart,
+}
+
+// which page of the menu is showing; the menu is rebuilt whenever this changes
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MenuScreen {
+    #[default]
+    Main,
+    MatchSetup,
+    Handicaps,
+    Settings,
+    Video,
+    Controls,
+    Paddles,
+    Records,
+    Leaderboard,
+    Online,
+    Profiles,
+    // the apology for a crash last run, before anything else
+    Crashed,
+}
+
+impl MenuScreen {
+    // where the back button goes
+    pub fn parent(&self) -> Self {
+        match self {
+            MenuScreen::Main | MenuScreen::MatchSetup | MenuScreen::Settings | MenuScreen::Records | MenuScreen::Online | MenuScreen::Profiles | MenuScreen::Crashed => MenuScreen::Main,
+            MenuScreen::Handicaps => MenuScreen::MatchSetup,
+            MenuScreen::Video | MenuScreen::Controls | MenuScreen::Paddles => MenuScreen::Settings,
+            MenuScreen::Leaderboard => MenuScreen::Records,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct MenuIdle(Timer);
+
+impl Default for MenuIdle {
+    fn default() -> Self {
+        Self(Timer::from_seconds(ATTRACT_IDLE_SECONDS, TimerMode::Once))
+    }
+}
+
+#[derive(Component)]
+pub struct DemoOverlay;
+
+#[derive(Component)]
+pub struct GameOverScreen;
+
+#[derive(Component, Clone, Copy)]
+pub enum GameOverButton {
+    Rematch,
+    Replay,
+    MainMenu,
+}
+
+// with more than one profile, the game opens on picking who's playing
+pub fn show_main_screen(mut screen: ResMut<MenuScreen>, profiles: Res<Profiles>, mut started: Local<bool>) {
+    *screen = if !*started && profiles.list.len() > 1 { MenuScreen::Profiles } else { MenuScreen::Main };
+    *started = true;
+}
+
+fn spawn_main_menu(mut commands: Commands, screen: Res<MenuScreen>, (difficulty, personality, rules, arena): (Res<Difficulty>, Res<AiPersonality>, Res<MatchRules>, Res<ArenaConfig>), (settings, theme, skins, mixer): (Res<Settings>, Res<Theme>, Res<PaddleSkins>, Res<AudioMixer>), (records, profiles, mutators, mod_mutators, daily, suspended, crash, lobby, leaderboard): (Res<Records>, Res<Profiles>, Res<Mutators>, Res<ModMutators>, Res<DailyRecord>, Res<SuspendSlot>, Res<LastCrash>, Res<Lobby>, Res<Leaderboard>), (bindings, capture, layout): (Res<Bindings>, Res<RebindCapture>, Res<KeyboardLayout>), menus: Query<Entity, With<MainMenu>>) {
+    for entity in menus.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let palette = theme.palette();
+    let title = match *screen {
+        MenuScreen::Main => "PONG",
+        MenuScreen::MatchSetup => "MATCH SETUP",
+        MenuScreen::Handicaps => "HANDICAPS",
+        MenuScreen::Settings => "SETTINGS",
+        MenuScreen::Video => "VIDEO",
+        MenuScreen::Controls => "CONTROLS",
+        MenuScreen::Paddles => "PADDLES",
+        MenuScreen::Records => "RECORDS",
+        MenuScreen::Leaderboard => "LEADERBOARD",
+        MenuScreen::Online => "LAN PLAY",
+        MenuScreen::Profiles => "PROFILES",
+        MenuScreen::Crashed => "SORRY",
+    };
+
+    commands.spawn((
+        MainMenu,
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                // long pages at a large ui scale spill into a second column instead of off the screen
+                flex_wrap: FlexWrap::Wrap,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                align_content: AlignContent::Center,
+                row_gap: Val::Px(12.),
+                column_gap: Val::Px(40.),
+                ..default()
+            },
+            background_color: palette.background.into(),
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(title, TextStyle {
+            font_size: 128.,
+            color: palette.text,
+            ..default()
+        }).with_style(Style {
+            margin: UiRect::bottom(Val::Px(40.)),
+            ..default()
+        }));
+
+        match *screen {
+            MenuScreen::Main => {
+                if suspended.0.is_some() {
+                    spawn_button(parent, "Continue", ContinueButton);
+                }
+                spawn_button(parent, "1 Player", MenuButton::OnePlayer);
+                spawn_button(parent, "2 Players", MenuButton::TwoPlayer);
+                spawn_button(parent, "Survival", MenuButton::Survival);
+                spawn_button(parent, "Practice", MenuButton::Practice);
+                spawn_button(parent, &daily_label(&daily), DailyButton);
+                // browsers can't open udp sockets
+                #[cfg(not(target_arch = "wasm32"))]
+                spawn_button(parent, "LAN Play", MenuButton::Online);
+                spawn_button(parent, "Match Setup", MenuButton::MatchSetup);
+                spawn_button(parent, "Settings", MenuButton::Settings);
+                spawn_button(parent, "Records", MenuButton::Records);
+                spawn_button(parent, &profile_label(&profiles), MenuButton::Profiles);
+                // there's nothing to quit to in a browser tab
+                #[cfg(not(target_arch = "wasm32"))]
+                spawn_button(parent, "Quit", MenuButton::Quit);
+            }
+            MenuScreen::MatchSetup => {
+                spawn_button(parent, &difficulty_label(*difficulty), MenuButton::Difficulty);
+                spawn_button(parent, &personality_label(*personality), MenuButton::Personality);
+                spawn_button(parent, &best_of_label(&rules), MenuButton::BestOf);
+                spawn_button(parent, &clock_label(&rules), MenuButton::Clock);
+                spawn_button(parent, &power_ups_label(&rules), MenuButton::PowerUps);
+                spawn_button(parent, &obstacles_label(&rules), MenuButton::Obstacles);
+                spawn_button(parent, &curve_ball_label(&rules), MenuButton::CurveBall);
+                spawn_button(parent, &streaks_label(&rules), MenuButton::Streaks);
+                spawn_button(parent, &serve_angle_label(&rules), MenuButton::ServeAngle);
+                for mutator in Mutator::ALL.into_iter().filter(|mutator| mutators.is_unlocked(*mutator)) {
+                    spawn_button(parent, &mutator_label(mutator, &mutators), MenuButton::Mutator(mutator));
+                }
+                for (index, name) in mod_mutators.names().iter().enumerate() {
+                    spawn_button(parent, &mod_mutator_label(name, &mod_mutators), ModMutatorButton(index));
+                }
+                spawn_button(parent, &arena_label(&arena), MenuButton::Arena);
+                spawn_button(parent, "Handicaps", MenuButton::Handicaps);
+                spawn_button(parent, "Back", MenuButton::Back);
+            }
+            MenuScreen::Handicaps => {
+                for player in [true, false] {
+                    for option in [HandicapOption::Size, HandicapOption::Speed, HandicapOption::HeadStart] {
+                        spawn_button(parent, &handicap_label(player, option, rules.handicap(player)), MenuButton::Handicap(player, option));
+                    }
+                }
+                spawn_button(parent, "Back", MenuButton::Back);
+            }
+            MenuScreen::Settings => {
+                for channel in [MixerChannel::Master, MixerChannel::Music, MixerChannel::Sfx] {
+                    spawn_button(parent, &volume_label(&mixer, channel), MenuButton::Volume(channel));
+                }
+                spawn_button(parent, &trajectory_assist_label(&settings), MenuButton::TrajectoryAssist);
+                spawn_button(parent, &speak_score_label(&settings), MenuButton::SpeakScore);
+                // a browser tab has nowhere to put the files
+                #[cfg(not(target_arch = "wasm32"))]
+                spawn_button(parent, &stats_export_label(&settings), MenuButton::StatsExport);
+                spawn_button(parent, "Video", MenuButton::Video);
+                spawn_button(parent, "Controls", MenuButton::Controls);
+                spawn_button(parent, "Paddles", MenuButton::Paddles);
+                spawn_button(parent, "Back", MenuButton::Back);
+            }
+            MenuScreen::Paddles => {
+                // both paddles side by side as they'll look in a match
+                parent.spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(120.),
+                        margin: UiRect::bottom(Val::Px(20.)),
+                        ..default()
+                    },
+                    ..default()
+                }).with_children(|parent| {
+                    for player in [false, true] {
+                        parent.spawn((
+                            SkinPreview(player),
+                            ImageBundle {
+                                style: Style {
+                                    width: Val::Px(17.),
+                                    height: Val::Px(120.),
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                        ));
+                    }
+                });
+                for player in [true, false] {
+                    spawn_button(parent, &skin_shape_label(player, skins.skin(player)), MenuButton::SkinShape(player));
+                    spawn_button(parent, &skin_color_label(player, skins.skin(player)), MenuButton::SkinColor(player));
+                }
+                spawn_button(parent, "Back", MenuButton::Back);
+            }
+            MenuScreen::Video => {
+                #[cfg(not(target_arch = "wasm32"))]
+                spawn_button(parent, &window_mode_label(&settings), MenuButton::WindowMode);
+                spawn_button(parent, &theme_label(*theme), MenuButton::Theme);
+                spawn_button(parent, &ui_scale_label(&settings), MenuButton::UiScale);
+                spawn_button(parent, &ball_trail_label(&settings), MenuButton::BallTrail);
+                spawn_button(parent, &screen_shake_label(&settings), MenuButton::ScreenShake);
+                spawn_button(parent, &crt_label(&settings), MenuButton::Crt);
+                spawn_button(parent, "Back", MenuButton::Back);
+            }
+            MenuScreen::Controls => {
+                spawn_button(parent, &control_scheme_label(&settings), MenuButton::ControlScheme);
+                spawn_button(parent, &player_side_label(&settings), MenuButton::PlayerSide);
+                spawn_button(parent, &rumble_label(&settings), MenuButton::Rumble);
+                for action in BindingAction::ALL {
+                    spawn_button(parent, &binding_label(action, &bindings, &capture, &layout), MenuButton::Rebind(action));
+                }
+                spawn_button(parent, "Back", MenuButton::Back);
+            }
+            MenuScreen::Records => {
+                parent.spawn(TextBundle::from_section(records_summary(&records), TextStyle {
+                    font_size: 40.,
+                    color: palette.text,
+                    ..default()
+                }).with_text_alignment(TextAlignment::Center).with_style(Style {
+                    margin: UiRect::bottom(Val::Px(40.)),
+                    ..default()
+                }));
+                spawn_button(parent, "Leaderboard", MenuButton::Leaderboard);
+                spawn_button(parent, "Back", MenuButton::Back);
+            }
+            MenuScreen::Leaderboard => {
+                parent.spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(60.),
+                        ..default()
+                    },
+                    ..default()
+                }).with_children(|parent| {
+                    for column in leaderboard_columns(&leaderboard.entries, leaderboard.board) {
+                        parent.spawn(TextBundle::from_section(column, TextStyle {
+                            font_size: 24.,
+                            color: palette.text,
+                            ..default()
+                        }));
+                    }
+                });
+                parent.spawn(TextBundle::from_section(leaderboard.status.clone(), TextStyle {
+                    font_size: 32.,
+                    color: palette.text.with_a(0.8),
+                    ..default()
+                }).with_text_alignment(TextAlignment::Center).with_style(Style {
+                    margin: UiRect::vertical(Val::Px(20.)),
+                    ..default()
+                }));
+                spawn_button(parent, &leaderboard.board.label(), LeaderboardButton::Board);
+                spawn_button(parent, "Back", MenuButton::Back);
+            }
+            MenuScreen::Profiles => {
+                if let Some(profile) = profiles.active() {
+                    parent.spawn((ProfileName, TextBundle::from_section(name_label(profile), TextStyle {
+                        font_size: 40.,
+                        color: palette.text,
+                        ..default()
+                    }).with_style(Style {
+                        margin: UiRect::bottom(Val::Px(20.)),
+                        ..default()
+                    })));
+                    parent.spawn(TextBundle::from_section(profile_summary(profile), TextStyle {
+                        font_size: 40.,
+                        color: palette.text,
+                        ..default()
+                    }).with_text_alignment(TextAlignment::Center).with_style(Style {
+                        margin: UiRect::bottom(Val::Px(40.)),
+                        ..default()
+                    }));
+                }
+                for index in 0..profiles.list.len() {
+                    spawn_button(parent, &select_label(&profiles, index), ProfileButton::Select(index));
+                }
+                if profiles.list.len() < MAX_PROFILES {
+                    spawn_button(parent, "New Profile", ProfileButton::New);
+                }
+                spawn_button(parent, "Back", MenuButton::Back);
+            }
+            MenuScreen::Online => {
+                spawn_button(parent, "Host Game", LobbyButton::Host);
+                spawn_button(parent, &lobby.join_label(), LobbyButton::Join);
+                spawn_button(parent, &input_delay_label(&settings), MenuButton::InputDelay);
+                parent.spawn((
+                    LobbyStatus,
+                    TextBundle::from_section(lobby.status.clone(), TextStyle {
+                        font_size: 32.,
+                        color: palette.muted_text,
+                        ..default()
+                    }).with_style(Style {
+                        margin: UiRect::vertical(Val::Px(20.)),
+                        ..default()
+                    }),
+                ));
+                spawn_button(parent, "Back", MenuButton::Back);
+            }
+            MenuScreen::Crashed => {
+                parent.spawn(TextBundle::from_section(crash_summary(&crash), TextStyle {
+                    font_size: 32.,
+                    color: palette.text,
+                    ..default()
+                }).with_text_alignment(TextAlignment::Center).with_style(Style {
+                    margin: UiRect::bottom(Val::Px(40.)),
+                    ..default()
+                }));
+                spawn_button(parent, "Open Report", CrashButton::Open);
+                spawn_button(parent, "Continue", CrashButton::Dismiss);
+            }
+        }
+    });
+}
+
+pub fn spawn_button(parent: &mut ChildBuilder, label: &str, action: impl Component) {
+    parent.spawn((
+        action,
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(400.),
+                height: Val::Px(64.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(2.)),
+                ..default()
+            },
+            // colored from the theme by `button_colors` as soon as it's spawned
+            border_color: Color::NONE.into(),
+            background_color: Color::NONE.into(),
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(label, TextStyle {
+            font_size: 40.,
+            color: Color::NONE,
+            ..default()
+        }));
+    });
+}
+
+fn records_summary(records: &Records) -> String {
+    let fastest_win = match records.fastest_win {
+        Some(seconds) => format!("{}:{:02}", seconds as u32 / 60, seconds as u32 % 60),
+        None => "-".to_string(),
+    };
+    let biggest_shutout = match records.biggest_shutout {
+        Some((player, computer)) => format!("{player} - {computer}"),
+        None => "-".to_string(),
+    };
+
+    [
+        format!("Longest rally  {}", records.longest_rally),
+        format!("Fastest win  {fastest_win}"),
+        format!("Biggest shutout  {biggest_shutout}"),
+        format!("Survival  {:.1}s", records.survival_time),
+    ].join("\n")
+}
+
+fn profile_label(profiles: &Profiles) -> String {
+    format!("Profile: {}", profiles.active().map_or("-", |profile| profile.name.as_str()))
+}
+
+fn profile_summary(profile: &Profile) -> String {
+    let minutes = profile.stats.seconds_played as u32 / 60;
+    [
+        format!("Matches  {}", profile.stats.matches),
+        format!("Won  {:.0}%", profile.stats.win_rate() * 100.),
+        format!("Longest rally  {}", profile.stats.longest_rally),
+        format!("Hits  {}", profile.stats.hits),
+        format!("Time played  {}:{:02}", minutes / 60, minutes % 60),
+    ].join("\n")
+}
+
+// ranked down each column in turn
+fn leaderboard_columns(entries: &[Score], board: Board) -> Vec<String> {
+    entries.chunks(LEADERBOARD_ROWS).enumerate().map(|(column, entries)| {
+        entries.iter().enumerate()
+            .map(|(row, entry)| format!("{}. {}  {}", column * LEADERBOARD_ROWS + row + 1, entry.name, board.score_label(entry.score)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }).collect()
+}
+
+fn despawn_main_menu(mut commands: Commands, query: Query<Entity, With<MainMenu>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn button_colors(mut query: Query<(&Interaction, &mut BackgroundColor, &mut BorderColor, &Children), (Changed<Interaction>, With<Button>)>, mut texts: Query<&mut Text>, theme: Res<Theme>) {
+    let palette = theme.palette();
+    for (interaction, mut color, mut border, children) in query.iter_mut() {
+        *color = match interaction {
+            Interaction::Pressed => palette.button_pressed,
+            Interaction::Hovered => palette.button_hovered,
+            Interaction::None => palette.button,
+        }.into();
+        border.0 = palette.text;
+
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                for section in text.sections.iter_mut() {
+                    section.style.color = palette.text;
+                }
+            }
+        }
+    }
+}
+
+fn difficulty_label(difficulty: Difficulty) -> String {
+    format!("Difficulty: {}", difficulty.label())
+}
+
+fn personality_label(personality: AiPersonality) -> String {
+    format!("AI: {}", personality.label())
+}
+
+fn best_of_label(rules: &MatchRules) -> String {
+    format!("Best of: {}", rules.best_of)
+}
+
+fn clock_label(rules: &MatchRules) -> String {
+    match rules.time_limit {
+        Some(minutes) => format!("Clock: {minutes} min"),
+        None => "Clock: Off".to_string(),
+    }
+}
+
+fn window_mode_label(settings: &Settings) -> String {
+    format!("Display: {}", if settings.fullscreen { "Fullscreen" } else { "Windowed" })
+}
+
+fn theme_label(theme: Theme) -> String {
+    format!("Theme: {}", theme.label())
+}
+
+fn control_scheme_label(settings: &Settings) -> String {
+    format!("Control: {}", settings.control_scheme.label())
+}
+
+fn player_side_label(settings: &Settings) -> String {
+    format!("Side: {}", if settings.left_handed { "Left" } else { "Right" })
+}
+
+fn binding_label(action: BindingAction, bindings: &Bindings, capture: &RebindCapture, layout: &KeyboardLayout) -> String {
+    if capture.0 == Some(action) {
+        format!("{}: press a key", action.label())
+    } else {
+        format!("{}: {}", action.label(), layout.binding_name(bindings, action))
+    }
+}
+
+fn stats_export_label(settings: &Settings) -> String {
+    format!("Export Stats: {}", settings.stats_export.label())
+}
+
+fn input_delay_label(settings: &Settings) -> String {
+    format!("Input Delay: {} ticks", settings.input_delay)
+}
+
+fn ui_scale_label(settings: &Settings) -> String {
+    format!("UI Scale: {:.0}%", settings.ui_scale * 100.)
+}
+
+fn ball_trail_label(settings: &Settings) -> String {
+    format!("Ball Trail: {}", if settings.ball_trail { "On" } else { "Off" })
+}
+
+fn trajectory_assist_label(settings: &Settings) -> String {
+    format!("Assist Line: {}", if settings.trajectory_assist { "On" } else { "Off" })
+}
+
+fn speak_score_label(settings: &Settings) -> String {
+    format!("Speak Score: {}", if settings.speak_score { "On" } else { "Off" })
+}
+
+fn rumble_label(settings: &Settings) -> String {
+    format!("Rumble: {}", if settings.rumble { "On" } else { "Off" })
+}
+
+fn screen_shake_label(settings: &Settings) -> String {
+    format!("Screen Shake: {}", if settings.screen_shake { "On" } else { "Off" })
+}
+
+fn crt_label(settings: &Settings) -> String {
+    format!("CRT Effect: {}", if settings.crt { "On" } else { "Off" })
+}
+
+fn volume_label(mixer: &AudioMixer, channel: MixerChannel) -> String {
+    format!("{} Volume: {:.0}%", channel.label(), mixer.level(channel) * 100.)
+}
+
+fn arena_label(arena: &ArenaConfig) -> String {
+    format!("Arena: {}", arena.label())
+}
+
+fn handicap_label(player: bool, option: HandicapOption, handicap: &Handicap) -> String {
+    let side = if player { "Player" } else { "Opponent" };
+    match option {
+        HandicapOption::Size => format!("{side} Size: {:.0}%", handicap.size * 100.),
+        HandicapOption::Speed => format!("{side} Speed: {:.0}%", handicap.speed * 100.),
+        HandicapOption::HeadStart => format!("{side} Head Start: {}", handicap.head_start),
+    }
+}
+
+fn skin_shape_label(player: bool, skin: &PaddleSkin) -> String {
+    let side = if player { "Player" } else { "Opponent" };
+    format!("{side} Shape: {}", skin.shape.label())
+}
+
+fn skin_color_label(player: bool, skin: &PaddleSkin) -> String {
+    let side = if player { "Player" } else { "Opponent" };
+    format!("{side} Color: {}", skin.color.label())
+}
+
+fn next_scale(scale: f32) -> f32 {
+    let index = HANDICAP_SCALES.iter().position(|step| *step == scale).unwrap_or(0);
+    HANDICAP_SCALES[(index + 1) % HANDICAP_SCALES.len()]
+}
+
+fn power_ups_label(rules: &MatchRules) -> String {
+    format!("Power-ups: {}", if rules.power_ups { "On" } else { "Off" })
+}
+
+fn obstacles_label(rules: &MatchRules) -> String {
+    format!("Obstacles: {}", if rules.obstacles { "On" } else { "Off" })
+}
+
+fn serve_angle_label(rules: &MatchRules) -> String {
+    format!("Serve Angle: {}", rules.serve_angle.label())
+}
+
+fn mutator_label(mutator: Mutator, mutators: &Mutators) -> String {
+    format!("{}: {}", mutator.label(), if mutators.is_enabled(mutator) { "On" } else { "Off" })
+}
+
+fn streaks_label(rules: &MatchRules) -> String {
+    format!("Streaks: {}", if rules.streaks { "On" } else { "Off" })
+}
+
+fn curve_ball_label(rules: &MatchRules) -> String {
+    format!("Curve Ball: {}", if rules.curve_ball { "On" } else { "Off" })
+}
+
+fn main_menu_actions(query: Query<(&Interaction, &MenuButton), Changed<Interaction>>, mut screen: ResMut<MenuScreen>, (mut mode, mut difficulty, mut personality, mut rules, mut arena): (ResMut<GameMode>, ResMut<Difficulty>, ResMut<AiPersonality>, ResMut<MatchRules>, ResMut<ArenaConfig>), (mut settings, mut theme, mut skins, mut mixer): (ResMut<Settings>, ResMut<Theme>, ResMut<PaddleSkins>, ResMut<AudioMixer>), (mut mutators, mut capture): (ResMut<Mutators>, ResMut<RebindCapture>), mut next_state: ResMut<NextState<GameState>>, mut exit: EventWriter<AppExit>) {
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            MenuButton::OnePlayer => {
+                *mode = GameMode::VersusComputer;
+                next_state.set(GameState::Playing);
+            }
+            MenuButton::TwoPlayer => {
+                *mode = GameMode::TwoPlayer;
+                next_state.set(GameState::Playing);
+            }
+            MenuButton::Survival => {
+                *mode = GameMode::Survival;
+                next_state.set(GameState::Playing);
+            }
+            MenuButton::Practice => {
+                *mode = GameMode::Practice;
+                next_state.set(GameState::Playing);
+            }
+            MenuButton::Online => *screen = MenuScreen::Online,
+            MenuButton::MatchSetup => *screen = MenuScreen::MatchSetup,
+            MenuButton::Handicaps => *screen = MenuScreen::Handicaps,
+            MenuButton::Settings => *screen = MenuScreen::Settings,
+            MenuButton::Video => *screen = MenuScreen::Video,
+            MenuButton::WindowMode => settings.fullscreen = !settings.fullscreen,
+            MenuButton::Controls => *screen = MenuScreen::Controls,
+            MenuButton::Paddles => *screen = MenuScreen::Paddles,
+            MenuButton::ControlScheme => settings.control_scheme = settings.control_scheme.next(),
+            MenuButton::PlayerSide => settings.left_handed = !settings.left_handed,
+            MenuButton::StatsExport => settings.stats_export = settings.stats_export.next(),
+            MenuButton::Rebind(action) => capture.0 = Some(*action),
+            MenuButton::Records => *screen = MenuScreen::Records,
+            MenuButton::Leaderboard => *screen = MenuScreen::Leaderboard,
+            MenuButton::Profiles => *screen = MenuScreen::Profiles,
+            MenuButton::Back => {
+                capture.0 = None;
+                *screen = screen.parent();
+            }
+            MenuButton::Difficulty => *difficulty = difficulty.next(),
+            MenuButton::Personality => *personality = personality.next(),
+            MenuButton::BestOf => rules.best_of = if rules.best_of >= 5 { 1 } else { rules.best_of + 2 },
+            MenuButton::Clock => rules.time_limit = match rules.time_limit {
+                None => Some(2),
+                Some(2) => Some(3),
+                Some(3) => Some(5),
+                Some(_) => None,
+            },
+            MenuButton::PowerUps => rules.power_ups = !rules.power_ups,
+            MenuButton::Obstacles => rules.obstacles = !rules.obstacles,
+            MenuButton::CurveBall => rules.curve_ball = !rules.curve_ball,
+            MenuButton::Streaks => rules.streaks = !rules.streaks,
+            MenuButton::ServeAngle => rules.serve_angle = rules.serve_angle.next(),
+            MenuButton::Mutator(mutator) => mutators.toggle(*mutator),
+            MenuButton::Arena => *arena = arena.next(),
+            MenuButton::Theme => *theme = theme.next(),
+            MenuButton::SkinShape(player) => skins.skin_mut(*player).shape = skins.skin(*player).shape.next(),
+            MenuButton::SkinColor(player) => skins.skin_mut(*player).color = skins.skin(*player).color.next(),
+            MenuButton::UiScale => settings.ui_scale = settings.next_ui_scale(),
+            MenuButton::InputDelay => settings.input_delay = settings.next_input_delay(),
+            MenuButton::BallTrail => settings.ball_trail = !settings.ball_trail,
+            MenuButton::TrajectoryAssist => settings.trajectory_assist = !settings.trajectory_assist,
+            MenuButton::ScreenShake => settings.screen_shake = !settings.screen_shake,
+            MenuButton::Rumble => settings.rumble = !settings.rumble,
+            MenuButton::SpeakScore => settings.speak_score = !settings.speak_score,
+            MenuButton::Crt => settings.crt = !settings.crt,
+            MenuButton::Volume(channel) => mixer.step(*channel),
+            MenuButton::Handicap(player, option) => {
+                let handicap = rules.handicap_mut(*player);
+                match option {
+                    HandicapOption::Size => handicap.size = next_scale(handicap.size),
+                    HandicapOption::Speed => handicap.speed = next_scale(handicap.speed),
+                    HandicapOption::HeadStart => handicap.head_start = (handicap.head_start + 1) % (MAX_HEAD_START + 1),
+                }
+            }
+            MenuButton::Quit => exit.send(AppExit),
+        }
+    }
+}
+
+fn update_option_labels((difficulty, personality, rules, arena): (Res<Difficulty>, Res<AiPersonality>, Res<MatchRules>, Res<ArenaConfig>), (settings, theme, skins, mixer): (Res<Settings>, Res<Theme>, Res<PaddleSkins>, Res<AudioMixer>), (mutators, bindings, capture, layout): (Res<Mutators>, Res<Bindings>, Res<RebindCapture>, Res<KeyboardLayout>), buttons: Query<(&MenuButton, &Children)>, mut texts: Query<&mut Text>) {
+    if !difficulty.is_changed() && !personality.is_changed() && !rules.is_changed() && !arena.is_changed() && !settings.is_changed() && !theme.is_changed() && !skins.is_changed() && !mixer.is_changed() && !mutators.is_changed() && !bindings.is_changed() && !capture.is_changed() && !layout.is_changed() {
+        return;
+    }
+
+    for (button, children) in buttons.iter() {
+        let label = match button {
+            MenuButton::Difficulty => difficulty_label(*difficulty),
+            MenuButton::Personality => personality_label(*personality),
+            MenuButton::BestOf => best_of_label(&rules),
+            MenuButton::Clock => clock_label(&rules),
+            MenuButton::PowerUps => power_ups_label(&rules),
+            MenuButton::Obstacles => obstacles_label(&rules),
+            MenuButton::CurveBall => curve_ball_label(&rules),
+            MenuButton::Streaks => streaks_label(&rules),
+            MenuButton::ServeAngle => serve_angle_label(&rules),
+            MenuButton::Mutator(mutator) => mutator_label(*mutator, &mutators),
+            MenuButton::Arena => arena_label(&arena),
+            MenuButton::WindowMode => window_mode_label(&settings),
+            MenuButton::Theme => theme_label(*theme),
+            MenuButton::SkinShape(player) => skin_shape_label(*player, skins.skin(*player)),
+            MenuButton::SkinColor(player) => skin_color_label(*player, skins.skin(*player)),
+            MenuButton::UiScale => ui_scale_label(&settings),
+            MenuButton::BallTrail => ball_trail_label(&settings),
+            MenuButton::TrajectoryAssist => trajectory_assist_label(&settings),
+            MenuButton::ScreenShake => screen_shake_label(&settings),
+            MenuButton::Rumble => rumble_label(&settings),
+            MenuButton::SpeakScore => speak_score_label(&settings),
+            MenuButton::Crt => crt_label(&settings),
+            MenuButton::ControlScheme => control_scheme_label(&settings),
+            MenuButton::PlayerSide => player_side_label(&settings),
+            MenuButton::StatsExport => stats_export_label(&settings),
+            MenuButton::InputDelay => input_delay_label(&settings),
+            MenuButton::Rebind(action) => binding_label(*action, &bindings, &capture, &layout),
+            MenuButton::Volume(channel) => volume_label(&mixer, *channel),
+            MenuButton::Handicap(player, option) => handicap_label(*player, *option, rules.handicap(*player)),
+            _ => continue,
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+}
+
+fn spawn_game_over_screen(mut commands: Commands, scoreboard: Res<Scoreboard>, rules: Res<MatchRules>, mode: Res<GameMode>, (survival, stats): (Res<SurvivalRecord>, Res<MatchStats>), (daily, demo): (Res<DailyChallenge>, Res<DemoPlayer>), theme: Res<Theme>) {
+    let (headline, final_score) = if *mode == GameMode::Survival {
+        (format!("Survived {:.1}s", survival.current), format!("Best {:.1}s", survival.best))
+    } else {
+        match_result(&scoreboard, &rules, *mode)
+    };
+    let summary = if mode.is_match() { stats_summary(&stats) } else { String::new() };
+    let palette = theme.palette();
+
+    commands.spawn((
+        GameOverScreen,
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.),
+                ..default()
+            },
+            background_color: palette.overlay.into(),
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(headline, TextStyle {
+            font_size: 96.,
+            color: palette.text,
+            ..default()
+        }));
+
+        parent.spawn(TextBundle::from_section(final_score, TextStyle {
+            font_size: 64.,
+            color: palette.text,
+            ..default()
+        }).with_text_alignment(TextAlignment::Center));
+
+        parent.spawn(TextBundle::from_section(summary, TextStyle {
+            font_size: 32.,
+            color: palette.muted_text,
+            ..default()
+        }).with_text_alignment(TextAlignment::Center).with_style(Style {
+            margin: UiRect::bottom(Val::Px(40.)),
+            ..default()
+        }));
+
+        // the daily challenge is one attempt, and a played back demo has nothing to play again on
+        if !daily.is_active() && !demo.is_playing() {
+            spawn_button(parent, "Rematch", GameOverButton::Rematch);
+        }
+        spawn_button(parent, "Watch Replay", GameOverButton::Replay);
+        spawn_button(parent, "Main Menu", GameOverButton::MainMenu);
+    });
+}
+
+fn match_result(scoreboard: &Scoreboard, rules: &MatchRules, mode: GameMode) -> (String, String) {
+    let (player_name, computer_name) = mode.side_names();
+    let headline = match scoreboard.winner(rules) {
+        Some(true) => format!("{player_name} wins!"),
+        Some(false) => format!("{computer_name} wins!"),
+        None => "Match ended".to_string(),
+    };
+
+    // a match ended from the pause menu still has a set in progress
+    let unfinished = (scoreboard.player > 0 || scoreboard.computer > 0).then_some((scoreboard.player, scoreboard.computer));
+    let mut final_score = scoreboard.sets.iter().copied().chain(unfinished)
+        .map(|(player, computer)| format!("{computer} - {player}"))
+        .collect::<Vec<_>>()
+        .join("   ");
+    if rules.uses_sets() {
+        final_score = format!("Sets {} - {}\n{final_score}", scoreboard.computer_sets, scoreboard.player_sets);
+    }
+
+    (headline, final_score)
+}
+
+fn stats_summary(stats: &MatchStats) -> String {
+    let mut lines = vec![
+        format!("Longest rally  {}", stats.longest_rally),
+        format!("Top ball speed  {:.0}", stats.max_ball_speed),
+        format!("Hits  {} - {}", stats.hits[0], stats.hits[1]),
+        format!("Aces  {} - {}", stats.aces[0], stats.aces[1]),
+        format!("Points on serve  {} - {}", stats.points_on_serve[0], stats.points_on_serve[1]),
+    ];
+    // only worth a line when it happened
+    if stats.rescues > 0 {
+        lines.push(format!("Stuck balls rescued  {}", stats.rescues));
+    }
+    lines.join("\n")
+}
+
+fn despawn_game_over_screen(mut commands: Commands, query: Query<Entity, With<GameOverScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn game_over_actions(query: Query<(&Interaction, &GameOverButton), Changed<Interaction>>, session: Option<Res<NetSession>>, mut next_state: ResMut<NextState<GameState>>, mut replay_state: ResMut<NextState<ReplayState>>) {
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            // the host starts online rematches
+            GameOverButton::Rematch if is_client(&session) => (),
+            GameOverButton::Rematch => next_state.set(GameState::Playing),
+            GameOverButton::Replay => replay_state.set(ReplayState::Watching),
+            GameOverButton::MainMenu => next_state.set(GameState::Menu),
+        }
+    }
+}
+
+fn reset_menu_idle(mut idle: ResMut<MenuIdle>) {
+    idle.0.reset();
+}
+
+fn start_attract_mode(mut idle: ResMut<MenuIdle>, time: Res<Time>, (keys, mouse_buttons, gamepad_buttons): (Res<Input<KeyCode>>, Res<Input<MouseButton>>, Res<Input<GamepadButton>>), focus: Res<MenuFocus>, mut mouse_motion: EventReader<MouseMotion>, mut mode: ResMut<GameMode>, mut next_state: ResMut<NextState<GameState>>) {
+    // a stick moving the focus around counts as well
+    let touched = keys.get_just_pressed().next().is_some() || mouse_buttons.get_just_pressed().next().is_some() || gamepad_buttons.get_just_pressed().next().is_some() || focus.is_changed() || mouse_motion.read().next().is_some();
+    if touched {
+        idle.0.reset();
+        return;
+    }
+
+    if idle.0.tick(time.delta()).just_finished() {
+        *mode = GameMode::Demo;
+        next_state.set(GameState::Playing);
+    }
+}
+
+fn spawn_demo_overlay(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        MatchEntity,
+        DemoOverlay,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                bottom: Val::Percent(15.),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn(TextBundle::from_section("Press any key", TextStyle {
+            font_size: 64.,
+            color: theme.palette().text,
+            ..default()
+        }));
+    });
+}
+
+fn exit_attract_mode(keys: Res<Input<KeyCode>>, mouse_buttons: Res<Input<MouseButton>>, gamepad_buttons: Res<Input<GamepadButton>>, mut next_state: ResMut<NextState<GameState>>) {
+    if keys.get_just_pressed().next().is_some() || mouse_buttons.get_just_pressed().next().is_some() || gamepad_buttons.get_just_pressed().next().is_some() {
+        next_state.set(GameState::Menu);
+    }
+}
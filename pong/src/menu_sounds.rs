@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+
+use crate::{menu::MenuButton, mixer::AudioMixer, navigation::MenuFocus};
+
+// the slider tick climbs with the level it's just been set to
+const SLIDER_MIN_PITCH: f32 = 0.7;
+const SLIDER_PITCH_RANGE: f32 = 0.8;
+
+pub struct MenuSoundPlugin;
+
+impl Plugin for MenuSoundPlugin {
+    fn build(&self, app: &mut App) {
+        // after the menus have acted on this frame's presses, so a volume tick plays at the new level
+        app.add_systems(PostUpdate, play_menu_sounds);
+    }
+}
+
+fn play_menu_sounds(mut commands: Commands, asset_server: Res<AssetServer>, mixer: Res<AudioMixer>, focus: Res<MenuFocus>, buttons: Query<(Entity, &Interaction, Option<&MenuButton>), (Changed<Interaction>, With<Button>)>, mut last_focus: Local<Option<Entity>>) {
+    let mut play = |sound: &'static str, pitch: f32| {
+        commands.spawn(AudioBundle {
+            source: asset_server.load(sound),
+            settings: mixer.sfx().with_speed(pitch),
+        });
+    };
+
+    for (entity, interaction, button) in buttons.iter() {
+        match (interaction, button) {
+            (Interaction::Pressed, Some(MenuButton::Back)) => play("sounds/ui_back.wav", 1.),
+            (Interaction::Pressed, Some(MenuButton::Volume(channel))) => play("sounds/ui_slider.wav", SLIDER_MIN_PITCH + mixer.level(*channel) * SLIDER_PITCH_RANGE),
+            (Interaction::Pressed, _) => play("sounds/ui_confirm.wav", 1.),
+            // the mouse settling back on a button it just clicked isn't a move
+            (Interaction::Hovered, _) if *last_focus != Some(entity) => {
+                *last_focus = Some(entity);
+                play("sounds/ui_focus.wav", 1.);
+            }
+            _ => (),
+        }
+    }
+
+    if let Some(button) = focus.button {
+        if *last_focus != Some(button) {
+            *last_focus = Some(button);
+            play("sounds/ui_focus.wav", 1.);
+        }
+    }
+}
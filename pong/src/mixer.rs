@@ -0,0 +1,71 @@
+use bevy::{prelude::*, audio::Volume};
+
+use serde::{Deserialize, Serialize};
+
+const VOLUME_STEP: f32 = 0.1;
+
+// every sound in the game is played at one of these levels
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioMixer {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Default for AudioMixer {
+    fn default() -> Self {
+        Self { master: 1., music: 0.5, sfx: 1. }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MixerChannel {
+    Master,
+    Music,
+    Sfx,
+}
+
+impl MixerChannel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MixerChannel::Master => "Master",
+            MixerChannel::Music => "Music",
+            MixerChannel::Sfx => "SFX",
+        }
+    }
+}
+
+impl AudioMixer {
+    pub fn level(&self, channel: MixerChannel) -> f32 {
+        match channel {
+            MixerChannel::Master => self.master,
+            MixerChannel::Music => self.music,
+            MixerChannel::Sfx => self.sfx,
+        }
+    }
+
+    // steps up a notch, wrapping back round to silent after full volume
+    pub fn step(&mut self, channel: MixerChannel) {
+        let level = match channel {
+            MixerChannel::Master => &mut self.master,
+            MixerChannel::Music => &mut self.music,
+            MixerChannel::Sfx => &mut self.sfx,
+        };
+        let next = ((*level + VOLUME_STEP) / VOLUME_STEP).round() * VOLUME_STEP;
+        *level = if next > 1. + VOLUME_STEP / 2. { 0. } else { next.min(1.) };
+    }
+
+    pub fn music_volume(&self) -> f32 {
+        self.master * self.music
+    }
+
+    pub fn sfx_volume(&self) -> f32 {
+        self.master * self.sfx
+    }
+
+    // for one-shot effects, which despawn once they finish
+    pub fn sfx(&self) -> PlaybackSettings {
+        PlaybackSettings::DESPAWN.with_volume(Volume::new_relative(self.sfx_volume()))
+    }
+}
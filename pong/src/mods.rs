@@ -0,0 +1,215 @@
+#[cfg(feature = "dynamic_mods")]
+use std::{ffi::{CStr, c_char}, path::Path};
+
+use bevy::prelude::*;
+
+use crate::{GameSet, GameState, Velocity, ball::{Ball, ball_collision}, menu::MenuScreen, net::NetSession};
+
+// how hard the gravity mutator pulls the ball down, in units per second per second
+const GRAVITY: f32 = 900.;
+#[cfg(feature = "dynamic_mods")]
+const MODS_DIR: &str = "mods";
+// what a mod library exports, all `extern "C"` so it doesn't have to be built by the same compiler against the same pong and bevy, or even in rust:
+// `pong_mod_api() -> u32` giving the MOD_API it was written for, `pong_mod_name() -> *const c_char` and `pong_mod_update(balls: *mut ModBall, count: usize, seconds: f32)`
+#[cfg(feature = "dynamic_mods")]
+const API_SYMBOL: &[u8] = b"pong_mod_api";
+#[cfg(feature = "dynamic_mods")]
+const NAME_SYMBOL: &[u8] = b"pong_mod_name";
+#[cfg(feature = "dynamic_mods")]
+const UPDATE_SYMBOL: &[u8] = b"pong_mod_update";
+// goes up whenever ModBall or the entry points change, so a library written for another version is turned away instead of misread
+#[cfg(feature = "dynamic_mods")]
+pub const MOD_API: u32 = 1;
+
+pub struct ModPlugin;
+
+impl Plugin for ModPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<ModMutators>()
+        .add_systems(Update, mod_mutator_actions.run_if(in_state(GameState::Menu)))
+        .add_mod_mutator(Gravity);
+
+        #[cfg(feature = "dynamic_mods")]
+        load_library_mods(app);
+    }
+}
+
+// a twist on a match from outside the core game, shown as a toggle on the match setup page;
+// whatever it adds in `build` should run_if `mod_mutator_active(name)` so that toggle turns it on and off
+pub trait MutatorMod: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+    fn build(&self, app: &mut App);
+}
+
+// every mod mutator in the order they were added, and the ones switched on; off again each run, like the hidden mutators
+#[derive(Resource, Default)]
+pub struct ModMutators {
+    names: Vec<&'static str>,
+    enabled: Vec<&'static str>,
+}
+
+impl ModMutators {
+    pub fn names(&self) -> &[&'static str] {
+        &self.names
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.enabled.contains(&name)
+    }
+
+    pub fn toggle(&mut self, index: usize) {
+        let name = if let Some(name) = self.names.get(index) { *name } else { return; };
+        if self.is_enabled(name) {
+            self.enabled.retain(|enabled| *enabled != name);
+        } else {
+            self.enabled.push(name);
+        }
+    }
+}
+
+// online both games have to play by the same rules, and there's no knowing the other one has the same mods
+pub fn mod_mutator_active(name: &'static str) -> impl Fn(Res<ModMutators>, Option<Res<NetSession>>) -> bool {
+    move |mutators: Res<ModMutators>, session: Option<Res<NetSession>>| session.is_none() && mutators.is_enabled(name)
+}
+
+pub trait ModAppExt {
+    fn add_mod_mutator(&mut self, mutator: impl MutatorMod) -> &mut Self;
+}
+
+impl ModAppExt for App {
+    fn add_mod_mutator(&mut self, mutator: impl MutatorMod) -> &mut Self {
+        register(self, &mutator);
+        self
+    }
+}
+
+fn register(app: &mut App, mutator: &dyn MutatorMod) {
+    app.init_resource::<ModMutators>();
+    mutator.build(app);
+    app.world.resource_mut::<ModMutators>().names.push(mutator.name());
+}
+
+// a ball as a library mod sees it every frame, free to move it and change how it's heading
+#[cfg(feature = "dynamic_mods")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct ModBall {
+    pub position: [f32; 2],
+    pub velocity: [f32; 2],
+}
+
+#[cfg(feature = "dynamic_mods")]
+#[derive(Debug, thiserror::Error)]
+pub enum ModLoadError {
+    #[error("{0}")]
+    Library(#[from] libloading::Error),
+    #[error("it was written for mod api {0}, not {MOD_API}")]
+    Api(u32),
+    #[error("its name isn't utf-8")]
+    Name,
+}
+
+#[derive(Component)]
+pub struct ModMutatorButton(pub usize);
+
+pub fn mod_mutator_label(name: &str, mutators: &ModMutators) -> String {
+    format!("{name}: {}", if mutators.is_enabled(name) { "On" } else { "Off" })
+}
+
+fn mod_mutator_actions(query: Query<(&Interaction, &ModMutatorButton), Changed<Interaction>>, mut mutators: ResMut<ModMutators>, mut screen: ResMut<MenuScreen>) {
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        mutators.toggle(button.0);
+        // rebuilt to show the new choice
+        screen.set_changed();
+    }
+}
+
+// every library in the mods folder of the data directory, each adding one mutator
+#[cfg(feature = "dynamic_mods")]
+fn load_library_mods(app: &mut App) {
+    let entries = if let Ok(entries) = std::fs::read_dir(crate::storage::path(MODS_DIR)) { entries } else { return; };
+    let libraries = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).filter(|path| path.extension().is_some_and(|extension| extension == std::env::consts::DLL_EXTENSION));
+
+    for path in libraries {
+        // there's no checking what a library does once it's loaded; putting it in the mods folder is the player trusting it
+        match unsafe { load_library(&path) } {
+            Ok(mutator) => {
+                info!("loaded the {} mod from {}", mutator.name(), path.display());
+                app.add_mod_mutator(mutator);
+            }
+            Err(error) => warn!("couldn't load the mod {}: {error}", path.display()),
+        }
+    }
+}
+
+/// # Safety
+///
+/// The library's initialisers run as it loads, and its entry points are called with the signatures above without any way to check them.
+/// `path` has to be a library that really exports them, with `pong_mod_name` returning a nul-terminated string that stays valid while the game runs.
+#[cfg(feature = "dynamic_mods")]
+pub unsafe fn load_library(path: &Path) -> Result<LibraryMod, ModLoadError> {
+    let library = libloading::Library::new(path)?;
+    let api = library.get::<extern "C" fn() -> u32>(API_SYMBOL)?();
+    if api != MOD_API {
+        return Err(ModLoadError::Api(api));
+    }
+    let name = CStr::from_ptr(library.get::<extern "C" fn() -> *const c_char>(NAME_SYMBOL)?()).to_str().map_err(|_| ModLoadError::Name)?;
+    let update = *library.get::<extern "C" fn(*mut ModBall, usize, f32)>(UPDATE_SYMBOL)?;
+    // its code has to stay loaded for as long as the game runs
+    std::mem::forget(library);
+    Ok(LibraryMod { name: Box::leak(name.to_string().into_boxed_str()), update })
+}
+
+// a mutator from a library, handed every ball in play once a frame before they're swept into anything
+#[cfg(feature = "dynamic_mods")]
+pub struct LibraryMod {
+    name: &'static str,
+    update: extern "C" fn(*mut ModBall, usize, f32),
+}
+
+#[cfg(feature = "dynamic_mods")]
+impl MutatorMod for LibraryMod {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn build(&self, app: &mut App) {
+        let update = self.update;
+        let update_balls = move |time: Res<Time>, mut balls: Query<(&mut Transform, &mut Velocity), With<Ball>>| {
+            let mut mod_balls: Vec<ModBall> = balls.iter().map(|(transform, velocity)| ModBall { position: transform.translation.truncate().to_array(), velocity: velocity.0.to_array() }).collect();
+            update(mod_balls.as_mut_ptr(), mod_balls.len(), time.delta_seconds());
+            for ((mut transform, mut velocity), ball) in balls.iter_mut().zip(mod_balls) {
+                transform.translation = Vec2::from_array(ball.position).extend(transform.translation.z);
+                velocity.0 = Vec2::from_array(ball.velocity);
+            }
+        };
+        app.add_systems(FixedUpdate, update_balls.before(ball_collision).run_if(mod_mutator_active(self.name())).in_set(GameSet::Physics));
+    }
+}
+
+// built in, and added the same way a mod's mutator is
+struct Gravity;
+
+impl MutatorMod for Gravity {
+    fn name(&self) -> &'static str {
+        "Gravity"
+    }
+
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, pull_balls_down.before(ball_collision).run_if(mod_mutator_active(self.name())).in_set(GameSet::Physics));
+    }
+}
+
+fn pull_balls_down(time: Res<Time>, mut balls: Query<&mut Velocity, With<Ball>>) {
+    for mut velocity in balls.iter_mut() {
+        // a ball waiting to be served stays where it is
+        if velocity.0 != Vec2::ZERO {
+            velocity.0.y -= GRAVITY * time.delta_seconds();
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{GameSet, paddle::{HumanControlled, PaddleMotion, Player, human_movement_control}, settings::mouse_control_enabled, ui::MainCamera};
+
+pub struct MousePlugin;
+
+impl Plugin for MousePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(FixedUpdate, mouse_paddle.after(human_movement_control).run_if(mouse_control_enabled).in_set(GameSet::Input));
+    }
+}
+
+// player one's paddle heads for the cursor's height, capped at the usual paddle speed
+pub fn mouse_paddle(windows: Query<&Window, With<PrimaryWindow>>, cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>, mut paddles: Query<(&mut PaddleMotion, &Transform), (With<Player>, With<HumanControlled>)>) {
+    let window = if let Ok(window) = windows.get_single() { window } else { return; };
+    let (camera, camera_transform) = if let Ok(camera) = cameras.get_single() { camera } else { return; };
+    let cursor = if let Some(cursor) = window.cursor_position().and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor)) { cursor } else { return; };
+
+    for (mut motion, transform) in paddles.iter_mut() {
+        motion.follow(cursor.y - transform.translation.y);
+    }
+}
@@ -0,0 +1,109 @@
+use bevy::{prelude::*, audio::{GlobalVolume, Volume}};
+
+use crate::{GameState, MatchRules, mixer::AudioMixer, score::Scoreboard};
+
+const CROSSFADE_SECONDS: f32 = 1.5;
+// the game keeps playing quietly under the pause screen
+const PAUSED_LEVEL: f32 = 0.35;
+// how much faster the gameplay track runs once someone is a point from the set
+const MATCH_POINT_SPEED: f32 = 1.12;
+
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<MusicController>()
+        .add_systems(Startup, spawn_music)
+        .add_systems(Update, (choose_track, crossfade).chain());
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MusicTrack {
+    Menu,
+    Gameplay,
+}
+
+impl MusicTrack {
+    fn path(&self) -> &'static str {
+        match self {
+            MusicTrack::Menu => "music/menu.wav",
+            MusicTrack::Gameplay => "music/gameplay.wav",
+        }
+    }
+}
+
+// what the music should be doing; `crossfade` eases every channel toward it
+#[derive(Resource)]
+pub struct MusicController {
+    pub track: MusicTrack,
+    // 0 to 1, scaled by the mixer's music volume
+    pub level: f32,
+    pub speed: f32,
+}
+
+impl Default for MusicController {
+    fn default() -> Self {
+        Self { track: MusicTrack::Menu, level: 1., speed: 1. }
+    }
+}
+
+// a looping track that stays alive for the whole session, faded in and out rather than respawned
+#[derive(Component)]
+pub struct MusicChannel {
+    pub track: MusicTrack,
+    pub fade: f32,
+}
+
+fn spawn_music(mut commands: Commands, asset_server: Res<AssetServer>) {
+    for track in [MusicTrack::Menu, MusicTrack::Gameplay] {
+        commands.spawn((
+            MusicChannel { track, fade: 0. },
+            AudioBundle {
+                source: asset_server.load(track.path()),
+                settings: PlaybackSettings::LOOP.with_volume(Volume::new_relative(0.)).paused(),
+            },
+        ));
+    }
+}
+
+fn choose_track(mut controller: ResMut<MusicController>, state: Res<State<GameState>>, scoreboard: Res<Scoreboard>, rules: Res<MatchRules>) {
+    let (track, level) = match state.get() {
+        GameState::Menu => (MusicTrack::Menu, 1.),
+        GameState::Playing | GameState::GameOver => (MusicTrack::Gameplay, 1.),
+        GameState::Paused => (MusicTrack::Gameplay, PAUSED_LEVEL),
+    };
+
+    let set_point = rules.point_target.saturating_sub(1);
+    let on_match_point = *state.get() == GameState::Playing
+        && rules.time_limit.is_none()
+        && (scoreboard.player == set_point || scoreboard.computer == set_point);
+    let speed = if on_match_point { MATCH_POINT_SPEED } else { 1. };
+
+    if controller.track != track || controller.level != level || controller.speed != speed {
+        *controller = MusicController { track, level, speed };
+    }
+}
+
+fn crossfade(controller: Res<MusicController>, mixer: Res<AudioMixer>, global_volume: Res<GlobalVolume>, mut channels: Query<(&mut MusicChannel, &AudioSink)>, time: Res<Time>) {
+    let step = time.delta_seconds() / CROSSFADE_SECONDS;
+
+    for (mut channel, sink) in channels.iter_mut() {
+        let target = if channel.track == controller.track { controller.level } else { 0. };
+        channel.fade += (target - channel.fade).clamp(-step, step);
+
+        // the global volume only reaches sounds as they start, and these never stop
+        sink.set_volume(channel.fade * mixer.music_volume() * global_volume.volume.get());
+        if channel.track == controller.track && sink.speed() != controller.speed {
+            sink.set_speed(controller.speed);
+        }
+
+        // silent tracks stop where they are and pick back up when faded in again
+        if channel.fade > 0. && sink.is_paused() {
+            sink.play();
+        } else if channel.fade == 0. && !sink.is_paused() {
+            sink.pause();
+        }
+    }
+}
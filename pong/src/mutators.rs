@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Collider, GameState, ball::{Ball, BallCollided}, menu::MenuScreen, mixer::AudioMixer, net::NetSession, paddle::Paddle};
+
+const GIANT_BALL_SCALE: f32 = 3.;
+// how long an invisible paddle shows itself after touching the ball
+const REVEAL_SECONDS: f32 = 0.25;
+
+const KONAMI_CODE: [KeyCode; 10] = [KeyCode::Up, KeyCode::Up, KeyCode::Down, KeyCode::Down, KeyCode::Left, KeyCode::Right, KeyCode::Left, KeyCode::Right, KeyCode::B, KeyCode::A];
+const GHOST_CODE: [KeyCode; 5] = [KeyCode::G, KeyCode::H, KeyCode::O, KeyCode::S, KeyCode::T];
+
+pub struct MutatorPlugin;
+
+impl Plugin for MutatorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Mutators>()
+        .add_systems(Update, enter_codes.run_if(in_state(GameState::Menu)))
+        // online both games have to play by the same rules, and there's no sharing these
+        .add_systems(Update, (
+            grow_balls.run_if(mutator_enabled(Mutator::GiantBall)),
+            (hide_paddles, reveal_paddles).chain().run_if(mutator_enabled(Mutator::InvisiblePaddles)),
+        ).run_if(not(resource_exists::<NetSession>())).run_if(in_state(GameState::Playing)));
+    }
+}
+
+// silly twists on a match, each one hidden until its code is entered on the menu
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Mutator {
+    GiantBall,
+    InvisiblePaddles,
+}
+
+impl Mutator {
+    pub const ALL: [Mutator; 2] = [Mutator::GiantBall, Mutator::InvisiblePaddles];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Mutator::GiantBall => "Giant Ball",
+            Mutator::InvisiblePaddles => "Invisible Paddles",
+        }
+    }
+
+    fn code(&self) -> &'static [KeyCode] {
+        match self {
+            Mutator::GiantBall => &KONAMI_CODE,
+            Mutator::InvisiblePaddles => &GHOST_CODE,
+        }
+    }
+}
+
+// only for this session; the codes are half the fun
+#[derive(Resource, Default)]
+pub struct Mutators {
+    unlocked: Vec<Mutator>,
+    enabled: Vec<Mutator>,
+    // how far into each code the last few key presses got, in the order of `Mutator::ALL`
+    progress: [usize; Mutator::ALL.len()],
+}
+
+impl Mutators {
+    pub fn is_unlocked(&self, mutator: Mutator) -> bool {
+        self.unlocked.contains(&mutator)
+    }
+
+    pub fn is_enabled(&self, mutator: Mutator) -> bool {
+        self.enabled.contains(&mutator)
+    }
+
+    pub fn enabled(&self) -> &[Mutator] {
+        &self.enabled
+    }
+
+    // for played back matches, which have to have whatever the original had on whether it's unlocked here or not
+    pub fn replace_enabled(&mut self, enabled: Vec<Mutator>) -> Vec<Mutator> {
+        std::mem::replace(&mut self.enabled, enabled)
+    }
+
+    pub fn toggle(&mut self, mutator: Mutator) {
+        if self.is_enabled(mutator) {
+            self.enabled.retain(|enabled| *enabled != mutator);
+        } else if self.is_unlocked(mutator) {
+            self.enabled.push(mutator);
+        }
+    }
+}
+
+pub fn mutator_enabled(mutator: Mutator) -> impl Fn(Res<Mutators>) -> bool {
+    move |mutators: Res<Mutators>| mutators.is_enabled(mutator)
+}
+
+#[derive(Component)]
+pub struct Grown;
+
+#[derive(Component)]
+struct Reveal(Timer);
+
+// a wrong key starts the code over, or counts as its first key if it is one
+fn enter_codes(input: Res<Input<KeyCode>>, mut mutators: ResMut<Mutators>, mut screen: ResMut<MenuScreen>, mut commands: Commands, asset_server: Res<AssetServer>, mixer: Res<AudioMixer>) {
+    for key in input.get_just_pressed() {
+        for (index, mutator) in Mutator::ALL.into_iter().enumerate() {
+            let code = mutator.code();
+            let progress = mutators.progress[index];
+            mutators.progress[index] = if code[progress] == *key { progress + 1 } else if code[0] == *key { 1 } else { 0 };
+
+            if mutators.progress[index] < code.len() {
+                continue;
+            }
+            mutators.progress[index] = 0;
+            if mutators.is_unlocked(mutator) {
+                continue;
+            }
+
+            mutators.unlocked.push(mutator);
+            mutators.enabled.push(mutator);
+            commands.spawn(AudioBundle {
+                source: asset_server.load("sounds/match_point.wav"),
+                settings: mixer.sfx(),
+            });
+            // shows up on the match setup page straight away
+            screen.set_changed();
+        }
+    }
+}
+
+pub fn grow_balls(mut commands: Commands, mut balls: Query<(Entity, &mut Transform, &mut Collider), (With<Ball>, Without<Grown>)>) {
+    for (ball, mut transform, mut collider) in balls.iter_mut() {
+        transform.scale *= GIANT_BALL_SCALE;
+        collider.0 *= GIANT_BALL_SCALE;
+        commands.entity(ball).insert(Grown);
+    }
+}
+
+fn hide_paddles(mut paddles: Query<&mut Visibility, (With<Paddle>, Without<Reveal>)>) {
+    for mut visibility in paddles.iter_mut() {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+// a glimpse of the paddle that just hit the ball, so it's not entirely guesswork
+fn reveal_paddles(mut commands: Commands, mut events: EventReader<BallCollided>, mut paddles: Query<(Entity, &mut Visibility, Option<&mut Reveal>), With<Paddle>>, time: Res<Time>) {
+    for event in events.read().filter(|event| event.is_paddle_hit()) {
+        if let Ok((paddle, mut visibility, _)) = paddles.get_mut(event.other) {
+            *visibility = Visibility::Visible;
+            commands.entity(paddle).insert(Reveal(Timer::from_seconds(REVEAL_SECONDS, TimerMode::Once)));
+        }
+    }
+
+    for (paddle, _, reveal) in paddles.iter_mut() {
+        if let Some(mut reveal) = reveal {
+            if reveal.0.tick(time.delta()).finished() {
+                commands.entity(paddle).remove::<Reveal>();
+            }
+        }
+    }
+}
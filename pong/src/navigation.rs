@@ -0,0 +1,161 @@
+use bevy::{prelude::*, window::CursorMoved};
+
+use crate::{GameState, menu::MenuButton, theme::Theme};
+
+// how far a stick has to lean before it counts as a press
+const STICK_THRESHOLD: f32 = 0.5;
+const REPEAT_DELAY: f32 = 0.4;
+const REPEAT_INTERVAL: f32 = 0.15;
+// sideways distance counts this much more than distance along the direction, so moving down a column stays in it
+const CROSS_AXIS_WEIGHT: f32 = 2.;
+const OUTLINE_WIDTH: f32 = 4.;
+
+pub struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<MenuFocus>()
+        .add_systems(Update, (release_pressed, hide_focus_for_mouse, navigate_buttons, press_focused, outline_focus).chain().run_if(in_state(GameState::Menu).or_else(in_state(GameState::GameOver))))
+        .add_systems(OnExit(GameState::Menu), clear_focus)
+        .add_systems(OnExit(GameState::GameOver), clear_focus);
+    }
+}
+
+// the button a controller is on; nothing until a controller moves it, and the mouse takes over again as soon as it moves
+#[derive(Resource, Default)]
+pub struct MenuFocus {
+    pub button: Option<Entity>,
+    outlined: Option<Entity>,
+    pressed: Option<Entity>,
+}
+
+#[derive(Default)]
+struct HeldDirection {
+    direction: Option<Vec2>,
+    timer: Timer,
+}
+
+fn gamepad_direction(gamepads: &Gamepads, buttons: &Input<GamepadButton>, axes: &Axis<GamepadAxis>) -> Option<Vec2> {
+    for gamepad in gamepads.iter() {
+        let dpad = [
+            (GamepadButtonType::DPadUp, Vec2::NEG_Y),
+            (GamepadButtonType::DPadDown, Vec2::Y),
+            (GamepadButtonType::DPadLeft, Vec2::NEG_X),
+            (GamepadButtonType::DPadRight, Vec2::X),
+        ];
+        if let Some((_, direction)) = dpad.into_iter().find(|(button, _)| buttons.pressed(GamepadButton::new(gamepad, *button))) {
+            return Some(direction);
+        }
+
+        // ui space runs down the screen, the stick up it
+        let stick = Vec2::new(
+            axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX)).unwrap_or(0.),
+            -axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY)).unwrap_or(0.),
+        );
+        if stick.abs().max_element() > STICK_THRESHOLD {
+            return Some(if stick.x.abs() > stick.y.abs() { Vec2::new(stick.x.signum(), 0.) } else { Vec2::new(0., stick.y.signum()) });
+        }
+    }
+    None
+}
+
+fn any_just_pressed(gamepads: &Gamepads, buttons: &Input<GamepadButton>, button: GamepadButtonType) -> bool {
+    gamepads.iter().any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button)))
+}
+
+// a pressed button gets one frame to be acted on, then goes back to how the mouse would leave it
+fn release_pressed(mut focus: ResMut<MenuFocus>, mut interactions: Query<&mut Interaction>) {
+    let pressed = if let Some(pressed) = focus.pressed { pressed } else { return; };
+    focus.pressed = None;
+    if let Ok(mut interaction) = interactions.get_mut(pressed) {
+        if *interaction == Interaction::Pressed {
+            *interaction = Interaction::None;
+        }
+    }
+}
+
+fn hide_focus_for_mouse(mut cursor: EventReader<CursorMoved>, mut focus: ResMut<MenuFocus>) {
+    if cursor.read().next().is_some() && focus.button.is_some() {
+        focus.button = None;
+    }
+}
+
+fn navigate_buttons(mut focus: ResMut<MenuFocus>, gamepads: Res<Gamepads>, buttons: Res<Input<GamepadButton>>, axes: Res<Axis<GamepadAxis>>, time: Res<Time>, mut held: Local<HeldDirection>, nodes: Query<(Entity, &GlobalTransform, &ViewVisibility), With<Button>>) {
+    let direction = gamepad_direction(&gamepads, &buttons, &axes);
+    let step = match direction {
+        Some(direction) if held.direction != Some(direction) => {
+            held.timer = Timer::from_seconds(REPEAT_DELAY, TimerMode::Once);
+            Some(direction)
+        }
+        // held long enough to start repeating
+        Some(direction) if held.timer.tick(time.delta()).finished() => {
+            held.timer = Timer::from_seconds(REPEAT_INTERVAL, TimerMode::Once);
+            Some(direction)
+        }
+        _ => None,
+    };
+    held.direction = direction;
+
+    let visible: Vec<(Entity, Vec2)> = nodes.iter()
+        .filter(|(_, _, visibility)| visibility.get())
+        .map(|(entity, transform, _)| (entity, transform.translation().truncate()))
+        .collect();
+    let current = focus.button.and_then(|button| visible.iter().find(|(entity, _)| *entity == button)).map(|(_, position)| *position);
+    // the first button on a fresh screen, or after the old one went away
+    let first = || visible.iter().min_by(|a, b| a.1.y.total_cmp(&b.1.y).then(a.1.x.total_cmp(&b.1.x))).map(|(entity, _)| *entity);
+
+    match (current, step) {
+        (None, Some(_)) => focus.button = first(),
+        // a screen change took the focused button with it
+        (None, None) if focus.button.is_some() => focus.button = first(),
+        (Some(current), Some(direction)) => {
+            let next = visible.iter()
+                .filter_map(|(entity, position)| {
+                    let offset = *position - current;
+                    let along = offset.dot(direction);
+                    (along > 0.).then(|| (*entity, along + offset.perp_dot(direction).abs() * CROSS_AXIS_WEIGHT))
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((next, _)) = next {
+                focus.button = Some(next);
+            }
+        }
+        _ => (),
+    }
+}
+
+// south is confirm and east backs out, the way most controllers label them
+fn press_focused(mut focus: ResMut<MenuFocus>, gamepads: Res<Gamepads>, buttons: Res<Input<GamepadButton>>, mut interactions: Query<(Entity, &mut Interaction, &ViewVisibility, Option<&MenuButton>), With<Button>>) {
+    let target = if any_just_pressed(&gamepads, &buttons, GamepadButtonType::South) {
+        focus.button
+    } else if any_just_pressed(&gamepads, &buttons, GamepadButtonType::East) {
+        interactions.iter().find(|(_, _, visibility, button)| visibility.get() && matches!(button, Some(MenuButton::Back))).map(|(entity, ..)| entity)
+    } else {
+        None
+    };
+
+    let target = if let Some(target) = target { target } else { return; };
+    if let Ok((_, mut interaction, _, _)) = interactions.get_mut(target) {
+        *interaction = Interaction::Pressed;
+        focus.pressed = Some(target);
+    }
+}
+
+fn outline_focus(mut commands: Commands, mut focus: ResMut<MenuFocus>, theme: Res<Theme>) {
+    if focus.outlined == focus.button {
+        return;
+    }
+
+    if let Some(mut entity) = focus.outlined.and_then(|outlined| commands.get_entity(outlined)) {
+        entity.remove::<Outline>();
+    }
+    if let Some(mut entity) = focus.button.and_then(|button| commands.get_entity(button)) {
+        entity.insert(Outline::new(Val::Px(OUTLINE_WIDTH), Val::Px(OUTLINE_WIDTH), theme.palette().text));
+    }
+    focus.outlined = focus.button;
+}
+
+fn clear_focus(mut focus: ResMut<MenuFocus>) {
+    *focus = MenuFocus::default();
+}
@@ -0,0 +1,628 @@
+use std::{collections::VecDeque, io, net::{IpAddr, SocketAddr, ToSocketAddrs, UdpSocket}};
+
+use bevy::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ArenaConfig, GameMode, GameSet, GameState, MatchRules, Velocity, ball::{Ball, BallCollided, BallDestroyed, BallSpeed, Serve, ball_bundle, spawn_ball}, gameplay::{GameplayConfig, GameplayOverride, GameplayRuntimeLayer}, menu::MenuScreen, mouse::mouse_paddle, overtime::Overtime, paddle::{HumanControlled, Paddle, PaddleMotion, PaddleSize, human_movement_control}, reset_match, rng::{GameRng, restart_rng}, score::{Rally, Scoreboard, check_winner}, settings::{MAX_INPUT_DELAY, Settings}, stats::MatchStats, touch::touch_paddle, watchdog::{BallRescued, StuckTimers}};
+
+const NET_PORT: u16 = 7777;
+// seconds without hearing from the other side before giving up on them
+const NET_TIMEOUT: f32 = 5.;
+const JOIN_INTERVAL: f32 = 0.5;
+const MAX_PACKET_SIZE: usize = 8192;
+const MAX_ADDRESS_LENGTH: usize = 64;
+// how many ticks play can get ahead of the other machine's inputs before it waits for them
+const MAX_ROLLBACK: u64 = 12;
+// ticks kept to go back to; a change of state from the host can land up to an input delay further back than the newest guess
+const HISTORY_TICKS: u64 = MAX_ROLLBACK + MAX_INPUT_DELAY as u64;
+
+// both machines play the whole match from the same seed, each sending the other its paddle's input for every tick;
+// the other side's input is guessed until it arrives, and a wrong guess sends play back to that tick to be played again on the real one
+pub struct NetPlugin;
+
+impl Plugin for NetPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Lobby>()
+        .add_event::<NetPacket>()
+        .add_systems(OnEnter(GameState::Menu), leave_session.run_if(resource_exists::<NetSession>()))
+        // once the seed's picked, and before anything's served from it
+        .add_systems(OnExit(GameState::Menu), start_round.after(restart_rng).before(spawn_ball).run_if(resource_exists::<NetSession>()))
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, start_round.after(restart_rng).before(reset_match).run_if(resource_exists::<NetSession>()))
+        .add_systems(StateTransition, note_state_change.after(apply_state_transition::<GameState>).run_if(state_changed::<GameState>()).run_if(resource_exists::<NetSession>()))
+        .add_systems(Update, (close_lobby.run_if(resource_changed::<MenuScreen>()), lobby_actions, type_address.run_if(resource_equals(MenuScreen::Online)).run_if(not(resource_exists::<NetSession>())), update_lobby_labels.run_if(resource_changed::<Lobby>())).chain().run_if(in_state(GameState::Menu)))
+        .add_systems(Update, drop_host_gameplay.run_if(resource_exists::<HostGameplay>()).run_if(not(resource_exists::<NetSession>())))
+        // before the frame's ticks, so they carry on from wherever the newest inputs put the match
+        .add_systems(PreUpdate, (
+            poll_socket,
+            (accept_player, welcome_received, leave_received, call_host, receive_inputs, follow_host),
+            roll_back,
+        ).chain().run_if(resource_exists::<NetSession>()))
+        // after every controller has had its say
+        .add_systems(FixedUpdate, start_tick.after(human_movement_control).after(mouse_paddle).after(touch_paddle).run_if(resource_exists::<NetSession>()).in_set(GameSet::Input))
+        .add_systems(FixedUpdate, hold_state_change.after(check_winner).run_if(resource_exists::<NetSession>()).in_set(GameSet::Scoring))
+        .add_systems(PostUpdate, (send_inputs, send_state).run_if(resource_exists::<NetSession>()));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NetRole {
+    // picks the seed and is the one that pauses and ends matches
+    Host,
+    // starts, pauses and ends each match when the host says
+    Client,
+}
+
+#[derive(Resource)]
+pub struct NetSession {
+    socket: UdpSocket,
+    pub role: NetRole,
+    // the other machine; a host doesn't know it until someone joins
+    peer: Option<SocketAddr>,
+    connected: bool,
+    silence: Timer,
+    join: Timer,
+    // counts the matches played this session, so inputs left over from the last one aren't taken for this one's
+    round: u32,
+    seed: u64,
+    // the next tick to play, counting from the start of the match
+    tick: u64,
+    delay: u64,
+    // this machine's inputs by tick, each taken `delay` ticks before it's played
+    local: Vec<i8>,
+    // the other machine's inputs by tick, as far as they've arrived in order; every tick before the end is confirmed
+    remote: Vec<i8>,
+    // how many of this machine's inputs the other has, so only the rest are sent again
+    acked: usize,
+    // where each recent tick started from, and the remote input it was played on
+    history: VecDeque<(u64, TickState, i8)>,
+    // the first tick played on a guess that turned out wrong
+    rollback_from: Option<u64>,
+    // a change of state play came to, waiting for the inputs up to it to be confirmed
+    held: Option<GameState>,
+    // the client's copy of a change of state the host made, and the tick it made it on
+    state_change: Option<(GameState, u64)>,
+    // the tick the state last changed on
+    state_tick: u64,
+    replaying: bool,
+}
+
+impl NetSession {
+    fn new(socket: UdpSocket, role: NetRole, peer: Option<SocketAddr>) -> io::Result<Self> {
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            role,
+            peer,
+            connected: false,
+            silence: Timer::from_seconds(NET_TIMEOUT, TimerMode::Once),
+            join: Timer::from_seconds(JOIN_INTERVAL, TimerMode::Repeating),
+            round: 0,
+            seed: 0,
+            tick: 0,
+            delay: 0,
+            local: Vec::new(),
+            remote: Vec::new(),
+            acked: 0,
+            history: VecDeque::new(),
+            rollback_from: None,
+            held: None,
+            state_change: None,
+            state_tick: 0,
+            replaying: false,
+        })
+    }
+
+    pub fn host() -> io::Result<Self> {
+        Self::new(UdpSocket::bind(("0.0.0.0", NET_PORT))?, NetRole::Host, None)
+    }
+
+    pub fn join(address: &str) -> io::Result<Self> {
+        let address = if address.contains(':') { address.to_string() } else { format!("{address}:{NET_PORT}") };
+        let peer = address.to_socket_addrs()?.next().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such host"))?;
+        let local = if peer.is_ipv6() { "[::]:0" } else { "0.0.0.0:0" };
+        Self::new(UdpSocket::bind(local)?, NetRole::Client, Some(peer))
+    }
+
+    fn send(&self, packet: &Packet) {
+        let peer = if let Some(peer) = self.peer { peer } else { return; };
+        // a dropped packet is no worse than a lost one, the next frame sends another
+        if let Ok(text) = ron::to_string(packet) {
+            let _ = self.socket.send_to(text.as_bytes(), peer);
+        }
+    }
+
+    fn confirmed(&self) -> u64 {
+        self.remote.len() as u64
+    }
+}
+
+// offline, or hosting; a client leaves pausing to the host
+pub fn in_charge(session: Option<Res<NetSession>>) -> bool {
+    !is_client(&session)
+}
+
+pub fn is_client(session: &Option<Res<NetSession>>) -> bool {
+    session.as_ref().is_some_and(|session| session.role == NetRole::Client)
+}
+
+// ticks already seen being played again, which shouldn't be heard twice
+pub fn replaying(session: Option<Res<NetSession>>) -> bool {
+    session.is_some_and(|session| session.replaying)
+}
+
+// whether play moves on this tick; online it also waits rather than get too far ahead of the other machine's inputs, or past a change of state both haven't agreed on
+pub fn ticking(state: Res<State<GameState>>, session: Option<Res<NetSession>>) -> bool {
+    let playing = *state.get() == GameState::Playing;
+    let session = if let Some(session) = session { session } else { return playing; };
+    if session.replaying {
+        return true;
+    }
+    playing && session.held.is_none() && session.state_change.is_none_or(|(_, tick)| session.tick < tick) && session.tick < session.confirmed() + MAX_ROLLBACK
+}
+
+// the paddle the other machine is steering
+#[derive(Component)]
+pub struct RemotePaddle;
+
+// the host's gameplay numbers are in effect on the client
+#[derive(Resource)]
+struct HostGameplay;
+
+#[derive(Serialize, Deserialize)]
+enum Packet {
+    // resent by a joining client until the host answers
+    Join,
+    // what the match plays out from besides the seed, so both machines play the same one
+    Welcome { rules: MatchRules, arena: ArenaConfig, gameplay: GameplayConfig },
+    // every input the other side hasn't said it has, from tick `first` on, along with how many of the other side's this one has; sent every frame, so it keeps the connection alive too
+    Inputs { round: u32, first: u64, inputs: Vec<i8>, received: u64 },
+    // the host's state and the tick it changed on, and the seed of the match it's in
+    State { round: u32, state: GameState, tick: u64, seed: u64 },
+    Leave,
+}
+
+// everything a tick can change, to go back to
+struct TickState {
+    scoreboard: Scoreboard,
+    serve: Serve,
+    rally: Rally,
+    stats: MatchStats,
+    overtime: Overtime,
+    rng: GameRng,
+    // position, velocity and how far overtime has shrunk it
+    paddles: Vec<(Entity, Transform, Vec2, f32)>,
+    // position, velocity and speed, with the watchdog's timers
+    balls: Vec<(Transform, Vec2, f32, Option<StuckTimers>)>,
+}
+
+impl TickState {
+    fn save(world: &mut World) -> Self {
+        let paddles = world.query_filtered::<(Entity, &Transform, &Velocity, &PaddleSize), With<Paddle>>().iter(world)
+            .map(|(entity, transform, velocity, size)| (entity, *transform, velocity.0, size.overtime))
+            .collect();
+        let mut balls: Vec<_> = world.query_filtered::<(Entity, &Transform, &Velocity, &BallSpeed, Option<&StuckTimers>), With<Ball>>().iter(world)
+            .map(|(entity, transform, velocity, speed, timers)| (entity, (*transform, velocity.0, speed.0, timers.cloned())))
+            .collect();
+        balls.sort_by_key(|(entity, _)| *entity);
+
+        Self {
+            scoreboard: world.resource::<Scoreboard>().clone(),
+            serve: world.resource::<Serve>().clone(),
+            rally: world.resource::<Rally>().clone(),
+            stats: world.resource::<MatchStats>().clone(),
+            overtime: world.resource::<Overtime>().clone(),
+            rng: world.resource::<GameRng>().clone(),
+            paddles,
+            balls: balls.into_iter().map(|(_, ball)| ball).collect(),
+        }
+    }
+
+    fn restore(self, world: &mut World) {
+        *world.resource_mut::<Scoreboard>() = self.scoreboard;
+        *world.resource_mut::<Serve>() = self.serve;
+        *world.resource_mut::<Rally>() = self.rally;
+        *world.resource_mut::<MatchStats>() = self.stats;
+        *world.resource_mut::<Overtime>() = self.overtime;
+        *world.resource_mut::<GameRng>() = self.rng;
+
+        for (entity, transform, velocity, overtime) in self.paddles {
+            let mut paddle = if let Some(paddle) = world.get_entity_mut(entity) { paddle } else { continue; };
+            paddle.insert((transform, Velocity(velocity)));
+            if let Some(mut size) = paddle.get_mut::<PaddleSize>() {
+                if size.overtime != overtime {
+                    size.overtime = overtime;
+                }
+            }
+        }
+
+        // as many balls as there were, whichever entities they are now
+        let mut balls: Vec<Entity> = world.query_filtered::<Entity, With<Ball>>().iter(world).collect();
+        balls.sort();
+        for ball in balls.split_off(self.balls.len().min(balls.len())) {
+            world.entity_mut(ball).despawn_recursive();
+        }
+        let asset_server = world.resource::<AssetServer>().clone();
+        while balls.len() < self.balls.len() {
+            balls.push(world.spawn(ball_bundle(&asset_server, Vec3::ZERO, Vec2::ZERO)).id());
+        }
+        for (ball, (transform, velocity, speed, timers)) in balls.into_iter().zip(self.balls) {
+            let mut ball = world.entity_mut(ball);
+            ball.insert((transform, Velocity(velocity), BallSpeed(speed)));
+            match timers {
+                Some(timers) => ball.insert(timers),
+                None => ball.remove::<StuckTimers>(),
+            };
+        }
+    }
+}
+
+#[derive(Event)]
+struct NetPacket(Packet);
+
+// what the lan play page shows
+#[derive(Resource)]
+pub struct Lobby {
+    pub address: String,
+    pub status: String,
+}
+
+impl Default for Lobby {
+    fn default() -> Self {
+        Self { address: "127.0.0.1".to_string(), status: String::new() }
+    }
+}
+
+impl Lobby {
+    pub fn join_label(&self) -> String {
+        format!("Join: {}_", self.address)
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+pub enum LobbyButton {
+    Host,
+    Join,
+}
+
+#[derive(Component)]
+pub struct LobbyStatus;
+
+// the address other machines on the network reach this one at; connecting a udp socket sends nothing
+fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    socket.local_addr().ok().map(|address| address.ip())
+}
+
+fn lobby_actions(mut commands: Commands, query: Query<(&Interaction, &LobbyButton), Changed<Interaction>>, mut lobby: ResMut<Lobby>, mut mode: ResMut<GameMode>) {
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let session = match button {
+            LobbyButton::Host => NetSession::host(),
+            LobbyButton::Join => NetSession::join(&lobby.address),
+        };
+        match session {
+            Ok(session) => {
+                lobby.status = match session.role {
+                    NetRole::Host => format!("Waiting for a player at {}:{NET_PORT}", local_ip().map_or("this machine".to_string(), |ip| ip.to_string())),
+                    NetRole::Client => format!("Connecting to {}...", lobby.address),
+                };
+                commands.insert_resource(session);
+                // straight away, so the gameplay numbers for online play are in before anyone joins
+                *mode = GameMode::Online;
+            }
+            Err(error) => lobby.status = format!("Couldn't connect: {error}"),
+        }
+    }
+}
+
+// backing out of the lan page stops hosting or joining
+fn close_lobby(mut commands: Commands, screen: Res<MenuScreen>, session: Option<Res<NetSession>>, mut lobby: ResMut<Lobby>) {
+    if *screen != MenuScreen::Online && session.is_some() {
+        commands.remove_resource::<NetSession>();
+        lobby.status.clear();
+    }
+}
+
+fn type_address(mut characters: EventReader<ReceivedCharacter>, input: Res<Input<KeyCode>>, mut lobby: ResMut<Lobby>) {
+    for character in characters.read() {
+        let allowed = character.char.is_ascii_alphanumeric() || matches!(character.char, '.' | ':' | '-' | '[' | ']');
+        if allowed && lobby.address.len() < MAX_ADDRESS_LENGTH {
+            lobby.address.push(character.char);
+        }
+    }
+    if input.just_pressed(KeyCode::Back) {
+        lobby.address.pop();
+    }
+}
+
+fn update_lobby_labels(lobby: Res<Lobby>, buttons: Query<(&LobbyButton, &Children)>, mut texts: Query<&mut Text, Without<LobbyStatus>>, mut status: Query<&mut Text, With<LobbyStatus>>) {
+    for (button, children) in buttons.iter() {
+        if let LobbyButton::Join = button {
+            for child in children.iter() {
+                if let Ok(mut text) = texts.get_mut(*child) {
+                    text.sections[0].value = lobby.join_label();
+                }
+            }
+        }
+    }
+    for mut text in status.iter_mut() {
+        text.sections[0].value = lobby.status.clone();
+    }
+}
+
+fn poll_socket(mut commands: Commands, mut session: ResMut<NetSession>, mut lobby: ResMut<Lobby>, mut packets: EventWriter<NetPacket>, state: Res<State<GameState>>, mut next_state: ResMut<NextState<GameState>>, time: Res<Time>) {
+    let mut buffer = [0; MAX_PACKET_SIZE];
+    while let Ok((length, from)) = session.socket.recv_from(&mut buffer) {
+        if session.peer.is_some_and(|peer| peer != from) {
+            continue;
+        }
+        let packet = if let Some(packet) = std::str::from_utf8(&buffer[..length]).ok().and_then(|text| ron::from_str::<Packet>(text).ok()) { packet } else { continue; };
+
+        // the first player to ask gets the game
+        if session.peer.is_none() {
+            if !matches!(packet, Packet::Join) {
+                continue;
+            }
+            session.peer = Some(from);
+        }
+
+        session.silence.reset();
+        packets.send(NetPacket(packet));
+    }
+
+    // hosts wait in the lobby as long as it takes
+    if session.peer.is_none() {
+        return;
+    }
+
+    if session.silence.tick(time.delta()).just_finished() {
+        lobby.status = if session.connected { "Connection lost".to_string() } else { "No answer from the host".to_string() };
+        commands.remove_resource::<NetSession>();
+        if *state.get() != GameState::Menu {
+            next_state.set(GameState::Menu);
+        }
+    }
+}
+
+fn leave_session(mut commands: Commands, session: Res<NetSession>) {
+    session.send(&Packet::Leave);
+    commands.remove_resource::<NetSession>();
+}
+
+fn leave_received(mut commands: Commands, mut packets: EventReader<NetPacket>, mut lobby: ResMut<Lobby>, state: Res<State<GameState>>, mut next_state: ResMut<NextState<GameState>>) {
+    if !packets.read().any(|packet| matches!(packet.0, Packet::Leave)) {
+        return;
+    }
+
+    lobby.status = "The other player left".to_string();
+    commands.remove_resource::<NetSession>();
+    if *state.get() != GameState::Menu {
+        next_state.set(GameState::Menu);
+    }
+}
+
+fn call_host(mut session: ResMut<NetSession>, time: Res<Time>) {
+    if session.role != NetRole::Client || session.connected {
+        return;
+    }
+
+    if session.join.tick(time.delta()).just_finished() {
+        session.send(&Packet::Join);
+    }
+}
+
+fn accept_player(mut session: ResMut<NetSession>, mut packets: EventReader<NetPacket>, mut rules: ResMut<MatchRules>, arena: Res<ArenaConfig>, gameplay: Res<GameplayConfig>, mut next_state: ResMut<NextState<GameState>>) {
+    if session.role != NetRole::Host || !packets.read().any(|packet| matches!(packet.0, Packet::Join)) {
+        return;
+    }
+
+    // the clock, power-ups, obstacles and spin don't move on in ticks or go back with them, so online matches go without them
+    rules.time_limit = None;
+    rules.power_ups = false;
+    rules.obstacles = false;
+    rules.curve_ball = false;
+
+    // answered every time, in case the client missed the last one
+    session.send(&Packet::Welcome { rules: rules.clone(), arena: *arena, gameplay: gameplay.clone() });
+    if !session.connected {
+        session.connected = true;
+        next_state.set(GameState::Playing);
+    }
+}
+
+// the client plays by the host's rules and numbers; the match itself starts when the host's first state arrives
+fn welcome_received(mut commands: Commands, mut session: ResMut<NetSession>, mut packets: EventReader<NetPacket>, mut rules: ResMut<MatchRules>, mut arena: ResMut<ArenaConfig>, mut runtime: ResMut<GameplayRuntimeLayer>) {
+    if session.role != NetRole::Client || session.connected {
+        return;
+    }
+
+    for packet in packets.read() {
+        if let Packet::Welcome { rules: host_rules, arena: host_arena, gameplay } = &packet.0 {
+            *rules = host_rules.clone();
+            *arena = *host_arena;
+            runtime.0 = Some(GameplayOverride::all(gameplay));
+            commands.insert_resource(HostGameplay);
+            session.connected = true;
+        }
+    }
+}
+
+fn drop_host_gameplay(mut commands: Commands, mut runtime: ResMut<GameplayRuntimeLayer>) {
+    runtime.0 = None;
+    commands.remove_resource::<HostGameplay>();
+}
+
+// both machines start every match on the same seed, from tick zero with nothing in their buffers but the input delay
+fn start_round(mut session: ResMut<NetSession>, mut rng: ResMut<GameRng>, settings: Res<Settings>) {
+    match session.role {
+        NetRole::Host => {
+            session.round += 1;
+            session.seed = rng.match_seed;
+        }
+        NetRole::Client => rng.start_match(session.seed),
+    }
+
+    session.delay = settings.input_delay.min(MAX_INPUT_DELAY) as u64;
+    session.tick = 0;
+    session.local = vec![0; session.delay as usize];
+    session.remote.clear();
+    session.acked = 0;
+    session.history.clear();
+    session.rollback_from = None;
+    session.held = None;
+    session.state_change = None;
+    session.state_tick = 0;
+}
+
+fn note_state_change(mut session: ResMut<NetSession>) {
+    session.state_tick = session.tick;
+}
+
+// keeps where the tick starts from, then sets both paddles going on their inputs for it; the other machine's is guessed to be the last one it sent until the real one arrives
+fn start_tick(world: &mut World) {
+    let state = TickState::save(world);
+    let pressed = world.query_filtered::<&PaddleMotion, (With<HumanControlled>, Without<RemotePaddle>)>().get_single(world).map_or(0, |motion| motion.input());
+
+    let mut session = world.resource_mut::<NetSession>();
+    let tick = session.tick;
+    // a tick being played again already has its input
+    if session.local.len() as u64 == tick + session.delay {
+        session.local.push(pressed);
+    }
+    let local = session.local.get(tick as usize).copied().unwrap_or(0);
+    let remote = session.remote.get(tick as usize).or(session.remote.last()).copied().unwrap_or(0);
+    while session.history.front().is_some_and(|(saved, ..)| saved + HISTORY_TICKS < tick) {
+        session.history.pop_front();
+    }
+    session.history.push_back((tick, state, remote));
+    session.tick += 1;
+
+    for (mut motion, remote_paddle) in world.query::<(&mut PaddleMotion, Has<RemotePaddle>)>().iter_mut(world) {
+        motion.set_input(if remote_paddle { remote } else { local });
+    }
+}
+
+// a match only ends once the other machine's inputs have caught up and shown it really did, and play waits there until then
+fn hold_state_change(mut session: ResMut<NetSession>, mut next_state: ResMut<NextState<GameState>>) {
+    if let Some(state) = next_state.0.take() {
+        session.held = Some(state);
+    }
+}
+
+fn receive_inputs(mut session: ResMut<NetSession>, mut packets: EventReader<NetPacket>) {
+    for packet in packets.read() {
+        let (first, inputs, received) = if let Packet::Inputs { round, first, inputs, received } = &packet.0 {
+            if *round != session.round {
+                continue;
+            }
+            (*first, inputs, *received)
+        } else { continue; };
+
+        session.acked = session.acked.max(received as usize);
+        for (tick, input) in (first..).zip(inputs.iter().copied()) {
+            if tick < session.confirmed() {
+                continue;
+            }
+            // the ones before it went missing, and come again next frame
+            if tick > session.confirmed() {
+                break;
+            }
+            let guessed = session.history.iter().find(|(saved, ..)| *saved == tick).map(|(_, _, guess)| *guess);
+            if guessed.is_some_and(|guess| guess != input) {
+                session.rollback_from = Some(session.rollback_from.map_or(tick, |from| from.min(tick)));
+            }
+            session.remote.push(input);
+        }
+    }
+}
+
+// the client starts each match when the host does, and pauses, carries on and ends it on the same ticks
+fn follow_host(mut session: ResMut<NetSession>, mut packets: EventReader<NetPacket>, state: Res<State<GameState>>, mut next_state: ResMut<NextState<GameState>>) {
+    if session.role != NetRole::Client || !session.connected {
+        return;
+    }
+
+    for packet in packets.read() {
+        let (round, host_state, tick, seed) = if let Packet::State { round, state, tick, seed } = packet.0 { (round, state, tick, seed) } else { continue; };
+        if round > session.round && matches!(state.get(), GameState::Menu | GameState::GameOver) {
+            session.round = round;
+            session.seed = seed;
+            next_state.set(GameState::Playing);
+        // older than the client's own last change, it's one the client has already caught up with
+        } else if round == session.round && host_state != *state.get() && tick >= session.state_tick {
+            session.state_change = Some((host_state, tick));
+        }
+    }
+}
+
+// goes back to the start of the first tick played on a wrong guess and plays on again from there, and takes up a change of state the host made on the tick it made it,
+// once the host's inputs up to it have arrived
+fn roll_back(world: &mut World) {
+    let mut session = world.resource_mut::<NetSession>();
+    let change = session.state_change.filter(|(_, tick)| *tick <= session.confirmed());
+    let target = change.map_or(session.tick, |(_, tick)| tick);
+    let from = [session.rollback_from.take(), (target < session.tick).then_some(target)].into_iter().flatten().min();
+
+    if let Some(from) = from {
+        if let Some(index) = session.history.iter().position(|(tick, ..)| *tick == from) {
+            let (_, state, _) = session.history.drain(index..).next().unwrap();
+            session.tick = from;
+            session.held = None;
+            state.restore(world);
+        } else {
+            warn!("couldn't go back to tick {from}, so the match may have fallen out of step");
+        }
+    }
+
+    if world.resource::<NetSession>().tick < target {
+        let fixed = world.resource::<Time<Fixed>>().as_generic();
+        let frame = std::mem::replace(&mut *world.resource_mut::<Time>(), fixed);
+        world.resource_mut::<NetSession>().replaying = true;
+        while world.resource::<NetSession>().tick < target && world.resource::<NetSession>().held.is_none() {
+            world.run_schedule(FixedUpdate);
+        }
+        world.resource_mut::<NetSession>().replaying = false;
+        *world.resource_mut::<Time>() = frame;
+
+        // what happened the first time round has already been seen and heard
+        world.resource_mut::<Events<BallCollided>>().clear();
+        world.resource_mut::<Events<BallDestroyed>>().clear();
+        world.resource_mut::<Events<BallRescued>>().clear();
+    }
+
+    let mut session = world.resource_mut::<NetSession>();
+    let mut next = None;
+    if let Some((state, _)) = change {
+        session.state_change = None;
+        next = Some(state);
+    }
+    if session.held.is_some() && session.tick <= session.confirmed() {
+        next = session.held.take();
+    }
+    if let Some(state) = next {
+        world.resource_mut::<NextState<GameState>>().set(state);
+    }
+}
+
+fn send_inputs(session: Res<NetSession>) {
+    if !session.connected {
+        return;
+    }
+
+    let first = session.acked.min(session.local.len());
+    session.send(&Packet::Inputs { round: session.round, first: first as u64, inputs: session.local[first..].to_vec(), received: session.confirmed() });
+}
+
+fn send_state(session: Res<NetSession>, state: Res<State<GameState>>) {
+    if session.role != NetRole::Host || session.round == 0 {
+        return;
+    }
+
+    session.send(&Packet::State { round: session.round, state: *state.get(), tick: session.state_tick, seed: session.seed });
+}
@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use rand::Rng;
+
+use crate::{Collider, GameState, MatchEntity, MatchRules, Velocity, ball::{BallCollided, Serve}, rng::GameRng};
+
+// how far a block can knock the ball off its reflected path
+const MAX_DEFLECTION: f32 = 0.35;
+
+pub struct ObstaclePlugin;
+
+impl Plugin for ObstaclePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, (
+            spawn_round_obstacles.run_if(obstacles_enabled),
+            obstacle_hits,
+            update_obstacle_colors,
+        ).chain().run_if(in_state(GameState::Playing)));
+    }
+}
+
+#[derive(Component)]
+pub struct Obstacle {
+    // None for blocks that can't be broken
+    pub hits_left: Option<u32>,
+}
+
+struct Block {
+    position: Vec2,
+    size: Vec2,
+    hits: Option<u32>,
+}
+
+const fn block(x: f32, y: f32, width: f32, height: f32, hits: Option<u32>) -> Block {
+    Block { position: Vec2::new(x, y), size: Vec2::new(width, height), hits }
+}
+
+const PILLARS: &[Block] = &[
+    block(-250., 200., 30., 120., None),
+    block(-250., -200., 30., 120., None),
+    block(250., 200., 30., 120., None),
+    block(250., -200., 30., 120., None),
+];
+
+const BRICKS: &[Block] = &[
+    block(-150., 300., 40., 80., Some(2)),
+    block(-150., 150., 40., 80., Some(2)),
+    block(-150., -150., 40., 80., Some(2)),
+    block(-150., -300., 40., 80., Some(2)),
+    block(150., 300., 40., 80., Some(2)),
+    block(150., 150., 40., 80., Some(2)),
+    block(150., -150., 40., 80., Some(2)),
+    block(150., -300., 40., 80., Some(2)),
+];
+
+const DIAMOND: &[Block] = &[
+    block(-300., 0., 60., 60., None),
+    block(300., 0., 60., 60., None),
+    block(-150., 250., 40., 40., Some(1)),
+    block(150., 250., 40., 40., Some(1)),
+    block(-150., -250., 40., 40., Some(1)),
+    block(150., -250., 40., 40., Some(1)),
+];
+
+const LAYOUTS: &[&[Block]] = &[PILLARS, BRICKS, DIAMOND];
+
+fn obstacles_enabled(rules: Res<MatchRules>) -> bool {
+    rules.obstacles
+}
+
+// a fresh layout every time a serve countdown starts
+fn spawn_round_obstacles(mut commands: Commands, serve: Res<Serve>, mut random: ResMut<GameRng>, mut last_elapsed: Local<Option<Duration>>, obstacles: Query<Entity, With<Obstacle>>) {
+    if !serve.is_counting_down() {
+        *last_elapsed = None;
+        return;
+    }
+
+    let elapsed = serve.countdown.elapsed();
+    let new_round = last_elapsed.is_none_or(|last| elapsed < last);
+    *last_elapsed = Some(elapsed);
+    if !new_round {
+        return;
+    }
+
+    for entity in obstacles.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let layout = LAYOUTS[random.gen_range(0..LAYOUTS.len())];
+    for block in layout {
+        commands.spawn((
+            MatchEntity,
+            Obstacle { hits_left: block.hits },
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::WHITE,
+                    custom_size: Some(block.size),
+                    ..default()
+                },
+                transform: Transform::from_translation(block.position.extend(0.)),
+                ..default()
+            },
+            Collider::cuboid(block.size.x, block.size.y),
+        ));
+    }
+}
+
+fn obstacle_hits(mut commands: Commands, mut events: EventReader<BallCollided>, mut obstacles: Query<&mut Obstacle>, mut balls: Query<&mut Velocity>, mut random: ResMut<GameRng>) {
+    for event in events.read() {
+        let mut obstacle = if let Ok(obstacle) = obstacles.get_mut(event.other) { obstacle } else { continue; };
+
+        if let Ok(mut velocity) = balls.get_mut(event.ball) {
+            let deflection = random.gen_range(-MAX_DEFLECTION..MAX_DEFLECTION);
+            velocity.0 = Vec2::from_angle(deflection).rotate(velocity.0);
+        }
+
+        if let Some(hits_left) = obstacle.hits_left.as_mut() {
+            *hits_left = hits_left.saturating_sub(1);
+            if *hits_left == 0 {
+                commands.entity(event.other).despawn_recursive();
+            }
+        }
+    }
+}
+
+fn update_obstacle_colors(mut obstacles: Query<(&Obstacle, &mut Sprite), Changed<Obstacle>>) {
+    for (obstacle, mut sprite) in obstacles.iter_mut() {
+        sprite.color = match obstacle.hits_left {
+            None => Color::WHITE,
+            Some(hits) if hits > 1 => Color::rgb(0.8, 0.5, 0.2),
+            Some(_) => Color::rgb(0.5, 0.3, 0.15),
+        };
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::{GameSet, GameState, MatchEntity, MatchRules, ball::BallDestroyed, paddle::PaddleSize, score::{Scoreboard, check_winner}, theme::Theme};
+
+const SHRINK_INTERVAL: f32 = 10.;
+const SHRINK_FACTOR: f32 = 0.85;
+const MIN_SIZE: f32 = 0.3;
+
+pub struct OvertimePlugin;
+
+impl Plugin for OvertimePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Overtime>()
+        .add_systems(OnExit(GameState::Menu), reset_overtime)
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, reset_overtime)
+        .add_systems(FixedUpdate, (end_overtime, start_overtime, shrink_paddles).chain().after(check_winner).in_set(GameSet::Scoring))
+        .add_systems(Update, show_overtime_banner.run_if(resource_changed::<Overtime>()).in_set(GameSet::Presentation));
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct Overtime {
+    pub active: bool,
+    pub shrink: Timer,
+    pub level: i32,
+}
+
+impl Default for Overtime {
+    fn default() -> Self {
+        Self {
+            active: false,
+            shrink: Timer::from_seconds(SHRINK_INTERVAL, TimerMode::Repeating),
+            level: 0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct OvertimeBanner;
+
+fn reset_overtime(mut overtime: ResMut<Overtime>) {
+    *overtime = Overtime::default();
+}
+
+fn start_overtime(mut overtime: ResMut<Overtime>, scoreboard: Res<Scoreboard>, rules: Res<MatchRules>) {
+    if overtime.active || !scoreboard.both_at_match_point(&rules) {
+        return;
+    }
+
+    *overtime = Overtime { active: true, ..default() };
+}
+
+// the banner follows overtime rather than going up and down with it, so ticks played over again online can't leave one behind
+fn show_overtime_banner(mut commands: Commands, overtime: Res<Overtime>, theme: Res<Theme>, banners: Query<Entity, With<OvertimeBanner>>) {
+    if overtime.active != banners.is_empty() {
+        return;
+    }
+
+    if !overtime.active {
+        for banner in banners.iter() {
+            commands.entity(banner).despawn_recursive();
+        }
+        return;
+    }
+
+    commands.spawn((
+        MatchEntity,
+        OvertimeBanner,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                bottom: Val::Px(40.),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn(TextBundle::from_section("SUDDEN DEATH", TextStyle {
+            font_size: 48.,
+            color: theme.palette().alert,
+            ..default()
+        }));
+    });
+}
+
+fn shrink_paddles(mut overtime: ResMut<Overtime>, time: Res<Time>, mut paddles: Query<&mut PaddleSize>) {
+    if !overtime.active || !overtime.shrink.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    overtime.level += 1;
+    let scale = SHRINK_FACTOR.powi(overtime.level).max(MIN_SIZE);
+    for mut size in paddles.iter_mut() {
+        size.overtime = scale;
+    }
+}
+
+fn end_overtime(mut overtime: ResMut<Overtime>, mut events: EventReader<BallDestroyed>, mut paddles: Query<&mut PaddleSize>) {
+    if events.read().count() == 0 || !overtime.active {
+        return;
+    }
+
+    *overtime = Overtime::default();
+    for mut size in paddles.iter_mut() {
+        size.overtime = 1.;
+    }
+}
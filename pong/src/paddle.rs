@@ -0,0 +1,264 @@
+use bevy::prelude::*;
+
+use crate::{ArenaConfig, Collider, GameMode, GameSet, GameState, Handicap, MatchEntity, MatchRules, Velocity, ai::{AiAim, AiControlled, AiPerception}, ball::{Ball, hand_rolled_physics}, bindings::{Bindings, movement_pressed}, gameplay::GameplayConfig, net::{NetSession, RemotePaddle, is_client}, score::Scoreboard, skins::PaddleSkins, spawn_match, theme::Theme};
+
+// how hard a mouse or touch controlled paddle chases the pointer, in speed per unit of distance
+const POINTER_FOLLOW_GAIN: f32 = 8.;
+// inputs sent online or kept in demos are this many steps either way of standing still
+const INPUT_STEPS: f32 = 127.;
+
+pub struct PaddlePlugin;
+
+impl Plugin for PaddlePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(OnExit(GameState::Menu), spawn_paddles.after(spawn_match))
+        .add_systems(FixedUpdate, human_movement_control.in_set(GameSet::Input))
+        // sizes are settled at the start of each tick so the tick plays out on them, and again after each frame for anything resized out of play
+        .add_systems(FixedUpdate, (apply_paddle_size, paddle_kinematics, velocity_movement.run_if(hand_rolled_physics), clamp_to_bounds).chain().in_set(GameSet::Physics))
+        .add_systems(PostUpdate, apply_paddle_size);
+    }
+}
+
+#[derive(Component)]
+pub struct Player;
+
+#[derive(Component)]
+pub struct Computer;
+
+#[derive(Component)]
+pub struct Player2;
+
+#[derive(Component)]
+pub struct Paddle;
+
+#[derive(Component)]
+pub struct HumanControlled {
+    pub up: KeyCode,
+    pub down: KeyCode,
+    // which connected controller drives this paddle, counting up from the first one plugged in
+    pub gamepad: usize,
+}
+
+// controllers set `target` and `paddle_kinematics` eases the paddle's velocity toward it
+#[derive(Component)]
+pub struct PaddleMotion {
+    pub target: f32,
+    pub max_speed: f32,
+    // scales both the target and the top speed, for power-ups and the like
+    pub speed_multiplier: f32,
+    // fixed for the whole match, from the side's handicap
+    pub handicap: f32,
+    pub acceleration: f32,
+    pub deceleration: f32,
+}
+
+impl PaddleMotion {
+    pub fn new(max_speed: f32, config: &GameplayConfig) -> Self {
+        Self {
+            target: 0.,
+            max_speed,
+            speed_multiplier: 1.,
+            handicap: 1.,
+            acceleration: config.paddle_acceleration,
+            deceleration: config.paddle_deceleration,
+        }
+    }
+
+    pub fn with_handicap(mut self, handicap: &Handicap) -> Self {
+        self.handicap = handicap.speed;
+        self
+    }
+
+    pub fn top_speed(&self) -> f32 {
+        self.max_speed * self.speed_multiplier * self.handicap
+    }
+
+    // chase a pointer `offset` away, slowing down as the paddle closes in but never faster than a key press
+    pub fn follow(&mut self, offset: f32) {
+        self.target = (offset * POINTER_FOLLOW_GAIN).clamp(-self.max_speed, self.max_speed);
+    }
+
+    // the target as a single byte, for sending to another machine or keeping in a demo
+    pub fn input(&self) -> i8 {
+        ((self.target / self.max_speed).clamp(-1., 1.) * INPUT_STEPS).round() as i8
+    }
+
+    pub fn set_input(&mut self, input: i8) {
+        self.target = input as f32 / INPUT_STEPS * self.max_speed;
+    }
+}
+
+// the paddle's height is its base height times every multiplier; `apply_paddle_size` keeps the collider, sprite and bounds in step
+#[derive(Component)]
+pub struct PaddleSize {
+    pub base: Vec2,
+    pub handicap: f32,
+    pub power_up: f32,
+    pub overtime: f32,
+}
+
+impl Default for PaddleSize {
+    fn default() -> Self {
+        Self { base: GameplayConfig::default().paddle_size, handicap: 1., power_up: 1., overtime: 1. }
+    }
+}
+
+impl PaddleSize {
+    pub fn height_multiplier(&self) -> f32 {
+        self.handicap * self.power_up * self.overtime
+    }
+}
+
+// vertical range an entity's center is kept inside
+#[derive(Component)]
+pub struct Bounds {
+    pub min_y: f32,
+    pub max_y: f32,
+}
+
+impl Bounds {
+    pub fn arena(arena: &ArenaConfig, half_height: f32) -> Self {
+        Self {
+            min_y: -arena.half_height() + half_height,
+            max_y: arena.half_height() - half_height,
+        }
+    }
+}
+
+// after the scoreboard's reset, which decides which end the player's paddle starts at
+fn spawn_paddles(mut commands: Commands, asset_server: Res<AssetServer>, (mode, session): (Res<GameMode>, Option<Res<NetSession>>), (rules, arena, config): (Res<MatchRules>, Res<ArenaConfig>, Res<GameplayConfig>), (theme, skins): (Res<Theme>, Res<PaddleSkins>), bindings: Res<Bindings>, scoreboard: Res<Scoreboard>) {
+    let (player_handicap, opponent_handicap) = (rules.player_handicap, rules.opponent_handicap);
+    let client = is_client(&session);
+
+    // paddle one
+    let mut paddle_one = commands.spawn((
+        MatchEntity,
+        SpriteBundle {
+            texture: asset_server.load(skins.player.shape.sprite()),
+            sprite: Sprite {
+                color: skins.tint(true, &theme),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(scoreboard.player_side() * arena.paddle_x(), 0., 0.)),
+            ..default()
+        },
+        Player,
+        Paddle,
+        Collider::cuboid(config.paddle_size.x, config.paddle_size.y),
+        PaddleSize { base: config.paddle_size, handicap: player_handicap.size, ..default() },
+        Bounds::arena(&arena, config.paddle_size.y / 2.),
+        Velocity::default(),
+    ));
+    match *mode {
+        GameMode::Demo => paddle_one.insert((AiControlled, AiAim::default(), AiPerception::default(), PaddleMotion::new(config.ai_max_speed, &config).with_handicap(&player_handicap))),
+        GameMode::Online if client => paddle_one.insert((RemotePaddle, PaddleMotion::new(config.player_speed, &config).with_handicap(&player_handicap))),
+        GameMode::Online | GameMode::VersusComputer | GameMode::TwoPlayer | GameMode::Survival | GameMode::Practice => paddle_one.insert((HumanControlled { up: bindings.player_up, down: bindings.player_down, gamepad: 0 }, PaddleMotion::new(config.player_speed, &config).with_handicap(&player_handicap))),
+    };
+
+    // paddle two, the single paddle modes put something else on this side
+    if mode.is_match() {
+        let mut paddle_two = commands.spawn((
+            MatchEntity,
+            SpriteBundle {
+                texture: asset_server.load(skins.opponent.shape.sprite()),
+                sprite: Sprite {
+                    color: skins.tint(false, &theme),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(-scoreboard.player_side() * arena.paddle_x(), 0., 0.)),
+                ..default()
+            },
+            Paddle,
+            Collider::cuboid(config.paddle_size.x, config.paddle_size.y),
+            PaddleSize { base: config.paddle_size, handicap: opponent_handicap.size, ..default() },
+            Bounds::arena(&arena, config.paddle_size.y / 2.),
+            Velocity::default(),
+        ));
+        match *mode {
+            GameMode::VersusComputer | GameMode::Demo | GameMode::Survival | GameMode::Practice => paddle_two.insert((Computer, AiControlled, AiAim::default(), AiPerception::default(), PaddleMotion::new(config.ai_max_speed, &config).with_handicap(&opponent_handicap))),
+            GameMode::TwoPlayer => paddle_two.insert((Player2, HumanControlled { up: bindings.player_two_up, down: bindings.player_two_down, gamepad: 1 }, PaddleMotion::new(config.player_speed, &config).with_handicap(&opponent_handicap))),
+            // each machine steers its own paddle with the first player's controls
+            GameMode::Online if client => paddle_two.insert((Player2, HumanControlled { up: bindings.player_up, down: bindings.player_down, gamepad: 0 }, PaddleMotion::new(config.player_speed, &config).with_handicap(&opponent_handicap))),
+            GameMode::Online => paddle_two.insert((Player2, RemotePaddle, PaddleMotion::new(config.player_speed, &config).with_handicap(&opponent_handicap))),
+        };
+    }
+}
+
+pub fn human_movement_control(mut query: Query<(&mut PaddleMotion, &HumanControlled)>, input: Res<Input<KeyCode>>, scans: Res<Input<ScanCode>>, gamepads: Res<Gamepads>, axes: Res<Axis<GamepadAxis>>, buttons: Res<Input<GamepadButton>>) {
+    let pads = connected_gamepads(&gamepads);
+
+    for (mut motion, controls) in query.iter_mut() {
+        let mut direction: f32 = 0.;
+        if movement_pressed(&input, &scans, controls.up) {
+            direction = 1.0;
+        } else if movement_pressed(&input, &scans, controls.down) {
+            direction = -1.0;
+        }
+
+        // the stick gives analog speed, the d-pad works like the keys; whichever is pushed further wins
+        if let Some(gamepad) = pads.get(controls.gamepad).copied() {
+            let stick = axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY)).unwrap_or(0.);
+            let dpad = if buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadUp)) {
+                1.
+            } else if buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadDown)) {
+                -1.
+            } else {
+                0.
+            };
+            for pushed in [stick, dpad] {
+                if pushed.abs() > direction.abs() {
+                    direction = pushed;
+                }
+            }
+        }
+
+        motion.target = direction.clamp(-1., 1.) * motion.max_speed;
+    }
+}
+
+// in the order they were connected, so the first pad is always player one's
+pub fn connected_gamepads(gamepads: &Gamepads) -> Vec<Gamepad> {
+    let mut pads: Vec<Gamepad> = gamepads.iter().collect();
+    pads.sort_by_key(|gamepad| gamepad.id);
+    pads
+}
+
+pub fn paddle_kinematics(mut query: Query<(&mut Velocity, &PaddleMotion)>, time: Res<Time>) {
+    for (mut velocity, motion) in query.iter_mut() {
+        let top_speed = motion.top_speed();
+        let target = (motion.target * motion.speed_multiplier * motion.handicap).clamp(-top_speed, top_speed);
+        let current = velocity.0.y;
+
+        // speeding up in the direction of travel accelerates, anything else is braking
+        let speeding_up = target.abs() > current.abs() && (current == 0. || target.signum() == current.signum());
+        let rate = if speeding_up { motion.acceleration } else { motion.deceleration };
+        let step = rate * time.delta_seconds();
+        velocity.0.y = current + (target - current).clamp(-step, step);
+    }
+}
+
+pub fn velocity_movement(mut query: Query<(&mut Transform, &Velocity), Without<Ball>>, time: Res<Time>) {
+    for (mut transform, velocity) in query.iter_mut() {
+        transform.translation += velocity.0.extend(0.) * time.delta_seconds();
+    }
+}
+
+pub fn apply_paddle_size(mut paddles: Query<(&PaddleSize, &mut Collider, &mut Sprite, &mut Bounds), Changed<PaddleSize>>, arena: Res<ArenaConfig>) {
+    for (size, mut collider, mut sprite, mut bounds) in paddles.iter_mut() {
+        collider.0 = Vec2::new(size.base.x, size.base.y * size.height_multiplier());
+        // drawn at the collider's size rather than scaled, so a retuned base size shows as well as plays
+        sprite.custom_size = Some(collider.0);
+        *bounds = Bounds::arena(&arena, collider.0.y / 2.);
+    }
+}
+
+pub fn clamp_to_bounds(mut query: Query<(&mut Transform, &mut Velocity, &Bounds)>) {
+    for (mut transform, mut velocity, bounds) in query.iter_mut() {
+        let y = transform.translation.y.clamp(bounds.min_y, bounds.max_y);
+        if y != transform.translation.y {
+            transform.translation.y = y;
+            velocity.0.y = 0.;
+        }
+    }
+}
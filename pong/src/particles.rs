@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use rand::Rng;
+
+use crate::{GameState, MatchEntity, ball::BallCollided};
+
+const BURST_SIZE: usize = 12;
+const PARTICLE_LIFETIME: f32 = 0.35;
+const PARTICLE_SIZE: f32 = 5.;
+const DRAG: f32 = 4.;
+
+const PADDLE_HIT_COLOR: Color = Color::rgb(1., 0.85, 0.3);
+const SMASH_HIT_COLOR: Color = Color::rgb(1., 0.3, 0.15);
+const WALL_HIT_COLOR: Color = Color::rgb(0.4, 0.7, 1.);
+
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, (spawn_bursts, update_particles).chain().run_if(not(in_state(GameState::Paused))));
+    }
+}
+
+#[derive(Component)]
+pub struct Particle {
+    pub velocity: Vec2,
+    pub lifetime: Timer,
+}
+
+fn spawn_bursts(mut commands: Commands, mut events: EventReader<BallCollided>, balls: Query<&Transform>) {
+    let mut random = rand::thread_rng();
+
+    for event in events.read() {
+        let contact = if let Ok(transform) = balls.get(event.ball) { transform.translation.truncate() } else { continue; };
+        let color = if event.is_smash() { SMASH_HIT_COLOR } else if event.is_paddle_hit() { PADDLE_HIT_COLOR } else { WALL_HIT_COLOR };
+        let size = if event.is_smash() { BURST_SIZE * 2 } else { BURST_SIZE };
+
+        for _ in 0..size {
+            let direction = Vec2::from_angle(random.gen_range(0.0..std::f32::consts::TAU));
+            commands.spawn((
+                MatchEntity,
+                Particle {
+                    velocity: direction * random.gen_range(150.0..400.0),
+                    lifetime: Timer::from_seconds(PARTICLE_LIFETIME * random.gen_range(0.6..1.0), TimerMode::Once),
+                },
+                SpriteBundle {
+                    sprite: Sprite {
+                        color,
+                        custom_size: Some(Vec2::splat(PARTICLE_SIZE)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(contact.extend(0.5)),
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+
+fn update_particles(mut commands: Commands, mut particles: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>, time: Res<Time>) {
+    for (entity, mut particle, mut transform, mut sprite) in particles.iter_mut() {
+        if particle.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let slowdown = (1. - DRAG * time.delta_seconds()).max(0.);
+        particle.velocity *= slowdown;
+        transform.translation += particle.velocity.extend(0.) * time.delta_seconds();
+        sprite.color.set_a(1. - particle.lifetime.percent());
+    }
+}
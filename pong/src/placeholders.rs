@@ -0,0 +1,103 @@
+use bevy::{prelude::*, asset::{AssetPath, LoadState}, render::render_resource::{Extent3d, TextureDimension, TextureFormat}, utils::HashMap};
+
+use crate::skins::PaddleShape;
+
+const BALL_DIAMETER: u32 = 30;
+const PADDLE_SPRITE_SIZE: UVec2 = UVec2::new(17, 120);
+const ROUNDED_RADIUS: f32 = 8.;
+const GROOVE_COUNT: u32 = 3;
+const GROOVE_ALPHA: f32 = 0.55;
+
+// nothing in here is pong's own except `add_pong_placeholders`, so the other games can take the rest as it is
+pub struct PlaceholderPlugin;
+
+impl Plugin for PlaceholderPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<PlaceholderSprites>()
+        .add_systems(Startup, add_pong_placeholders)
+        .add_systems(PostUpdate, swap_missing_sprites);
+    }
+}
+
+// generated stand ins for sprite files, used in place of any that fail to load
+#[derive(Resource, Default)]
+pub struct PlaceholderSprites(HashMap<AssetPath<'static>, Handle<Image>>);
+
+impl PlaceholderSprites {
+    pub fn add(&mut self, path: &'static str, image: Image, images: &mut Assets<Image>) {
+        self.0.insert(AssetPath::from(path), images.add(image));
+    }
+
+    fn for_failed(&self, handle: &Handle<Image>, asset_server: &AssetServer) -> Option<Handle<Image>> {
+        let placeholder = self.0.get(handle.path()?)?;
+        (asset_server.get_load_state(handle) == Some(LoadState::Failed)).then(|| placeholder.clone())
+    }
+}
+
+// white throughout, so the sprite's color tints it the same as it would the real artwork; `alpha` gets each pixel's center
+pub fn generate_image(size: UVec2, alpha: impl Fn(Vec2) -> f32) -> Image {
+    let mut data = Vec::with_capacity((size.x * size.y * 4) as usize);
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let coverage = alpha(Vec2::new(x as f32 + 0.5, y as f32 + 0.5)).clamp(0., 1.);
+            data.extend_from_slice(&[255, 255, 255, (coverage * 255.).round() as u8]);
+        }
+    }
+
+    Image::new(Extent3d { width: size.x, height: size.y, depth_or_array_layers: 1 }, TextureDimension::D2, data, TextureFormat::Rgba8UnormSrgb)
+}
+
+pub fn rectangle(size: UVec2) -> Image {
+    generate_image(size, |_| 1.)
+}
+
+pub fn rounded_rectangle(size: UVec2, radius: f32) -> Image {
+    let half = size.as_vec2() / 2.;
+    let radius = radius.min(half.min_element());
+    generate_image(size, |point| {
+        // distance outside the rectangle shrunk by the radius, the edge sitting half a pixel either side of it
+        let outside = ((point - half).abs() - (half - radius)).max(Vec2::ZERO).length();
+        radius - outside + 0.5
+    })
+}
+
+pub fn circle(diameter: u32) -> Image {
+    rounded_rectangle(UVec2::splat(diameter), diameter as f32 / 2.)
+}
+
+// a flat bar with `grooves` faded lines spread evenly across it
+pub fn grooved_rectangle(size: UVec2, grooves: u32, groove_alpha: f32) -> Image {
+    let spacing = size.y as f32 / (grooves + 1) as f32;
+    generate_image(size, |point| {
+        let nearest = (point.y / spacing).round();
+        let in_groove = nearest >= 1. && nearest <= grooves as f32 && (point.y - nearest * spacing).abs() < 1.;
+        if in_groove { groove_alpha } else { 1. }
+    })
+}
+
+fn add_pong_placeholders(mut placeholders: ResMut<PlaceholderSprites>, mut images: ResMut<Assets<Image>>) {
+    placeholders.add("sprites/ball.png", circle(BALL_DIAMETER), &mut images);
+    for shape in PaddleShape::ALL {
+        let image = match shape {
+            PaddleShape::Flat => rectangle(PADDLE_SPRITE_SIZE),
+            PaddleShape::Rounded => rounded_rectangle(PADDLE_SPRITE_SIZE, ROUNDED_RADIUS),
+            PaddleShape::Grooved => grooved_rectangle(PADDLE_SPRITE_SIZE, GROOVE_COUNT, GROOVE_ALPHA),
+        };
+        placeholders.add(shape.sprite(), image, &mut images);
+    }
+}
+
+// after everything's had its chance to load something this frame, so a missing file is never drawn for long
+fn swap_missing_sprites(placeholders: Res<PlaceholderSprites>, asset_server: Res<AssetServer>, mut sprites: Query<&mut Handle<Image>>, mut ui_images: Query<&mut UiImage>) {
+    for mut handle in sprites.iter_mut() {
+        if let Some(placeholder) = placeholders.for_failed(&handle, &asset_server) {
+            *handle = placeholder;
+        }
+    }
+    for mut image in ui_images.iter_mut() {
+        if let Some(placeholder) = placeholders.for_failed(&image.texture, &asset_server) {
+            image.texture = placeholder;
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use bevy::prelude::*;
+
+use rand::Rng;
+
+use crate::{ArenaConfig, Collider, GameState, MatchEntity, MatchRules, Velocity, ball::{Ball, BallCollided, BallSpeed, ball_bundle}, paddle::{Paddle, PaddleMotion, PaddleSize, Player}, rng::GameRng, theme::Theme, ui::SideRow};
+
+const SPAWN_INTERVAL: f32 = 7.;
+const PICKUP_LIFETIME: f32 = 10.;
+const PICKUP_SIZE: f32 = 50.;
+const EFFECT_DURATION: f32 = 8.;
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<PowerUpSpawner>()
+        .add_systems(OnExit(GameState::Menu), reset_spawner)
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, clear_power_ups)
+        .add_systems(Update, (
+            spawn_pickups.run_if(power_ups_enabled),
+            track_last_hit,
+            collect_pickups,
+            expire_pickups,
+            tick_effects,
+            apply_effects,
+        ).chain().run_if(in_state(GameState::Playing)))
+        .add_systems(Update, (attach_effects, update_effect_indicators));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerUpKind {
+    LargePaddle,
+    FastPaddle,
+    MultiBall,
+    SlowOpponent,
+}
+
+impl PowerUpKind {
+    const ALL: [PowerUpKind; 4] = [PowerUpKind::LargePaddle, PowerUpKind::FastPaddle, PowerUpKind::MultiBall, PowerUpKind::SlowOpponent];
+
+    fn color(&self) -> Color {
+        match self {
+            PowerUpKind::LargePaddle => Color::rgb(0.2, 0.8, 0.3),
+            PowerUpKind::FastPaddle => Color::rgb(0.2, 0.6, 1.),
+            PowerUpKind::MultiBall => Color::rgb(1., 0.8, 0.2),
+            PowerUpKind::SlowOpponent => Color::rgb(0.9, 0.3, 0.3),
+        }
+    }
+
+    fn letter(&self) -> &'static str {
+        match self {
+            PowerUpKind::LargePaddle => "L",
+            PowerUpKind::FastPaddle => "F",
+            PowerUpKind::MultiBall => "M",
+            PowerUpKind::SlowOpponent => "S",
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+    pub lifetime: Timer,
+}
+
+// the paddle that last returned this ball, who gets whatever it picks up
+#[derive(Component)]
+pub struct LastHitBy(pub Entity);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PaddleEffect {
+    Enlarged,
+    Hastened,
+    Slowed,
+}
+
+impl PaddleEffect {
+    fn label(&self) -> &'static str {
+        match self {
+            PaddleEffect::Enlarged => "LARGE",
+            PaddleEffect::Hastened => "FAST",
+            PaddleEffect::Slowed => "SLOW",
+        }
+    }
+}
+
+#[derive(Component, Default)]
+pub struct PaddleEffects(pub Vec<(PaddleEffect, Timer)>);
+
+impl PaddleEffects {
+    pub fn add(&mut self, effect: PaddleEffect) {
+        // picking up the same thing again refreshes it rather than stacking
+        self.0.retain(|(active, _)| *active != effect);
+        self.0.push((effect, Timer::from_seconds(EFFECT_DURATION, TimerMode::Once)));
+    }
+
+    pub fn has(&self, effect: PaddleEffect) -> bool {
+        self.0.iter().any(|(active, _)| *active == effect)
+    }
+}
+
+#[derive(Component)]
+pub struct EffectIndicator(bool);
+
+#[derive(Resource)]
+pub struct PowerUpSpawner(Timer);
+
+impl Default for PowerUpSpawner {
+    fn default() -> Self {
+        Self(Timer::from_seconds(SPAWN_INTERVAL, TimerMode::Repeating))
+    }
+}
+
+fn power_ups_enabled(rules: Res<MatchRules>) -> bool {
+    rules.power_ups
+}
+
+fn reset_spawner(mut commands: Commands, mut spawner: ResMut<PowerUpSpawner>, theme: Res<Theme>) {
+    spawner.0.reset();
+    spawn_effect_indicators(&mut commands, theme.palette().text);
+}
+
+fn attach_effects(mut commands: Commands, paddles: Query<Entity, Added<Paddle>>) {
+    for paddle in paddles.iter() {
+        commands.entity(paddle).insert(PaddleEffects::default());
+    }
+}
+
+fn spawn_effect_indicators(commands: &mut Commands, color: Color) {
+    commands.spawn((
+        MatchEntity,
+        SideRow,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::SpaceAround,
+                width: Val::Percent(100.),
+                top: Val::Px(150.),
+                ..default()
+            },
+            ..default()
+        },
+    )).with_children(|parent| {
+        for player_side in [false, true] {
+            parent.spawn((
+                EffectIndicator(player_side),
+                TextBundle::from_section("", TextStyle {
+                    font_size: 32.,
+                    color,
+                    ..default()
+                }),
+            ));
+        }
+    });
+}
+
+fn clear_power_ups(mut commands: Commands, pickups: Query<Entity, With<PowerUp>>, mut paddles: Query<&mut PaddleEffects>, mut spawner: ResMut<PowerUpSpawner>) {
+    for pickup in pickups.iter() {
+        commands.entity(pickup).despawn_recursive();
+    }
+    for mut effects in paddles.iter_mut() {
+        effects.0.clear();
+    }
+    spawner.0.reset();
+}
+
+fn spawn_pickups(mut commands: Commands, mut spawner: ResMut<PowerUpSpawner>, mut random: ResMut<GameRng>, time: Res<Time>, arena: Res<ArenaConfig>, pickups: Query<(), With<PowerUp>>) {
+    if !spawner.0.tick(time.delta()).just_finished() || !pickups.is_empty() {
+        return;
+    }
+
+    let kind = PowerUpKind::ALL[random.gen_range(0..PowerUpKind::ALL.len())];
+    let reach = arena.half_height() - 190.;
+    let y = random.gen_range(-reach..reach);
+
+    commands.spawn((
+        MatchEntity,
+        PowerUp { kind, lifetime: Timer::from_seconds(PICKUP_LIFETIME, TimerMode::Once) },
+        SpriteBundle {
+            sprite: Sprite {
+                color: kind.color(),
+                custom_size: Some(Vec2::splat(PICKUP_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0., y, 1.),
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn(Text2dBundle {
+            text: Text::from_section(kind.letter(), TextStyle {
+                font_size: 40.,
+                color: Color::BLACK,
+                ..default()
+            }),
+            transform: Transform::from_xyz(0., 0., 1.),
+            ..default()
+        });
+    });
+}
+
+fn track_last_hit(mut commands: Commands, mut events: EventReader<BallCollided>, paddles: Query<(), With<Paddle>>) {
+    for event in events.read() {
+        if paddles.contains(event.other) {
+            commands.entity(event.ball).insert(LastHitBy(event.other));
+        }
+    }
+}
+
+fn collect_pickups(mut commands: Commands, asset_server: Res<AssetServer>, pickups: Query<(Entity, &PowerUp, &Transform)>, balls: Query<(&Transform, &Collider, &Velocity, &BallSpeed, Option<&LastHitBy>), With<Ball>>, mut paddles: Query<(Entity, &mut PaddleEffects)>) {
+    for (pickup, power_up, pickup_transform) in pickups.iter() {
+        let collected_by = balls.iter().find(|(ball_transform, collider, ..)| {
+            let reach = (collider.0 + Vec2::splat(PICKUP_SIZE)) / 2.;
+            let offset = (ball_transform.translation - pickup_transform.translation).truncate().abs();
+            offset.x < reach.x && offset.y < reach.y
+        });
+        let (ball_transform, _, ball_velocity, ball_speed, last_hit) = if let Some(ball) = collected_by { ball } else { continue; };
+
+        commands.entity(pickup).despawn_recursive();
+
+        // nobody has touched the ball yet, so nobody gets it
+        let owner = if let Some(last_hit) = last_hit { last_hit.0 } else { continue; };
+
+        match power_up.kind {
+            PowerUpKind::LargePaddle | PowerUpKind::FastPaddle => {
+                if let Ok((_, mut effects)) = paddles.get_mut(owner) {
+                    effects.add(if power_up.kind == PowerUpKind::LargePaddle { PaddleEffect::Enlarged } else { PaddleEffect::Hastened });
+                }
+            }
+            PowerUpKind::SlowOpponent => {
+                for (paddle, mut effects) in paddles.iter_mut() {
+                    if paddle != owner {
+                        effects.add(PaddleEffect::Slowed);
+                    }
+                }
+            }
+            PowerUpKind::MultiBall => {
+                for angle in [-0.35_f32, 0.35] {
+                    let velocity = Vec2::from_angle(angle).rotate(ball_velocity.0.normalize_or_zero()) * ball_speed.0;
+                    commands.spawn(ball_bundle(&asset_server, ball_transform.translation, velocity))
+                        .insert(LastHitBy(owner));
+                }
+            }
+        }
+    }
+}
+
+fn expire_pickups(mut commands: Commands, mut pickups: Query<(Entity, &mut PowerUp)>, time: Res<Time>) {
+    for (entity, mut power_up) in pickups.iter_mut() {
+        if power_up.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn tick_effects(mut paddles: Query<&mut PaddleEffects>, time: Res<Time>) {
+    for mut effects in paddles.iter_mut() {
+        for (_, timer) in effects.0.iter_mut() {
+            timer.tick(time.delta());
+        }
+        effects.0.retain(|(_, timer)| !timer.finished());
+    }
+}
+
+fn apply_effects(mut paddles: Query<(&PaddleEffects, &mut PaddleSize, &mut PaddleMotion), Changed<PaddleEffects>>) {
+    for (effects, mut size, mut motion) in paddles.iter_mut() {
+        let power_up = if effects.has(PaddleEffect::Enlarged) { 1.5 } else { 1. };
+        if size.power_up != power_up {
+            size.power_up = power_up;
+        }
+
+        motion.speed_multiplier = match (effects.has(PaddleEffect::Hastened), effects.has(PaddleEffect::Slowed)) {
+            (true, false) => 1.4,
+            (false, true) => 0.6,
+            _ => 1.,
+        };
+    }
+}
+
+fn update_effect_indicators(paddles: Query<(&PaddleEffects, Has<Player>)>, mut indicators: Query<(&mut Text, &EffectIndicator)>) {
+    for (mut text, indicator) in indicators.iter_mut() {
+        let effects = if let Some((effects, _)) = paddles.iter().find(|(_, is_player)| *is_player == indicator.0) { effects } else { continue; };
+
+        text.sections[0].value = effects.0.iter()
+            .map(|(effect, timer)| format!("{} {}s", effect.label(), timer.remaining_secs().ceil() as u32))
+            .collect::<Vec<_>>()
+            .join("  ");
+    }
+}
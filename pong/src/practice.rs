@@ -0,0 +1,156 @@
+use bevy::{prelude::*, sprite::Anchor, input::common_conditions::input_just_pressed};
+
+use crate::{ArenaConfig, GameMode, GameState, MatchEntity, ball::{Ball, BallSpeed, MAX_BOUNCE_ANGLE, MIN_BALL_SPEED, Serve, ball_bundle}, gameplay::GameplayConfig, reset_match, score::Scoreboard, spawn_match, theme::Theme};
+
+const ANGLE_STEP: f32 = 5.;
+const SPEED_STEP: f32 = 50.;
+
+pub struct PracticePlugin;
+
+impl Plugin for PracticePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Launcher>()
+        .add_systems(OnExit(GameState::Menu), (spawn_launcher.after(spawn_match), skip_serve).run_if(resource_equals(GameMode::Practice)))
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, (clear_balls, skip_serve).after(reset_match).run_if(resource_equals(GameMode::Practice)))
+        .add_systems(Update, (
+            adjust_launcher,
+            clear_balls.run_if(input_just_pressed(KeyCode::R)),
+            fire_ball.run_if(input_just_pressed(KeyCode::Space).or_else(input_just_pressed(KeyCode::R))),
+        ).chain().run_if(in_state(GameState::Playing)).run_if(resource_equals(GameMode::Practice)))
+        .add_systems(Update, (aim_launcher, update_launcher_hud).run_if(resource_equals(GameMode::Practice)));
+    }
+}
+
+#[derive(Resource)]
+pub struct Launcher {
+    // degrees above the horizontal, toward the player
+    pub angle: f32,
+    pub speed: f32,
+}
+
+impl Default for Launcher {
+    fn default() -> Self {
+        Self { angle: 0., speed: GameplayConfig::default().ball_speed }
+    }
+}
+
+impl Launcher {
+    // `player_side` is which way along x the player's paddle is
+    fn velocity(&self, player_side: f32) -> Vec2 {
+        let velocity = Vec2::from_angle(self.angle.to_radians()) * self.speed;
+        Vec2::new(velocity.x * player_side, velocity.y)
+    }
+}
+
+#[derive(Component)]
+pub struct LauncherBarrel;
+
+#[derive(Component)]
+pub struct LauncherText;
+
+// on the opponent's end, facing the player
+fn launcher_position(arena: &ArenaConfig, scoreboard: &Scoreboard) -> Vec2 {
+    Vec2::new(-scoreboard.player_side() * arena.paddle_x(), 0.)
+}
+
+fn spawn_launcher(mut commands: Commands, arena: Res<ArenaConfig>, scoreboard: Res<Scoreboard>, theme: Res<Theme>) {
+    commands.spawn((
+        MatchEntity,
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(0.6, 0.6, 0.6),
+                custom_size: Some(Vec2::splat(40.)),
+                ..default()
+            },
+            transform: Transform::from_translation(launcher_position(&arena, &scoreboard).extend(1.)),
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn((
+            LauncherBarrel,
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::WHITE,
+                    custom_size: Some(Vec2::new(90., 6.)),
+                    anchor: Anchor::CenterLeft,
+                    ..default()
+                },
+                ..default()
+            },
+        ));
+    });
+
+    commands.spawn((
+        MatchEntity,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                top: Val::Px(20.),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn((
+            LauncherText,
+            TextBundle::from_section("", TextStyle {
+                font_size: 40.,
+                color: theme.palette().text,
+                ..default()
+            }).with_text_alignment(TextAlignment::Center),
+        ));
+    });
+}
+
+// balls come from the launcher, not the serve
+fn skip_serve(mut serve: ResMut<Serve>) {
+    let duration = serve.countdown.duration();
+    serve.countdown.tick(duration);
+}
+
+fn adjust_launcher(mut launcher: ResMut<Launcher>, config: Res<GameplayConfig>, input: Res<Input<KeyCode>>) {
+    let max_angle = MAX_BOUNCE_ANGLE.to_degrees();
+    if input.just_pressed(KeyCode::Up) {
+        launcher.angle = (launcher.angle + ANGLE_STEP).min(max_angle);
+    }
+    if input.just_pressed(KeyCode::Down) {
+        launcher.angle = (launcher.angle - ANGLE_STEP).max(-max_angle);
+    }
+    if input.just_pressed(KeyCode::Right) {
+        launcher.speed = (launcher.speed + SPEED_STEP).min(config.max_ball_speed);
+    }
+    if input.just_pressed(KeyCode::Left) {
+        launcher.speed = (launcher.speed - SPEED_STEP).max(MIN_BALL_SPEED);
+    }
+}
+
+fn clear_balls(mut commands: Commands, balls: Query<Entity, With<Ball>>) {
+    for ball in balls.iter() {
+        commands.entity(ball).despawn_recursive();
+    }
+}
+
+fn fire_ball(mut commands: Commands, asset_server: Res<AssetServer>, launcher: Res<Launcher>, arena: Res<ArenaConfig>, scoreboard: Res<Scoreboard>) {
+    commands.spawn(ball_bundle(&asset_server, launcher_position(&arena, &scoreboard).extend(0.), launcher.velocity(scoreboard.player_side())))
+        .insert(BallSpeed(launcher.speed));
+}
+
+fn aim_launcher(launcher: Res<Launcher>, scoreboard: Res<Scoreboard>, mut barrels: Query<&mut Transform, With<LauncherBarrel>>) {
+    let direction = launcher.velocity(scoreboard.player_side());
+    for mut transform in barrels.iter_mut() {
+        transform.rotation = Quat::from_rotation_z(direction.y.atan2(direction.x));
+    }
+}
+
+fn update_launcher_hud(launcher: Res<Launcher>, mut query: Query<&mut Text, With<LauncherText>>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!(
+            "Angle {:+.0}  Speed {:.0}\nUp/Down aim   Left/Right speed   Space fire   R reset",
+            launcher.angle,
+            launcher.speed,
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use bevy::{prelude::*, ecs::system::SystemParam};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GameMode, GameState, MatchRules, ai::{AiPersonality, Difficulty}, bindings::Bindings, menu::MenuScreen, mixer::AudioMixer, records::{Records, seed_survival_best}, score::Scoreboard, settings::{Settings, preferences_overridden}, skins::PaddleSkins, stats::MatchStats, storage, theme::Theme};
+
+const PROFILES_PATH: &str = "profiles.ron";
+// as many as fit on the profiles page with room to spare
+pub const MAX_PROFILES: usize = 6;
+const MAX_NAME_LENGTH: usize = 16;
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(storage::load::<Profiles>(PROFILES_PATH))
+        // records have to be the active profile's before anything reads them
+        .add_systems(Startup, load_active_profile.before(seed_survival_best))
+        .add_systems(OnEnter(GameState::GameOver), record_lifetime_stats)
+        .add_systems(Update, (fill_blank_name.run_if(resource_changed::<MenuScreen>()), profile_actions, type_name.run_if(resource_equals(MenuScreen::Profiles)), update_profile_labels.run_if(resource_changed::<Profiles>())).chain().run_if(in_state(GameState::Menu)))
+        .add_systems(Update, (write_through.run_if(profile_data_changed).run_if(not(preferences_overridden)), save_profiles.run_if(resource_changed::<Profiles>()).run_if(not(resource_added::<Profiles>()))).chain());
+    }
+}
+
+// every match the profile played through to the end, whoever won
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub matches: u32,
+    pub wins: u32,
+    pub longest_rally: u32,
+    pub hits: u32,
+    pub seconds_played: f32,
+}
+
+impl LifetimeStats {
+    pub fn win_rate(&self) -> f32 {
+        if self.matches == 0 { 0. } else { self.wins as f32 / self.matches as f32 }
+    }
+}
+
+// everything set from the settings and audio pages and match setup
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Preferences {
+    pub settings: Settings,
+    pub mixer: AudioMixer,
+    pub difficulty: Difficulty,
+    pub personality: AiPersonality,
+    pub theme: Theme,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    pub stats: LifetimeStats,
+    pub records: Records,
+    pub bindings: Bindings,
+    pub skins: PaddleSkins,
+    // none until the profile's first change, it takes on whatever was already set until then
+    pub preferences: Option<Preferences>,
+}
+
+// whoever's playing on this machine; the records, bindings, skins and preference resources always hold the active one's
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profiles {
+    pub active: usize,
+    pub list: Vec<Profile>,
+}
+
+impl Profiles {
+    pub fn active(&self) -> Option<&Profile> {
+        self.list.get(self.active)
+    }
+
+    fn active_mut(&mut self) -> Option<&mut Profile> {
+        self.list.get_mut(self.active)
+    }
+
+    // the first "Player n" nobody has taken yet
+    fn unused_name(&self) -> String {
+        (1..).map(|number| format!("Player {number}"))
+            .find(|name| self.list.iter().all(|profile| profile.name != *name))
+            .unwrap()
+    }
+}
+
+// a marker for the line showing the active profile's name as it's typed
+#[derive(Component)]
+pub struct ProfileName;
+
+pub fn name_label(profile: &Profile) -> String {
+    format!("Name: {}_", profile.name)
+}
+
+pub fn select_label(profiles: &Profiles, index: usize) -> String {
+    let name = profiles.list.get(index).map_or("", |profile| profile.name.as_str());
+    if index == profiles.active { format!("> {name} <") } else { name.to_string() }
+}
+
+#[derive(Component, Clone, Copy)]
+pub enum ProfileButton {
+    Select(usize),
+    New,
+}
+
+// everything a profile keeps that's live in the game while it's the active one
+#[derive(SystemParam)]
+struct ProfileData<'w> {
+    records: ResMut<'w, Records>,
+    bindings: ResMut<'w, Bindings>,
+    skins: ResMut<'w, PaddleSkins>,
+    settings: ResMut<'w, Settings>,
+    mixer: ResMut<'w, AudioMixer>,
+    difficulty: ResMut<'w, Difficulty>,
+    personality: ResMut<'w, AiPersonality>,
+    theme: ResMut<'w, Theme>,
+}
+
+impl ProfileData<'_> {
+    fn apply(&mut self, profile: &Profile) {
+        *self.records = profile.records.clone();
+        *self.bindings = profile.bindings;
+        *self.skins = profile.skins;
+        if let Some(preferences) = &profile.preferences {
+            // the window, seed and leaderboard server belong to the machine, not whoever's playing on it
+            *self.settings = Settings { fullscreen: self.settings.fullscreen, seed: self.settings.seed, leaderboard_url: self.settings.leaderboard_url.clone(), ..preferences.settings.clone() };
+            *self.mixer = preferences.mixer.clone();
+            *self.difficulty = preferences.difficulty;
+            *self.personality = preferences.personality;
+            *self.theme = preferences.theme;
+        }
+        // scores go online under the profile's name
+        if self.settings.player_name != profile.name {
+            self.settings.player_name = profile.name.clone();
+        }
+    }
+}
+
+fn load_active_profile(mut profiles: ResMut<Profiles>, mut data: ProfileData) {
+    // the first run with profiles keeps everything saved from before them
+    if profiles.list.is_empty() {
+        profiles.list.push(Profile {
+            name: data.settings.player_name.clone(),
+            stats: LifetimeStats::default(),
+            records: data.records.clone(),
+            bindings: *data.bindings,
+            skins: *data.skins,
+            preferences: None,
+        });
+    }
+    profiles.active = profiles.active.min(profiles.list.len() - 1);
+
+    let profile = profiles.list[profiles.active].clone();
+    data.apply(&profile);
+}
+
+fn profile_actions(query: Query<(&Interaction, &ProfileButton), Changed<Interaction>>, mut profiles: ResMut<Profiles>, mut data: ProfileData, mut screen: ResMut<MenuScreen>) {
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            ProfileButton::Select(index) => profiles.active = (*index).min(profiles.list.len().saturating_sub(1)),
+            ProfileButton::New if profiles.list.len() < MAX_PROFILES => {
+                let name = profiles.unused_name();
+                profiles.list.push(Profile { name, ..default() });
+                profiles.active = profiles.list.len() - 1;
+            }
+            ProfileButton::New => continue,
+        }
+
+        let profile = if let Some(profile) = profiles.active() { profile.clone() } else { continue; };
+        data.apply(&profile);
+        // rebuilt to show the new choice
+        *screen = MenuScreen::Profiles;
+    }
+}
+
+// the active profile's name is typed straight in while its page is open
+fn type_name(mut characters: EventReader<ReceivedCharacter>, input: Res<Input<KeyCode>>, mut profiles: ResMut<Profiles>, mut settings: ResMut<Settings>) {
+    // read first, anything written to the profiles saves them
+    let current = if let Some(profile) = profiles.active() { profile.name.clone() } else { return; };
+    let mut name = current.clone();
+    for character in characters.read() {
+        if !character.char.is_control() && name.chars().count() < MAX_NAME_LENGTH {
+            name.push(character.char);
+        }
+    }
+    if input.just_pressed(KeyCode::Back) {
+        name.pop();
+    }
+    if name == current {
+        return;
+    }
+    settings.player_name = name.clone();
+    if let Some(profile) = profiles.active_mut() {
+        profile.name = name;
+    }
+}
+
+// a name erased and never retyped gets a fresh one on leaving the page
+fn fill_blank_name(mut profiles: ResMut<Profiles>, mut settings: ResMut<Settings>) {
+    if !profiles.active().is_some_and(|profile| profile.name.trim().is_empty()) {
+        return;
+    }
+    let name = profiles.unused_name();
+    settings.player_name = name.clone();
+    if let Some(profile) = profiles.active_mut() {
+        profile.name = name;
+    }
+}
+
+fn update_profile_labels(profiles: Res<Profiles>, buttons: Query<(&ProfileButton, &Children)>, mut texts: Query<&mut Text, Without<ProfileName>>, mut name: Query<&mut Text, With<ProfileName>>) {
+    for (button, children) in buttons.iter() {
+        if let ProfileButton::Select(index) = button {
+            for child in children.iter() {
+                if let Ok(mut text) = texts.get_mut(*child) {
+                    text.sections[0].value = select_label(&profiles, *index);
+                }
+            }
+        }
+    }
+    let profile = if let Some(profile) = profiles.active() { profile } else { return; };
+    for mut text in name.iter_mut() {
+        text.sections[0].value = name_label(profile);
+    }
+}
+
+// loading everything at startup isn't a change worth writing back
+fn profile_data_changed(records: Res<Records>, bindings: Res<Bindings>, skins: Res<PaddleSkins>, (settings, mixer, difficulty, personality, theme): (Res<Settings>, Res<AudioMixer>, Res<Difficulty>, Res<AiPersonality>, Res<Theme>)) -> bool {
+    let changed = records.is_changed() || bindings.is_changed() || skins.is_changed() || settings.is_changed() || mixer.is_changed() || difficulty.is_changed() || personality.is_changed() || theme.is_changed();
+    changed && !records.is_added()
+}
+
+fn write_through(mut profiles: ResMut<Profiles>, records: Res<Records>, bindings: Res<Bindings>, skins: Res<PaddleSkins>, (settings, mixer, difficulty, personality, theme): (Res<Settings>, Res<AudioMixer>, Res<Difficulty>, Res<AiPersonality>, Res<Theme>)) {
+    let profile = if let Some(profile) = profiles.active_mut() { profile } else { return; };
+    profile.records = records.clone();
+    profile.bindings = *bindings;
+    profile.skins = *skins;
+    profile.preferences = Some(Preferences { settings: settings.clone(), mixer: mixer.clone(), difficulty: *difficulty, personality: *personality, theme: *theme });
+}
+
+// practice and the attract demo don't count, and neither does survival, which has no one to beat
+fn record_lifetime_stats(mode: Res<GameMode>, scoreboard: Res<Scoreboard>, rules: Res<MatchRules>, stats: Res<MatchStats>, mut profiles: ResMut<Profiles>) {
+    if !mode.is_match() || *mode == GameMode::Demo {
+        return;
+    }
+    // ended early from the pause screen
+    let won = if let Some(won) = scoreboard.winner(&rules) { won } else { return; };
+    let profile = if let Some(profile) = profiles.active_mut() { profile } else { return; };
+
+    profile.stats.matches += 1;
+    if won {
+        profile.stats.wins += 1;
+    }
+    profile.stats.longest_rally = profile.stats.longest_rally.max(stats.longest_rally);
+    profile.stats.hits += stats.hits[1];
+    profile.stats.seconds_played += stats.duration;
+}
+
+fn save_profiles(profiles: Res<Profiles>) {
+    storage::save(PROFILES_PATH, &*profiles);
+}
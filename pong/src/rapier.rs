@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude as rapier;
+
+use crate::{Collider, GameSet, GameState, TICKS_PER_SECOND, Velocity, ball::{Ball, BallCollided, BallSpeed, SweepHit, bounce, hit_offset}, gameplay::GameplayConfig, net::{NetSession, ticking}, paddle::Paddle};
+
+const PIXELS_PER_METER: f32 = 100.;
+
+pub struct RapierBackendPlugin;
+
+impl Plugin for RapierBackendPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        // one step a tick, in with the rest of play
+        .insert_resource(rapier::RapierConfiguration {
+            gravity: Vec2::ZERO,
+            timestep_mode: rapier::TimestepMode::Fixed { dt: (1. / TICKS_PER_SECOND) as f32, substeps: 1 },
+            ..default()
+        })
+        .add_plugins(rapier::RapierPhysicsPlugin::<rapier::NoUserData>::pixels_per_meter(PIXELS_PER_METER).in_fixed_schedule())
+        .configure_sets(FixedUpdate, rapier::PhysicsSet::SyncBackend.after(GameSet::Scoring))
+        // every serve, bounce, push, resize and reset after a goal has to be in before the step
+        .add_systems(FixedUpdate, (attach_bodies, push_velocities, resize_paddle_bodies, pause_simulation).chain().after(GameSet::Scoring).before(rapier::PhysicsSet::SyncBackend))
+        .add_systems(FixedUpdate, (pull_velocities, collision_events).chain().after(rapier::PhysicsSet::Writeback));
+    }
+}
+
+fn attach_bodies(mut commands: Commands, query: Query<(Entity, &Collider, Has<Ball>, Has<Paddle>), Added<Collider>>) {
+    for (entity, collider, is_ball, is_paddle) in query.iter() {
+        let half_extents = collider.0 / 2.;
+        if is_ball {
+            commands.entity(entity).insert((
+                rapier::RigidBody::Dynamic,
+                rapier::Collider::ball(half_extents.x),
+                rapier::Restitution { coefficient: 1., combine_rule: rapier::CoefficientCombineRule::Max },
+                rapier::Friction { coefficient: 0., combine_rule: rapier::CoefficientCombineRule::Min },
+                rapier::GravityScale(0.),
+                rapier::LockedAxes::ROTATION_LOCKED,
+                rapier::Ccd::enabled(),
+                rapier::ActiveEvents::COLLISION_EVENTS,
+                rapier::Velocity::zero(),
+            ));
+        } else if is_paddle {
+            commands.entity(entity).insert((
+                rapier::RigidBody::KinematicVelocityBased,
+                rapier::Collider::cuboid(half_extents.x, half_extents.y),
+                rapier::Velocity::zero(),
+            ));
+        } else {
+            commands.entity(entity).insert((
+                rapier::RigidBody::Fixed,
+                rapier::Collider::cuboid(half_extents.x, half_extents.y),
+            ));
+        }
+    }
+}
+
+// handicaps, power-ups, overtime and retuning all change a paddle's size after its body is made
+fn resize_paddle_bodies(mut query: Query<(&Collider, &mut rapier::Collider), (Changed<Collider>, With<Paddle>)>) {
+    for (collider, mut body_collider) in query.iter_mut() {
+        let half_extents = collider.0 / 2.;
+        *body_collider = rapier::Collider::cuboid(half_extents.x, half_extents.y);
+    }
+}
+
+// gameplay systems own `Velocity`; hand it to rapier before each step
+fn push_velocities(mut query: Query<(&Velocity, &mut rapier::Velocity)>) {
+    for (velocity, mut body_velocity) in query.iter_mut() {
+        body_velocity.linvel = velocity.0;
+    }
+}
+
+fn pull_velocities(mut query: Query<(&mut Velocity, &rapier::Velocity), With<Ball>>) {
+    for (mut velocity, body_velocity) in query.iter_mut() {
+        velocity.0 = body_velocity.linvel;
+    }
+}
+
+// the step runs every tick, so it's told whether play is moving on this one
+fn pause_simulation(state: Res<State<GameState>>, session: Option<Res<NetSession>>, mut config: ResMut<rapier::RapierConfiguration>) {
+    config.physics_pipeline_active = ticking(state, session);
+}
+
+// rapier only reflects the ball, so paddle hits are turned into the same angled, quickening return the hand-rolled physics gives
+fn collision_events(mut collisions: EventReader<rapier::CollisionEvent>, mut balls: Query<(&Transform, &mut Velocity, &mut rapier::Velocity, &mut BallSpeed), With<Ball>>, paddles: Query<(&Transform, &Collider, &Velocity), (With<Paddle>, Without<Ball>)>, config: Res<GameplayConfig>, mut events: EventWriter<BallCollided>) {
+    for collision in collisions.read() {
+        let (first, second) = if let rapier::CollisionEvent::Started(first, second, _) = collision {
+            (*first, *second)
+        } else { continue; };
+
+        let (ball, other) = if balls.contains(first) { (first, second) } else if balls.contains(second) { (second, first) } else { continue; };
+        let (ball_transform, mut velocity, mut body_velocity, mut speed) = balls.get_mut(ball).unwrap();
+
+        let paddle = paddles.get(other).ok();
+        let paddle_offset = paddle.map(|(paddle_transform, collider, _)| hit_offset(ball_transform.translation, paddle_transform.translation, collider.0));
+        events.send(BallCollided { ball, other, paddle_offset });
+
+        let (paddle_transform, collider, paddle_velocity) = if let Some(paddle) = paddle { paddle } else { continue; };
+        let to_ball = ball_transform.translation.truncate() - paddle_transform.translation.truncate();
+        // off the paddle's end rather than its face, which rapier's reflection already handles
+        if to_ball.y.abs() > collider.0.y / 2. {
+            continue;
+        }
+        let hit = SweepHit { time: 0., normal: Vec2::X * to_ball.x.signum() };
+        velocity.0 = bounce(velocity.0, &mut speed, &hit, paddle_offset, paddle_velocity.0, &config);
+        body_velocity.linvel = velocity.0;
+    }
+}
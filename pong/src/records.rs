@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GameMode, GameState, MatchRules, score::{Rally, Scoreboard}, stats::MatchStats, storage, survival::SurvivalRecord};
+
+const RECORDS_PATH: &str = "records.ron";
+
+pub struct RecordsPlugin;
+
+impl Plugin for RecordsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(storage::load::<Records>(RECORDS_PATH))
+        .add_systems(Startup, seed_survival_best)
+        .add_systems(OnEnter(GameState::GameOver), record_match)
+        .add_systems(Update, record_rally.run_if(in_state(GameState::Playing)));
+    }
+}
+
+// bests across every session, saved with the active profile; the old records file only seeds the first profile
+#[derive(Resource, Default, Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Records {
+    pub longest_rally: u32,
+    // seconds from the first serve to the winning point against the computer
+    pub fastest_win: Option<f32>,
+    // (player, computer) points of the most lopsided set won against the computer
+    pub biggest_shutout: Option<(u32, u32)>,
+    pub survival_time: f32,
+}
+
+// practice and the attract demo don't count
+fn counts_for_records(mode: GameMode) -> bool {
+    !matches!(mode, GameMode::Practice | GameMode::Demo)
+}
+
+pub fn seed_survival_best(records: Res<Records>, mut survival: ResMut<SurvivalRecord>) {
+    survival.best = records.survival_time;
+}
+
+fn record_rally(rally: Res<Rally>, mode: Res<GameMode>, mut records: ResMut<Records>) {
+    if counts_for_records(*mode) && rally.hits > records.longest_rally {
+        records.longest_rally = rally.hits;
+    }
+}
+
+fn record_match(mode: Res<GameMode>, scoreboard: Res<Scoreboard>, rules: Res<MatchRules>, stats: Res<MatchStats>, survival: Res<SurvivalRecord>, mut records: ResMut<Records>) {
+    match *mode {
+        GameMode::Survival if survival.current > records.survival_time => records.survival_time = survival.current,
+        GameMode::VersusComputer => {
+            if scoreboard.winner(&rules) != Some(true) {
+                return;
+            }
+
+            if records.fastest_win.is_none_or(|fastest| stats.duration < fastest) {
+                records.fastest_win = Some(stats.duration);
+            }
+
+            let margin = |(player, computer): (u32, u32)| player.saturating_sub(computer);
+            let shutout = scoreboard.sets.iter().copied().filter(|(player, computer)| player > computer).max_by_key(|set| margin(*set));
+            if let Some(shutout) = shutout {
+                if records.biggest_shutout.is_none_or(|biggest| margin(shutout) > margin(biggest)) {
+                    records.biggest_shutout = Some(shutout);
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{GameMode, GameState, ball::{Ball, BallDestroyed, Serve}, paddle::Paddle, score::Scoreboard, theme::Theme};
+
+// ten minutes at 60fps; older frames are dropped first
+const MAX_FRAMES: usize = 36_000;
+// how many seconds of match one second of holding left or right covers
+const SCRUB_SPEED: f32 = 4.;
+// how much of the run up to a goal is shown again, and how slowly
+const GOAL_REPLAY_SECONDS: f32 = 1.5;
+const GOAL_REPLAY_SPEED: f32 = 0.5;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_state::<ReplayState>()
+        .init_resource::<ReplayRecording>()
+        .init_resource::<ReplayPlayback>()
+        .add_systems(OnExit(GameState::Menu), clear_recording)
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, clear_recording)
+        .add_systems(PostUpdate, record_frame.run_if(in_state(GameState::Playing)).run_if(in_state(ReplayState::Off)))
+        .add_systems(PostUpdate, start_goal_replay.after(record_frame).run_if(in_state(GameState::Playing)).run_if(in_state(ReplayState::Off)).run_if(on_event::<BallDestroyed>()).run_if(not(resource_equals(GameMode::Online))))
+        .add_systems(OnEnter(ReplayState::Watching), (start_playback, spawn_replay_hud))
+        .add_systems(OnExit(ReplayState::Watching), (end_playback, despawn_replay))
+        .add_systems(OnEnter(ReplayState::Goal), (start_goal_playback, spawn_goal_banner))
+        .add_systems(OnExit(ReplayState::Goal), (end_playback, despawn_replay, resume_time))
+        .add_systems(Update, (playback_controls, drive_playback, update_replay_hud).chain().run_if(in_state(ReplayState::Watching)))
+        .add_systems(Update, (goal_replay_controls, drive_playback).chain().run_if(in_state(ReplayState::Goal)).run_if(in_state(GameState::Playing)))
+        .add_systems(Update, abandon_goal_replay.run_if(in_state(ReplayState::Goal)).run_if(in_state(GameState::Menu).or_else(in_state(GameState::GameOver))));
+    }
+}
+
+// watching a replay happens on top of the game over state, so the match itself never restarts
+#[derive(States, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReplayState {
+    #[default]
+    Off,
+    Watching,
+    // the last moments before a goal again in slow motion, with the match held still until it's done
+    Goal,
+}
+
+struct ReplayFrame {
+    // seconds of play since the recording started
+    time: f32,
+    paddles: Vec<(Entity, Transform)>,
+    balls: Vec<Vec3>,
+    // (player, computer) points
+    score: (u32, u32),
+}
+
+#[derive(Resource, Default)]
+pub struct ReplayRecording {
+    frames: VecDeque<ReplayFrame>,
+    elapsed: f32,
+}
+
+impl ReplayRecording {
+    fn start(&self) -> f32 {
+        self.frames.front().map_or(0., |frame| frame.time)
+    }
+
+    fn end(&self) -> f32 {
+        self.frames.back().map_or(0., |frame| frame.time)
+    }
+
+    // the last frame recorded at or before the given time
+    fn frame_at(&self, time: f32) -> Option<&ReplayFrame> {
+        let index = self.frames.partition_point(|frame| frame.time <= time).saturating_sub(1);
+        self.frames.get(index)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ReplayPlayback {
+    time: f32,
+    playing: bool,
+}
+
+#[derive(Component)]
+pub struct ReplayBall;
+
+#[derive(Component)]
+pub struct ReplayHud;
+
+#[derive(Component)]
+pub struct ReplayProgress;
+
+#[derive(Component)]
+pub struct ReplayText;
+
+fn clear_recording(mut recording: ResMut<ReplayRecording>) {
+    *recording = ReplayRecording::default();
+}
+
+fn record_frame(mut recording: ResMut<ReplayRecording>, time: Res<Time>, paddles: Query<(Entity, &Transform), With<Paddle>>, balls: Query<&Transform, With<Ball>>, scoreboard: Res<Scoreboard>) {
+    recording.elapsed += time.delta_seconds();
+    let frame = ReplayFrame {
+        time: recording.elapsed,
+        paddles: paddles.iter().map(|(entity, transform)| (entity, *transform)).collect(),
+        balls: balls.iter().map(|transform| transform.translation).collect(),
+        score: (scoreboard.player, scoreboard.computer),
+    };
+
+    if recording.frames.len() >= MAX_FRAMES {
+        recording.frames.pop_front();
+    }
+    recording.frames.push_back(frame);
+}
+
+// only once the last ball is gone and the next serve is lined up, so multiball goals don't each get one
+fn start_goal_replay(mut events: EventReader<BallDestroyed>, serve: Res<Serve>, mut next_state: ResMut<NextState<ReplayState>>) {
+    events.clear();
+
+    if serve.is_counting_down() && serve.countdown.elapsed().is_zero() {
+        next_state.set(ReplayState::Goal);
+    }
+}
+
+fn start_goal_playback(mut playback: ResMut<ReplayPlayback>, recording: Res<ReplayRecording>, mut time: ResMut<Time<Virtual>>, mut balls: Query<&mut Visibility, With<Ball>>) {
+    *playback = ReplayPlayback { time: (recording.end() - GOAL_REPLAY_SECONDS).max(recording.start()), playing: true };
+    time.pause();
+
+    // the ball waiting to be served
+    for mut visibility in balls.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+// the match's own clock is stopped, so this runs on real time
+fn goal_replay_controls(input: Res<Input<KeyCode>>, time: Res<Time<Real>>, recording: Res<ReplayRecording>, mut playback: ResMut<ReplayPlayback>, mut next_state: ResMut<NextState<ReplayState>>) {
+    playback.time += time.delta_seconds() * GOAL_REPLAY_SPEED;
+    if playback.time >= recording.end() || input.get_just_pressed().next().is_some() {
+        next_state.set(ReplayState::Off);
+    }
+}
+
+// the match ended from the pause screen partway through
+fn abandon_goal_replay(mut next_state: ResMut<NextState<ReplayState>>) {
+    next_state.set(ReplayState::Off);
+}
+
+fn spawn_goal_banner(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        ReplayHud,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                bottom: Val::Px(40.),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn(TextBundle::from_section("REPLAY - any key to skip", TextStyle {
+            font_size: 40.,
+            color: theme.palette().text,
+            ..default()
+        }));
+    });
+}
+
+fn start_playback(mut playback: ResMut<ReplayPlayback>, recording: Res<ReplayRecording>, mut balls: Query<&mut Visibility, With<Ball>>) {
+    *playback = ReplayPlayback { time: recording.start(), playing: true };
+
+    // whatever is left on the field at the end of the match would get in the way
+    for mut visibility in balls.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+// put the paddles back where the match left them
+fn end_playback(recording: Res<ReplayRecording>, mut paddles: Query<&mut Transform, With<Paddle>>, mut balls: Query<&mut Visibility, With<Ball>>) {
+    if let Some(last) = recording.frames.back() {
+        for (entity, recorded) in last.paddles.iter() {
+            if let Ok(mut transform) = paddles.get_mut(*entity) {
+                *transform = *recorded;
+            }
+        }
+    }
+
+    for mut visibility in balls.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn despawn_replay(mut commands: Commands, query: Query<Entity, Or<(With<ReplayBall>, With<ReplayHud>)>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn spawn_replay_hud(mut commands: Commands, theme: Res<Theme>) {
+    let text = theme.palette().text;
+    commands.spawn((
+        ReplayHud,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.),
+                width: Val::Percent(100.),
+                bottom: Val::Px(40.),
+                ..default()
+            },
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn((
+            ReplayText,
+            TextBundle::from_section("", TextStyle {
+                font_size: 40.,
+                color: text,
+                ..default()
+            }),
+        ));
+
+        parent.spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(60.),
+                height: Val::Px(12.),
+                border: UiRect::all(Val::Px(2.)),
+                ..default()
+            },
+            border_color: text.into(),
+            ..default()
+        }).with_children(|parent| {
+            parent.spawn((
+                ReplayProgress,
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(0.),
+                        height: Val::Percent(100.),
+                        ..default()
+                    },
+                    background_color: text.into(),
+                    ..default()
+                },
+            ));
+        });
+
+        parent.spawn(TextBundle::from_section("Space play/pause - Left/Right scrub - Enter to return", TextStyle {
+            font_size: 28.,
+            color: Color::rgb(0.7, 0.7, 0.7),
+            ..default()
+        }));
+    });
+}
+
+fn playback_controls(input: Res<Input<KeyCode>>, time: Res<Time>, recording: Res<ReplayRecording>, mut playback: ResMut<ReplayPlayback>, mut next_state: ResMut<NextState<ReplayState>>) {
+    if input.just_pressed(KeyCode::Return) || input.just_pressed(KeyCode::Back) {
+        next_state.set(ReplayState::Off);
+        return;
+    }
+
+    if input.just_pressed(KeyCode::Space) {
+        // starting again from the end rewinds
+        if !playback.playing && playback.time >= recording.end() {
+            playback.time = recording.start();
+        }
+        playback.playing = !playback.playing;
+    }
+
+    let scrub = match (input.pressed(KeyCode::Left), input.pressed(KeyCode::Right)) {
+        (true, false) => -SCRUB_SPEED,
+        (false, true) => SCRUB_SPEED,
+        _ => 0.,
+    };
+    let step = if scrub != 0. { scrub } else if playback.playing { 1. } else { 0. };
+
+    playback.time = (playback.time + step * time.delta_seconds()).clamp(recording.start(), recording.end());
+    if playback.time >= recording.end() {
+        playback.playing = false;
+    }
+}
+
+fn drive_playback(mut commands: Commands, asset_server: Res<AssetServer>, recording: Res<ReplayRecording>, playback: Res<ReplayPlayback>, mut paddles: Query<&mut Transform, (With<Paddle>, Without<ReplayBall>)>, mut balls: Query<(Entity, &mut Transform), With<ReplayBall>>) {
+    let frame = if let Some(frame) = recording.frame_at(playback.time) { frame } else { return; };
+
+    for (entity, recorded) in frame.paddles.iter() {
+        if let Ok(mut transform) = paddles.get_mut(*entity) {
+            *transform = *recorded;
+        }
+    }
+
+    let mut positions = frame.balls.iter();
+    for (entity, mut transform) in balls.iter_mut() {
+        match positions.next() {
+            Some(position) => transform.translation = *position,
+            None => commands.entity(entity).despawn_recursive(),
+        }
+    }
+    for position in positions {
+        commands.spawn((
+            ReplayBall,
+            SpriteBundle {
+                texture: asset_server.load("sprites/ball.png"),
+                transform: Transform::from_translation(*position),
+                ..default()
+            },
+        ));
+    }
+}
+
+fn update_replay_hud(recording: Res<ReplayRecording>, playback: Res<ReplayPlayback>, mut texts: Query<&mut Text, With<ReplayText>>, mut progress: Query<&mut Style, With<ReplayProgress>>) {
+    let (player, computer) = recording.frame_at(playback.time).map_or((0, 0), |frame| frame.score);
+    let played = playback.time - recording.start();
+    let length = recording.end() - recording.start();
+
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = format!("REPLAY {}  {computer} - {player}  {} / {}", if playback.playing { ">" } else { "||" }, clock(played), clock(length));
+    }
+
+    let fraction = if length > 0. { played / length } else { 0. };
+    for mut style in progress.iter_mut() {
+        style.width = Val::Percent(fraction * 100.);
+    }
+}
+
+fn clock(seconds: f32) -> String {
+    format!("{}:{:02}", seconds as u32 / 60, seconds as u32 % 60)
+}
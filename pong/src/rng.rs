@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::{GameState, ball::spawn_ball, reset_match};
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(OnExit(GameState::Menu), restart_rng.before(spawn_ball))
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, restart_rng.before(reset_match));
+    }
+}
+
+// every random number that affects play comes from here, so a fixed seed plays out the same serves every match
+#[derive(Resource, Deref, DerefMut, Clone)]
+pub struct GameRng {
+    // None picks a fresh seed every match
+    pub seed: Option<u64>,
+    // whichever seed the current match started from, fixed or fresh, so it can be played again
+    pub match_seed: u64,
+    #[deref]
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: Option<u64>) -> Self {
+        let match_seed = seed.unwrap_or_else(rand::random);
+        Self { seed, match_seed, rng: StdRng::seed_from_u64(match_seed) }
+    }
+
+    pub fn restart(&mut self) {
+        self.start_match(self.seed.unwrap_or_else(rand::random));
+    }
+
+    // online the host picks the seed and both machines start from it
+    pub fn start_match(&mut self, match_seed: u64) {
+        self.match_seed = match_seed;
+        self.rng = StdRng::seed_from_u64(match_seed);
+    }
+
+    pub fn coin_flip(&mut self) -> f32 {
+        let number = self.rng.gen::<u32>() % 2;
+        if number == 0 { -1. } else { 1. }
+    }
+
+    // standard normal, by box-muller; mostly small, now and then a long way out
+    pub fn normal(&mut self) -> f32 {
+        let radius = (-2. * (1. - self.rng.gen::<f32>()).ln()).sqrt();
+        radius * (std::f32::consts::TAU * self.rng.gen::<f32>()).cos()
+    }
+}
+
+// `--seed 1234` on the command line
+pub fn seed_argument() -> Option<u64> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            return args.next()?.parse().ok();
+        }
+    }
+    None
+}
+
+pub fn restart_rng(mut random: ResMut<GameRng>) {
+    random.restart();
+}
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest}};
+
+use crate::{GameState, ball::{BallCollided, BallDestroyed}, paddle::{HumanControlled, Player, connected_gamepads}, settings::rumble_enabled};
+
+const HIT_SECONDS: f32 = 0.08;
+const HIT_INTENSITY: GamepadRumbleIntensity = GamepadRumbleIntensity { strong_motor: 0., weak_motor: 0.4 };
+const CONCEDE_SECONDS: f32 = 0.35;
+const CONCEDE_INTENSITY: GamepadRumbleIntensity = GamepadRumbleIntensity { strong_motor: 0.9, weak_motor: 0.5 };
+
+pub struct RumblePlugin;
+
+impl Plugin for RumblePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (rumble_on_hits, rumble_on_goals).run_if(in_state(GameState::Playing)).run_if(rumble_enabled));
+    }
+}
+
+// the pad driving whichever paddle it was; the computer's and a remote player's have none
+fn rumble(requests: &mut EventWriter<GamepadRumbleRequest>, gamepads: &Gamepads, controls: &HumanControlled, seconds: f32, intensity: GamepadRumbleIntensity) {
+    if let Some(gamepad) = connected_gamepads(gamepads).get(controls.gamepad).copied() {
+        requests.send(GamepadRumbleRequest::Add { gamepad, duration: Duration::from_secs_f32(seconds), intensity });
+    }
+}
+
+fn rumble_on_hits(mut events: EventReader<BallCollided>, mut requests: EventWriter<GamepadRumbleRequest>, gamepads: Res<Gamepads>, paddles: Query<&HumanControlled>) {
+    for event in events.read() {
+        if let Ok(controls) = paddles.get(event.other) {
+            rumble(&mut requests, &gamepads, controls, HIT_SECONDS, HIT_INTENSITY);
+        }
+    }
+}
+
+fn rumble_on_goals(mut events: EventReader<BallDestroyed>, mut requests: EventWriter<GamepadRumbleRequest>, gamepads: Res<Gamepads>, paddles: Query<(&HumanControlled, Has<Player>)>) {
+    for event in events.read() {
+        // whoever the goal went past
+        for (controls, _) in paddles.iter().filter(|(_, player)| *player != event.player_scored) {
+            rumble(&mut requests, &gamepads, controls, CONCEDE_SECONDS, CONCEDE_INTENSITY);
+        }
+    }
+}
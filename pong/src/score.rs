@@ -0,0 +1,185 @@
+use bevy::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GameMode, GameSet, GameState, MatchRules, Velocity, ball::{BallCollided, BallDestroyed}, console::{ConsoleAppExt, argument}, is_match, offline_match, paddle::Paddle, streaks::streak_multiplier};
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Scoreboard>()
+        .init_resource::<Rally>()
+        .register_type::<Scoreboard>()
+        .register_console_command("score", "score <player|opponent> <points> - in the current set", score_command)
+        .add_systems(FixedUpdate, ((award_points, check_winner).chain().run_if(is_match), count_rally).in_set(GameSet::Scoring));
+    }
+}
+
+#[derive(Resource, Default, Clone, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+pub struct Scoreboard {
+    // points in the current set
+    pub player: u32,
+    pub computer: u32,
+    pub player_sets: u32,
+    pub computer_sets: u32,
+    // finished sets as (player, computer) points
+    pub sets: Vec<(u32, u32)>,
+    // the players switch ends after every set
+    pub sides_swapped: bool,
+    // points in a row without conceding; either side's goes back to nothing when the other scores
+    pub player_streak: u32,
+    pub computer_streak: u32,
+}
+
+impl Scoreboard {
+    // which way the player's goal is along x
+    pub fn player_side(&self) -> f32 {
+        if self.sides_swapped { -1. } else { 1. }
+    }
+
+    // sets open with each side's head start already on the board
+    pub fn start_set(&mut self, rules: &MatchRules) {
+        self.player = rules.player_handicap.head_start;
+        self.computer = rules.opponent_handicap.head_start;
+        self.player_streak = 0;
+        self.computer_streak = 0;
+    }
+
+    pub fn streak(&self, player: bool) -> u32 {
+        if player { self.player_streak } else { self.computer_streak }
+    }
+
+    // one goal's worth, more for a side on a streak when the rules reward them
+    pub fn award(&mut self, player_scored: bool, rules: &MatchRules) {
+        let points = if rules.streaks { streak_multiplier(self.streak(player_scored)) } else { 1 };
+        if player_scored {
+            self.player += points;
+            self.player_streak += 1;
+            self.computer_streak = 0;
+        } else {
+            self.computer += points;
+            self.computer_streak += 1;
+            self.player_streak = 0;
+        }
+    }
+
+    pub fn finish_set(&mut self) {
+        if self.player > self.computer {
+            self.player_sets += 1;
+        } else {
+            self.computer_sets += 1;
+        }
+        self.sets.push((self.player, self.computer));
+        self.player = 0;
+        self.computer = 0;
+    }
+
+    // either side one point away from taking the match; the clock decides timed matches, so never there
+    pub fn match_point(&self, rules: &MatchRules) -> bool {
+        if rules.time_limit.is_some() {
+            return false;
+        }
+
+        let set_point = rules.point_target.saturating_sub(1);
+        let player = self.player == set_point && self.player_sets + 1 >= rules.sets_to_win();
+        let computer = self.computer == set_point && self.computer_sets + 1 >= rules.sets_to_win();
+        player || computer
+    }
+
+    // both sides one point away from taking the match, which can only happen in the deciding set
+    pub fn both_at_match_point(&self, rules: &MatchRules) -> bool {
+        let set_point = rules.point_target.saturating_sub(1);
+        let deciding_set = self.player_sets + 1 == rules.sets_to_win() && self.computer_sets + 1 == rules.sets_to_win();
+        rules.time_limit.is_none() && set_point > 0 && deciding_set && self.player == set_point && self.computer == set_point
+    }
+
+    // Some(true) once the player has taken enough sets, Some(false) for their opponent
+    pub fn winner(&self, rules: &MatchRules) -> Option<bool> {
+        if self.player_sets >= rules.sets_to_win() {
+            Some(true)
+        } else if self.computer_sets >= rules.sets_to_win() {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Resource, Default, Clone)]
+pub struct Rally {
+    // paddle hits since the last goal
+    pub hits: u32,
+    // longest rally this session
+    pub best: u32,
+    // the current rally has beaten a previous best
+    pub new_best: bool,
+}
+
+impl Rally {
+    pub fn reset(&mut self) {
+        self.hits = 0;
+        self.new_best = false;
+    }
+}
+
+pub fn award_points(mut events: EventReader<BallDestroyed>, mut scoreboard: ResMut<Scoreboard>, rules: Res<MatchRules>) {
+    for event in events.read() {
+        scoreboard.award(event.player_scored, &rules);
+    }
+}
+
+pub fn check_winner(mut scoreboard: ResMut<Scoreboard>, rules: Res<MatchRules>, mode: Res<GameMode>, mut next_state: ResMut<NextState<GameState>>, mut paddles: Query<(&mut Transform, &mut Velocity), With<Paddle>>) {
+    // the clock decides timed matches
+    if rules.time_limit.is_some() || (scoreboard.player < rules.point_target && scoreboard.computer < rules.point_target) {
+        return;
+    }
+
+    scoreboard.finish_set();
+    if scoreboard.winner(&rules).is_some() {
+        next_state.set(if *mode == GameMode::Demo { GameState::Menu } else { GameState::GameOver });
+        return;
+    }
+
+    // switch ends for the next set
+    scoreboard.start_set(&rules);
+    scoreboard.sides_swapped = !scoreboard.sides_swapped;
+    for (mut transform, mut velocity) in paddles.iter_mut() {
+        transform.translation.x = -transform.translation.x;
+        transform.translation.y = 0.;
+        velocity.0 = Vec2::ZERO;
+    }
+}
+
+pub fn count_rally(mut rally: ResMut<Rally>, mut collisions: EventReader<BallCollided>, mut goals: EventReader<BallDestroyed>) {
+    if goals.read().count() > 0 {
+        rally.reset();
+    }
+
+    let hits = collisions.read().filter(|event| event.is_paddle_hit()).count() as u32;
+    if hits == 0 {
+        return;
+    }
+
+    rally.hits += hits;
+    if rally.hits > rally.best {
+        rally.new_best |= rally.best > 0;
+        rally.best = rally.hits;
+    }
+}
+
+fn score_command(world: &mut World, arguments: &[&str]) -> Result<String, String> {
+    offline_match(world)?;
+    let side = *arguments.first().ok_or("missing side")?;
+    let points = argument::<u32>(arguments, 1, "score")?;
+
+    let mut scoreboard = world.resource_mut::<Scoreboard>();
+    match side {
+        "player" => scoreboard.player = points,
+        "opponent" | "computer" => scoreboard.computer = points,
+        _ => return Err(format!("{side} isn't player or opponent")),
+    }
+    Ok(format!("{side} score set to {points}"))
+}
@@ -0,0 +1,225 @@
+use bevy::{prelude::*, ui::UiScale, window::{PrimaryWindow, WindowMode, WindowResized}};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{VIRTUAL_RESOLUTION, ai::{AiPersonality, Difficulty}, bindings::Bindings, daily::DailyChallenge, demos::DemoPlayer, mixer::AudioMixer, skins::PaddleSkins, storage, theme::Theme};
+
+pub const CONFIG_PATH: &str = "config.ron";
+pub const UI_SCALES: [f32; 6] = [0.75, 1., 1.25, 1.5, 1.75, 2.];
+// ticks, past this online play feels sluggish whatever the connection
+pub const MAX_INPUT_DELAY: u32 = 6;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, apply_ui_scale.run_if(resource_changed::<Settings>().or_else(on_event::<WindowResized>())))
+        .add_systems(Update, save_config.run_if(config_changed).run_if(not(preferences_overridden)));
+
+        // a browser tab can't be made fullscreen without the player asking, and the canvas already fills the page
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Update, (toggle_fullscreen, apply_window_mode.run_if(resource_changed::<Settings>())).chain());
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, Reflect)]
+pub enum ControlScheme {
+    #[default]
+    Keyboard,
+    // player one's paddle follows the cursor
+    Mouse,
+}
+
+impl ControlScheme {
+    pub fn next(&self) -> Self {
+        match self {
+            ControlScheme::Keyboard => ControlScheme::Mouse,
+            ControlScheme::Mouse => ControlScheme::Keyboard,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ControlScheme::Keyboard => "Keyboard",
+            ControlScheme::Mouse => "Mouse",
+        }
+    }
+}
+
+// what each finished match is written out as, to a file of its own in the data directory
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, Reflect)]
+pub enum StatsExport {
+    #[default]
+    Off,
+    Json,
+    Csv,
+}
+
+impl StatsExport {
+    pub fn next(&self) -> Self {
+        match self {
+            StatsExport::Off => StatsExport::Json,
+            StatsExport::Json => StatsExport::Csv,
+            StatsExport::Csv => StatsExport::Off,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StatsExport::Off => "Off",
+            StatsExport::Json => "JSON",
+            StatsExport::Csv => "CSV",
+        }
+    }
+}
+
+// everything a player sets up once and expects to find again next time, saved together in one file
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Config {
+    pub settings: Settings,
+    pub mixer: AudioMixer,
+    pub difficulty: Difficulty,
+    pub personality: AiPersonality,
+    pub theme: Theme,
+    pub bindings: Bindings,
+    pub skins: PaddleSkins,
+}
+
+impl Config {
+    pub fn load() -> Self {
+        storage::load(CONFIG_PATH)
+    }
+}
+
+// player preferences that outlive a single match, and the game itself
+#[derive(Resource, Clone, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Settings {
+    pub ball_trail: bool,
+    pub screen_shake: bool,
+    pub crt: bool,
+    // draws the ball's predicted path for players still getting the hang of it
+    pub trajectory_assist: bool,
+    // multiplies every hud and menu size, text included
+    pub ui_scale: f32,
+    pub control_scheme: ControlScheme,
+    // the player starts matches on the left end instead of the right
+    pub left_handed: bool,
+    // borderless fullscreen, or a resizable window
+    pub fullscreen: bool,
+    // fixes every match's serves and other random play, the --seed flag takes priority
+    pub seed: Option<u64>,
+    // an http address serving the online leaderboard, none keeps scores offline
+    pub leaderboard_url: Option<String>,
+    // what submitted scores go under
+    pub player_name: String,
+    pub stats_export: StatsExport,
+    // short buzzes on the player's controller for hits and goals against them
+    pub rumble: bool,
+    // reads the score out after every point, for players who can't easily see it
+    pub speak_score: bool,
+    // how many ticks online presses wait before they count, so they've usually reached the other machine by then and it needn't roll back
+    pub input_delay: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self { ball_trail: true, screen_shake: true, crt: false, trajectory_assist: false, ui_scale: 1., control_scheme: ControlScheme::Keyboard, left_handed: false, fullscreen: true, seed: None, leaderboard_url: None, player_name: "Player".to_string(), stats_export: StatsExport::Off, rumble: true, speak_score: false, input_delay: 2 }
+    }
+}
+
+pub fn ball_trail_enabled(settings: Res<Settings>) -> bool {
+    settings.ball_trail
+}
+
+pub fn rumble_enabled(settings: Res<Settings>) -> bool {
+    settings.rumble
+}
+
+pub fn speak_score_enabled(settings: Res<Settings>) -> bool {
+    settings.speak_score
+}
+
+impl Settings {
+    pub fn next_ui_scale(&self) -> f32 {
+        let index = UI_SCALES.iter().position(|scale| *scale == self.ui_scale).unwrap_or(0);
+        UI_SCALES[(index + 1) % UI_SCALES.len()]
+    }
+
+    pub fn next_input_delay(&self) -> u32 {
+        (self.input_delay + 1) % (MAX_INPUT_DELAY + 1)
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        if self.fullscreen { WindowMode::BorderlessFullscreen } else { WindowMode::Windowed }
+    }
+}
+
+// the ui is laid out for the virtual resolution, so it shrinks and grows with the window on top of the player's own scale
+fn apply_ui_scale(settings: Res<Settings>, windows: Query<&Window, With<PrimaryWindow>>, mut ui_scale: ResMut<UiScale>) {
+    let window = if let Ok(window) = windows.get_single() { window } else { return; };
+
+    let fit = (window.width() / VIRTUAL_RESOLUTION.x).min(window.height() / VIRTUAL_RESOLUTION.y);
+    // minimized
+    if fit <= 0. {
+        return;
+    }
+    let scale = (settings.ui_scale * fit) as f64;
+    if ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn toggle_fullscreen(input: Res<Input<KeyCode>>, mut settings: ResMut<Settings>) {
+    let alt = input.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+    if alt && input.just_pressed(KeyCode::Return) {
+        settings.fullscreen = !settings.fullscreen;
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn apply_window_mode(settings: Res<Settings>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    let mode = settings.window_mode();
+    for mut window in windows.iter_mut() {
+        if window.mode != mode {
+            window.mode = mode;
+        }
+    }
+}
+
+// everything goes in at startup together, so only later changes need writing back
+fn config_changed(settings: Res<Settings>, mixer: Res<AudioMixer>, difficulty: Res<Difficulty>, personality: Res<AiPersonality>, theme: Res<Theme>, bindings: Res<Bindings>, skins: Res<PaddleSkins>) -> bool {
+    let changed = settings.is_changed() || mixer.is_changed() || difficulty.is_changed() || personality.is_changed() || theme.is_changed() || bindings.is_changed() || skins.is_changed();
+    changed && !settings.is_added()
+}
+
+// a played back demo or the daily challenge puts its own difficulty and the like in place of the player's, which aren't theirs to keep;
+// putting the player's back afterwards is a change too, so anything held back meanwhile is written then
+pub fn preferences_overridden(demo: Res<DemoPlayer>, daily: Res<DailyChallenge>) -> bool {
+    demo.is_playing() || daily.is_active()
+}
+
+fn save_config(settings: Res<Settings>, mixer: Res<AudioMixer>, difficulty: Res<Difficulty>, personality: Res<AiPersonality>, theme: Res<Theme>, bindings: Res<Bindings>, skins: Res<PaddleSkins>) {
+    let config = Config {
+        settings: settings.clone(),
+        mixer: mixer.clone(),
+        difficulty: *difficulty,
+        personality: *personality,
+        theme: *theme,
+        bindings: *bindings,
+        skins: *skins,
+    };
+    storage::save(CONFIG_PATH, &config);
+}
+
+pub fn mouse_control_enabled(settings: Res<Settings>) -> bool {
+    settings.control_scheme == ControlScheme::Mouse
+}
+
+pub fn trajectory_assist_enabled(settings: Res<Settings>) -> bool {
+    settings.trajectory_assist
+}
@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+
+use rand::Rng;
+
+use crate::{ball::BallDestroyed, gameplay::GameplayConfig, settings::Settings};
+
+// how far the camera jumps for a goal scored at serve speed
+const SHAKE_PER_SERVE_SPEED: f32 = 10.;
+const MAX_SHAKE: f32 = 30.;
+// fraction of the shake left after a second
+const SHAKE_FALLOFF: f32 = 0.02;
+
+pub struct ShakePlugin;
+
+impl Plugin for ShakePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, (start_shake, shake_camera).chain());
+    }
+}
+
+// the camera's offset from its resting place is tracked separately so the world itself never moves
+#[derive(Component, Default)]
+pub struct CameraShake {
+    pub amplitude: f32,
+    pub offset: Vec2,
+}
+
+fn start_shake(mut events: EventReader<BallDestroyed>, settings: Res<Settings>, config: Res<GameplayConfig>, mut cameras: Query<&mut CameraShake>) {
+    for event in events.read() {
+        if !settings.screen_shake {
+            continue;
+        }
+
+        let amplitude = (event.speed / config.ball_speed * SHAKE_PER_SERVE_SPEED).min(MAX_SHAKE);
+        for mut shake in cameras.iter_mut() {
+            shake.amplitude = shake.amplitude.max(amplitude);
+        }
+    }
+}
+
+// on real time, so it still settles while a goal replay holds the match still
+fn shake_camera(mut cameras: Query<(&mut CameraShake, &mut Transform)>, time: Res<Time<Real>>) {
+    let mut random = rand::thread_rng();
+
+    for (mut shake, mut transform) in cameras.iter_mut() {
+        if shake.amplitude == 0. && shake.offset == Vec2::ZERO {
+            continue;
+        }
+
+        shake.amplitude *= SHAKE_FALLOFF.powf(time.delta_seconds());
+        if shake.amplitude < 0.5 {
+            shake.amplitude = 0.;
+        }
+
+        let offset = Vec2::new(random.gen_range(-1.0..1.0), random.gen_range(-1.0..1.0)) * shake.amplitude;
+        transform.translation += (offset - shake.offset).extend(0.);
+        shake.offset = offset;
+    }
+}
@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::theme::Theme;
+
+pub struct SkinPlugin;
+
+impl Plugin for SkinPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<PaddleSkins>()
+        .add_systems(Update, update_skin_previews);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum PaddleShape {
+    #[default]
+    Flat,
+    Rounded,
+    Grooved,
+}
+
+impl PaddleShape {
+    pub const ALL: [PaddleShape; 3] = [PaddleShape::Flat, PaddleShape::Rounded, PaddleShape::Grooved];
+
+    pub fn sprite(&self) -> &'static str {
+        match self {
+            PaddleShape::Flat => "sprites/paddle.png",
+            PaddleShape::Rounded => "sprites/paddle_rounded.png",
+            PaddleShape::Grooved => "sprites/paddle_grooved.png",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            PaddleShape::Flat => PaddleShape::Rounded,
+            PaddleShape::Rounded => PaddleShape::Grooved,
+            PaddleShape::Grooved => PaddleShape::Flat,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PaddleShape::Flat => "Flat",
+            PaddleShape::Rounded => "Rounded",
+            PaddleShape::Grooved => "Grooved",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum SkinColor {
+    // whatever the theme gives that side
+    #[default]
+    Theme,
+    Red,
+    Green,
+    Gold,
+    Violet,
+    White,
+}
+
+impl SkinColor {
+    pub fn next(&self) -> Self {
+        match self {
+            SkinColor::Theme => SkinColor::Red,
+            SkinColor::Red => SkinColor::Green,
+            SkinColor::Green => SkinColor::Gold,
+            SkinColor::Gold => SkinColor::Violet,
+            SkinColor::Violet => SkinColor::White,
+            SkinColor::White => SkinColor::Theme,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SkinColor::Theme => "Theme",
+            SkinColor::Red => "Red",
+            SkinColor::Green => "Green",
+            SkinColor::Gold => "Gold",
+            SkinColor::Violet => "Violet",
+            SkinColor::White => "White",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaddleSkin {
+    pub shape: PaddleShape,
+    pub color: SkinColor,
+}
+
+impl PaddleSkin {
+    pub fn color(&self, theme_color: Color) -> Color {
+        match self.color {
+            SkinColor::Theme => theme_color,
+            SkinColor::Red => Color::rgb(0.86, 0.2, 0.2),
+            SkinColor::Green => Color::rgb(0.2, 0.78, 0.35),
+            SkinColor::Gold => Color::rgb(0.95, 0.76, 0.2),
+            SkinColor::Violet => Color::rgb(0.6, 0.36, 0.9),
+            SkinColor::White => Color::WHITE,
+        }
+    }
+}
+
+// the player's side is paddle one, the opponent's paddle two
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaddleSkins {
+    pub player: PaddleSkin,
+    pub opponent: PaddleSkin,
+}
+
+impl PaddleSkins {
+    pub fn skin(&self, player: bool) -> &PaddleSkin {
+        if player { &self.player } else { &self.opponent }
+    }
+
+    pub fn skin_mut(&mut self, player: bool) -> &mut PaddleSkin {
+        if player { &mut self.player } else { &mut self.opponent }
+    }
+
+    pub fn tint(&self, player: bool, theme: &Theme) -> Color {
+        let palette = theme.palette();
+        self.skin(player).color(if player { palette.player } else { palette.opponent })
+    }
+}
+
+// a paddle on the skins page showing one side's current choice, true for the player's side
+#[derive(Component)]
+pub struct SkinPreview(pub bool);
+
+fn update_skin_previews(skins: Res<PaddleSkins>, theme: Res<Theme>, asset_server: Res<AssetServer>, mut previews: Query<(Ref<SkinPreview>, &mut UiImage, &mut BackgroundColor)>) {
+    for (preview, mut image, mut background) in previews.iter_mut() {
+        if !preview.is_added() && !skins.is_changed() && !theme.is_changed() {
+            continue;
+        }
+
+        image.texture = asset_server.load(skins.skin(preview.0).shape.sprite());
+        background.0 = skins.tint(preview.0, &theme);
+    }
+}
@@ -0,0 +1,120 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::process::{Child, Command};
+
+use bevy::prelude::*;
+
+use crate::{GameMode, GameState, is_match, mixer::AudioMixer, score::Scoreboard, settings::speak_score_enabled};
+
+const ONES: [&str; 20] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen"];
+const TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+pub struct SpeechPlugin;
+
+impl Plugin for SpeechPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Speaker>()
+        .add_systems(Update, speak_score.run_if(in_state(GameState::Playing)).run_if(is_match).run_if(speak_score_enabled).run_if(resource_changed::<Scoreboard>()));
+    }
+}
+
+fn number_words(number: u32) -> String {
+    match number {
+        0..=19 => ONES[number as usize].to_string(),
+        20..=99 => match number % 10 {
+            0 => TENS[number as usize / 10].to_string(),
+            ones => format!("{} {}", TENS[number as usize / 10], ONES[ones as usize]),
+        },
+        // nobody's getting this far, but each digit is still better than silence
+        _ => number.to_string().chars().map(|digit| ONES[digit.to_digit(10).unwrap_or(0) as usize]).collect::<Vec<_>>().join(" "),
+    }
+}
+
+// reads things out with whatever the platform has: the browser's speech synthesis, or a command line voice natively
+#[derive(Resource, Default)]
+pub struct Speaker {
+    // the voice still talking, cut off when there's something newer to say
+    #[cfg(not(target_arch = "wasm32"))]
+    speaking: Option<Child>,
+    // set once no voice could be found, so it isn't looked for after every point
+    #[cfg(not(target_arch = "wasm32"))]
+    unavailable: bool,
+}
+
+impl Speaker {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn say(&mut self, text: &str, volume: f32) {
+        if let Some(mut previous) = self.speaking.take() {
+            let _ = previous.kill();
+            let _ = previous.wait();
+        }
+        if self.unavailable {
+            return;
+        }
+
+        for mut command in voice_commands(text, volume) {
+            if let Ok(child) = command.spawn() {
+                self.speaking = Some(child);
+                return;
+            }
+        }
+        warn!("no text to speech voice found, scores won't be spoken");
+        self.unavailable = true;
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn say(&mut self, text: &str, volume: f32) {
+        let synthesis = if let Some(synthesis) = web_sys::window().and_then(|window| window.speech_synthesis().ok()) { synthesis } else { return; };
+        let utterance = if let Ok(utterance) = web_sys::SpeechSynthesisUtterance::new_with_text(text) { utterance } else { return; };
+        utterance.set_volume(volume);
+        synthesis.cancel();
+        synthesis.speak(&utterance);
+    }
+}
+
+// tried in order until one starts
+#[cfg(not(target_arch = "wasm32"))]
+fn voice_commands(text: &str, volume: f32) -> Vec<Command> {
+    if cfg!(target_os = "macos") {
+        let mut say = Command::new("say");
+        say.arg(text);
+        vec![say]
+    } else if cfg!(target_os = "windows") {
+        let script = format!("Add-Type -AssemblyName System.Speech; $voice = New-Object System.Speech.Synthesis.SpeechSynthesizer; $voice.Volume = {}; $voice.Speak('{text}')", (volume * 100.).round());
+        let mut powershell = Command::new("powershell");
+        powershell.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+        // without a console window flashing up every point
+        #[cfg(target_os = "windows")]
+        std::os::windows::process::CommandExt::creation_flags(&mut powershell, CREATE_NO_WINDOW);
+        vec![powershell]
+    } else {
+        // espeak's amplitude runs to 200, but past 100 it starts to distort
+        let amplitude = (volume * 100.).round().to_string();
+        let mut commands: Vec<Command> = ["espeak-ng", "espeak"].into_iter().map(|program| {
+            let mut espeak = Command::new(program);
+            espeak.args(["-a", &amplitude, text]);
+            espeak
+        }).collect();
+        let mut spd_say = Command::new("spd-say");
+        spd_say.arg(text);
+        commands.push(spd_say);
+        commands
+    }
+}
+
+// "seven, four" after each point, the player's own score first
+fn speak_score(scoreboard: Res<Scoreboard>, mode: Res<GameMode>, mixer: Res<AudioMixer>, mut speaker: ResMut<Speaker>, mut last_score: Local<(u32, u32)>) {
+    let score = (scoreboard.player, scoreboard.computer);
+    if score == *last_score {
+        return;
+    }
+    *last_score = score;
+
+    // nobody's listening to the attract screen, and a fresh match has nothing to read out yet
+    if *mode == GameMode::Demo || score == (0, 0) {
+        return;
+    }
+    speaker.say(&format!("{}, {}", number_words(score.0), number_words(score.1)), mixer.sfx_volume());
+}
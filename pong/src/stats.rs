@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GameSet, GameState, Velocity, ball::{Ball, BallCollided, BallDestroyed, Serve}, paddle::Player, score::{Scoreboard, award_points}, watchdog::BallRescued};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{GameMode, settings::{Settings, StatsExport}, storage};
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<MatchStats>()
+        .init_resource::<MatchLog>()
+        .add_systems(OnExit(GameState::Menu), reset_stats)
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, reset_stats)
+        // goals have to be read before the next serve is set up and before the ends switch
+        .add_systems(FixedUpdate, record_stats.before(award_points).in_set(GameSet::Scoring));
+
+        // a browser tab has nowhere to put the files
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(OnEnter(GameState::GameOver), export_match.run_if(stats_export_enabled));
+    }
+}
+
+// per-side counts are [opponent, player], matching the left to right order of the scores
+#[derive(Resource, Default, Clone, Debug, Serialize, Deserialize)]
+pub struct MatchStats {
+    pub longest_rally: u32,
+    pub hits: [u32; 2],
+    pub max_ball_speed: f32,
+    // points where the receiver never got a paddle on the serve
+    pub aces: [u32; 2],
+    pub points_on_serve: [u32; 2],
+    // seconds of play, serves included
+    pub duration: f32,
+    // balls the watchdog had to put back in play
+    pub rescues: u32,
+    rally: u32,
+}
+
+#[derive(Serialize)]
+struct PointRecord {
+    // seconds into the match
+    time: f32,
+    player_scored: bool,
+    // paddle hits in the rally that ended with this point
+    rally: u32,
+    // points each side has won so far this match, sets included
+    player_points: u32,
+    opponent_points: u32,
+}
+
+#[derive(Serialize)]
+struct HitRecord {
+    time: f32,
+    player: bool,
+    // where on the paddle, from -1 (bottom edge) to 1 (top edge)
+    offset: f32,
+    position: Vec2,
+}
+
+// everything that happened in the match point by point and hit by hit, for exporting; too much to send online every snapshot
+#[derive(Resource, Default, Serialize)]
+pub struct MatchLog {
+    points: Vec<PointRecord>,
+    hits: Vec<HitRecord>,
+}
+
+fn side(player: bool) -> usize {
+    player as usize
+}
+
+fn reset_stats(mut stats: ResMut<MatchStats>, mut log: ResMut<MatchLog>) {
+    *stats = MatchStats::default();
+    *log = MatchLog::default();
+}
+
+fn record_stats(mut stats: ResMut<MatchStats>, mut log: ResMut<MatchLog>, (mut collisions, mut goals, mut rescues): (EventReader<BallCollided>, EventReader<BallDestroyed>, EventReader<BallRescued>), paddles: Query<Has<Player>>, balls: Query<(&Transform, &Velocity), With<Ball>>, (serve, scoreboard): (Res<Serve>, Res<Scoreboard>), time: Res<Time>) {
+    stats.duration += time.delta_seconds();
+
+    for event in collisions.read() {
+        let offset = if let Some(offset) = event.paddle_offset { offset } else { continue; };
+        let player = if let Ok(player) = paddles.get(event.other) { player } else { continue; };
+
+        let position = balls.get(event.ball).map_or(Vec2::ZERO, |(transform, _)| transform.translation.truncate());
+        log.hits.push(HitRecord { time: stats.duration, player, offset, position });
+
+        stats.hits[side(player)] += 1;
+        stats.rally += 1;
+        stats.longest_rally = stats.longest_rally.max(stats.rally);
+    }
+
+    // the last serve went away from whoever served it
+    let player_served = serve.direction != scoreboard.player_side();
+    for event in goals.read() {
+        let scorer = side(event.player_scored);
+        if stats.rally == 0 {
+            stats.aces[scorer] += 1;
+        }
+        if event.player_scored == player_served {
+            stats.points_on_serve[scorer] += 1;
+        }
+
+        let (player_points, opponent_points) = log.points.last().map_or((0, 0), |point| (point.player_points, point.opponent_points));
+        log.points.push(PointRecord {
+            time: stats.duration,
+            player_scored: event.player_scored,
+            rally: stats.rally,
+            player_points: player_points + event.player_scored as u32,
+            opponent_points: opponent_points + !event.player_scored as u32,
+        });
+        stats.rally = 0;
+    }
+
+    for event in rescues.read() {
+        warn!("rescued a stuck ball: {:?}", event.reason);
+        stats.rescues += 1;
+    }
+
+    for (_, velocity) in balls.iter() {
+        stats.max_ball_speed = stats.max_ball_speed.max(velocity.0.length());
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn stats_export_enabled(settings: Res<Settings>, mode: Res<GameMode>) -> bool {
+    settings.stats_export != StatsExport::Off && !matches!(*mode, GameMode::Practice | GameMode::Demo)
+}
+
+// one file per match, named for when it ended so they sort in order
+#[cfg(not(target_arch = "wasm32"))]
+fn export_match(settings: Res<Settings>, stats: Res<MatchStats>, log: Res<MatchLog>) {
+    let ended = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    let (extension, contents) = match settings.stats_export {
+        StatsExport::Off => return,
+        StatsExport::Json => ("json", match_json(&stats, &log)),
+        StatsExport::Csv => ("csv", match_csv(&log)),
+    };
+    storage::save_text(&format!("matches/match-{ended}.{extension}"), &contents);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn match_json(stats: &MatchStats, log: &MatchLog) -> String {
+    #[derive(Serialize)]
+    struct ExportedMatch<'a> {
+        stats: &'a MatchStats,
+        #[serde(flatten)]
+        log: &'a MatchLog,
+    }
+
+    serde_json::to_string_pretty(&ExportedMatch { stats, log }).unwrap_or_default()
+}
+
+// points and hits in a single table in the order they happened, each row leaving the other kind's columns empty
+#[cfg(not(target_arch = "wasm32"))]
+fn match_csv(log: &MatchLog) -> String {
+    let side = |player: bool| if player { "player" } else { "opponent" };
+    let mut rows: Vec<(f32, String)> = log.points.iter()
+        .map(|point| (point.time, format!("point,{:.3},{},{},{},{},,,", point.time, side(point.player_scored), point.rally, point.player_points, point.opponent_points)))
+        .chain(log.hits.iter().map(|hit| (hit.time, format!("hit,{:.3},{},,,,{:.3},{:.1},{:.1}", hit.time, side(hit.player), hit.offset, hit.position.x, hit.position.y))))
+        .collect();
+    rows.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut csv = "event,time,side,rally,player_points,opponent_points,offset,x,y\n".to_string();
+    for (_, row) in rows {
+        csv.push_str(&row);
+        csv.push('\n');
+    }
+    csv
+}
@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+
+use serde::{Serialize, de::DeserializeOwned};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{env, fs, path::PathBuf};
+
+#[cfg(not(target_arch = "wasm32"))]
+const DATA_DIR_NAME: &str = "pong";
+
+// saved data lives in files in the platform's data directory natively, and in localStorage under the same names on the web
+#[cfg(not(target_arch = "wasm32"))]
+fn data_dir() -> Option<PathBuf> {
+    let home = || env::var_os("HOME").map(PathBuf::from);
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()).map(PathBuf::from).or_else(|| home().map(|home| home.join(".local").join("share")))
+    };
+    base.map(|base| base.join(DATA_DIR_NAME))
+}
+
+// the working directory if there's no telling where the data directory is
+#[cfg(not(target_arch = "wasm32"))]
+pub fn path(name: &str) -> PathBuf {
+    data_dir().map_or_else(|| PathBuf::from(name), |dir| dir.join(name))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(name: &str) -> Option<String> {
+    // saves from before the data directory are picked up from next to the game, and move over the next time they're written
+    fs::read_to_string(path(name)).or_else(|_| fs::read_to_string(name)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(name: &str, contents: &str) -> Result<(), String> {
+    let path = path(name);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+    }
+    fs::write(path, contents).map_err(|error| error.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read(name: &str) -> Option<String> {
+    local_storage()?.get_item(name).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write(name: &str, contents: &str) -> Result<(), String> {
+    let storage = local_storage().ok_or("localStorage is unavailable")?;
+    storage.set_item(name, contents).map_err(|error| format!("{error:?}"))
+}
+
+// anything missing or unreadable falls back to the defaults
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    let contents = if let Some(contents) = read(name) { contents } else { return T::default(); };
+
+    ron::from_str(&contents).unwrap_or_else(|error| {
+        warn!("ignoring unreadable {name}: {error}");
+        T::default()
+    })
+}
+
+// for files meant to be read outside the game rather than loaded back in, which only makes sense natively
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_text(name: &str, contents: &str) {
+    if let Err(error) = write(name, contents) {
+        warn!("couldn't save {name}: {error}");
+    }
+}
+
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    let result = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())
+        .and_then(|contents| write(name, &contents));
+
+    if let Err(error) = result {
+        warn!("couldn't save {name}: {error}");
+    }
+}
+
+// for binary files, which go in localStorage as hex since it only holds text
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_bytes(name: &str, bytes: &[u8]) {
+    let path = path(name);
+    let result = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(path, bytes));
+    if let Err(error) = result {
+        warn!("couldn't save {name}: {error}");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_bytes(name: &str, bytes: &[u8]) {
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    if let Err(error) = write(name, &hex) {
+        warn!("couldn't save {name}: {error}");
+    }
+}
+
+// a name in the data directory, or natively any path at all, so files from someone else can be opened where they are
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_bytes(name: &str) -> Option<Vec<u8>> {
+    fs::read(path(name)).or_else(|_| fs::read(name)).ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load_bytes(name: &str) -> Option<Vec<u8>> {
+    let hex = read(name)?;
+    (0..hex.len()).step_by(2).map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok()).collect()
+}
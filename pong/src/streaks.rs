@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+
+use crate::{GameMode, GameState, MatchEntity, MatchRules, score::Scoreboard, theme::Theme, ui::SideRow};
+
+// every this many points in a row the next ones are worth one more
+const STREAK_STEP: u32 = 3;
+const MAX_MULTIPLIER: u32 = 3;
+const HOT_COLOR: Color = Color::rgb(1., 0.78, 0.25);
+const BLAZING_COLOR: Color = Color::rgb(1., 0.4, 0.15);
+const PULSE_SPEED: f32 = 8.;
+const PULSE_SIZE: f32 = 0.08;
+// the jump the label gives when the multiplier goes up
+const POP_SECONDS: f32 = 0.3;
+const POP_SIZE: f32 = 0.5;
+
+pub struct StreakPlugin;
+
+impl Plugin for StreakPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(OnExit(GameState::Menu), spawn_streak_hud.run_if(streak_scoring))
+        .add_systems(Update, update_streak_hud.run_if(streak_scoring));
+    }
+}
+
+pub fn streak_scoring(rules: Res<MatchRules>, mode: Res<GameMode>) -> bool {
+    rules.streaks && mode.is_match()
+}
+
+// what the next point is worth to a side that's won the last `streak` in a row
+pub fn streak_multiplier(streak: u32) -> u32 {
+    (1 + streak / STREAK_STEP).min(MAX_MULTIPLIER)
+}
+
+// under each side's score, true for the player's
+#[derive(Component)]
+struct StreakText {
+    player: bool,
+    multiplier: u32,
+    pop: Timer,
+}
+
+fn spawn_streak_hud(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        MatchEntity,
+        SideRow,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::SpaceAround,
+                width: Val::Percent(100.),
+                top: Val::Px(120.),
+                ..default()
+            },
+            ..default()
+        },
+    )).with_children(|parent| {
+        // laid out like the scores above them, the opponent's on the left
+        for player in [false, true] {
+            parent.spawn((
+                StreakText { player, multiplier: 1, pop: Timer::from_seconds(POP_SECONDS, TimerMode::Once) },
+                TextBundle::from_section("", TextStyle {
+                    font_size: 36.,
+                    color: theme.palette().text,
+                    ..default()
+                }),
+            ));
+        }
+    });
+}
+
+// the label heats up with the multiplier, and at the top one it won't sit still
+fn update_streak_hud(scoreboard: Res<Scoreboard>, theme: Res<Theme>, time: Res<Time>, mut labels: Query<(&mut StreakText, &mut Text, &mut Transform)>) {
+    for (mut label, mut text, mut transform) in labels.iter_mut() {
+        let streak = scoreboard.streak(label.player);
+        let multiplier = streak_multiplier(streak);
+        if multiplier > label.multiplier {
+            label.pop.reset();
+        }
+        label.multiplier = multiplier;
+        label.pop.tick(time.delta());
+
+        if scoreboard.is_changed() {
+            text.sections[0].value = match (streak, multiplier) {
+                (0, _) => String::new(),
+                (_, 1) => format!("Streak {streak}"),
+                _ => format!("Streak {streak}  x{multiplier}"),
+            };
+            text.sections[0].style.color = match multiplier {
+                1 => theme.palette().text,
+                2 => HOT_COLOR,
+                _ => BLAZING_COLOR,
+            };
+        }
+
+        let pulse = if multiplier == MAX_MULTIPLIER { (time.elapsed_seconds() * PULSE_SPEED).sin() * PULSE_SIZE } else { 0. };
+        let pop = label.pop.percent_left() * POP_SIZE;
+        transform.scale = Vec3::splat(1. + pulse + pop);
+    }
+}
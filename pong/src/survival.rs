@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+use crate::{ArenaConfig, Collider, GameMode, GameState, MatchEntity, ball::{BallDestroyed, Serve}, score::Scoreboard, spawn_match, theme::Theme};
+
+pub struct SurvivalPlugin;
+
+impl Plugin for SurvivalPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<SurvivalRecord>()
+        .add_systems(OnExit(GameState::Menu), (spawn_back_wall.after(spawn_match), spawn_survival_hud, reset_run).run_if(resource_equals(GameMode::Survival)))
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, reset_run)
+        .add_systems(Update, (
+            tick_survival_time,
+            end_run,
+        ).run_if(in_state(GameState::Playing)).run_if(resource_equals(GameMode::Survival)))
+        .add_systems(Update, update_survival_hud.run_if(resource_equals(GameMode::Survival)));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SurvivalRecord {
+    // seconds survived so far this run
+    pub current: f32,
+    pub best: f32,
+}
+
+#[derive(Component)]
+pub struct BackWall;
+
+#[derive(Component)]
+pub struct SurvivalTime;
+
+fn reset_run(mut record: ResMut<SurvivalRecord>) {
+    record.current = 0.;
+}
+
+// on the opponent's end, wherever the player chose to stand
+fn spawn_back_wall(mut commands: Commands, arena: Res<ArenaConfig>, scoreboard: Res<Scoreboard>, theme: Res<Theme>) {
+    let size = Vec2::new(17., arena.height);
+    commands.spawn((
+        MatchEntity,
+        BackWall,
+        SpriteBundle {
+            sprite: Sprite {
+                color: theme.palette().wall,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(-scoreboard.player_side() * arena.paddle_x(), 0., 0.)),
+            ..default()
+        },
+        Collider::cuboid(size.x, size.y),
+    ));
+}
+
+fn spawn_survival_hud(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        MatchEntity,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn((
+            SurvivalTime,
+            TextBundle::from_section("", TextStyle {
+                font_size: 96.,
+                color: theme.palette().text,
+                ..default()
+            }),
+        ));
+    });
+}
+
+// the clock only runs while the ball is actually in play
+fn tick_survival_time(mut record: ResMut<SurvivalRecord>, serve: Res<Serve>, time: Res<Time>) {
+    if serve.is_counting_down() {
+        return;
+    }
+
+    record.current += time.delta_seconds();
+}
+
+fn end_run(mut events: EventReader<BallDestroyed>, mut record: ResMut<SurvivalRecord>, mut next_state: ResMut<NextState<GameState>>) {
+    if events.read().count() == 0 {
+        return;
+    }
+
+    record.best = record.best.max(record.current);
+    next_state.set(GameState::GameOver);
+}
+
+fn update_survival_hud(record: Res<SurvivalRecord>, mut query: Query<&mut Text, With<SurvivalTime>>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("{:.1}", record.current);
+    }
+}
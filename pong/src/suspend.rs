@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, app::AppExit};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ArenaConfig, GameMode, GameState, MatchRules, QuitConfirm, Velocity, ai::{AiPersonality, Difficulty}, ball::{Ball, BallSpeed, Serve, ball_bundle}, daily::DailyChallenge, demos::DemoPlayer, paddle::{Paddle, Player}, score::Scoreboard, storage, survival::SurvivalRecord, timed::MatchClock};
+
+const SUSPENDED_PATH: &str = "suspended.ron";
+
+pub struct SuspendPlugin;
+
+impl Plugin for SuspendPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(SuspendSlot(storage::load::<Option<SuspendedMatch>>(SUSPENDED_PATH)))
+        .add_systems(Update, continue_actions.run_if(in_state(GameState::Menu)))
+        .add_systems(OnEnter(GameState::Playing), resume_match.run_if(resource_exists::<Resuming>()))
+        // last, so a quit sent from anywhere this frame is seen before the app closes
+        .add_systems(Last, suspend_match.run_if(in_state(GameState::Playing).or_else(in_state(GameState::Paused))).run_if(suspendable));
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+struct SuspendedBall {
+    translation: Vec3,
+    velocity: Vec2,
+    speed: f32,
+}
+
+// a match as it stood when the player walked away from it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SuspendedMatch {
+    mode: GameMode,
+    difficulty: Difficulty,
+    personality: AiPersonality,
+    rules: MatchRules,
+    arena: ArenaConfig,
+    scoreboard: Scoreboard,
+    serve_elapsed: f32,
+    serve_direction: f32,
+    balls: Vec<SuspendedBall>,
+    player_paddle: Vec3,
+    opponent_paddle: Option<Vec3>,
+    clock_elapsed: f32,
+    tie_break: bool,
+    survival_seconds: f32,
+}
+
+// the one match waiting to be picked up again, kept across runs
+#[derive(Resource, Default)]
+pub struct SuspendSlot(pub Option<SuspendedMatch>);
+
+// taken out of the slot on the way into the match, and laid over it once the fresh one has spawned
+#[derive(Resource)]
+pub struct Resuming(SuspendedMatch);
+
+#[derive(Component)]
+pub struct ContinueButton;
+
+pub fn can_suspend(mode: &GameMode, daily: &DailyChallenge, demo: &DemoPlayer) -> bool {
+    // the daily challenge is one sitting, and a demo plays out the same however it's left
+    matches!(mode, GameMode::VersusComputer | GameMode::TwoPlayer | GameMode::Survival) && !daily.is_active() && !demo.is_playing()
+}
+
+fn suspendable(mode: Res<GameMode>, daily: Res<DailyChallenge>, demo: Res<DemoPlayer>) -> bool {
+    can_suspend(&mode, &daily, &demo)
+}
+
+// quitting the game mid-match keeps it, as does suspending from the pause screen
+fn suspend_match(mut slot: ResMut<SuspendSlot>, (mut exit, input, state, quit): (EventReader<AppExit>, Res<Input<KeyCode>>, Res<State<GameState>>, Res<QuitConfirm>), mut next_state: ResMut<NextState<GameState>>, (mode, difficulty, personality, rules, arena): (Res<GameMode>, Res<Difficulty>, Res<AiPersonality>, Res<MatchRules>, Res<ArenaConfig>), (scoreboard, serve, clock, survival): (Res<Scoreboard>, Res<Serve>, Res<MatchClock>, Res<SurvivalRecord>), balls: Query<(&Transform, &Velocity, &BallSpeed), With<Ball>>, paddles: Query<(&Transform, Has<Player>), With<Paddle>>) {
+    let quitting = exit.read().next().is_some();
+    // s would otherwise also answer the quit prompt
+    let suspending = *state.get() == GameState::Paused && !quit.0 && !quit.is_changed() && input.just_pressed(KeyCode::S);
+    if !quitting && !suspending {
+        return;
+    }
+
+    let player_paddle = if let Some((transform, _)) = paddles.iter().find(|(_, player)| *player) { transform.translation } else { return; };
+    let suspended = SuspendedMatch {
+        mode: *mode,
+        difficulty: *difficulty,
+        personality: *personality,
+        rules: rules.clone(),
+        arena: *arena,
+        scoreboard: scoreboard.clone(),
+        serve_elapsed: serve.countdown.elapsed_secs(),
+        serve_direction: serve.direction,
+        balls: balls.iter().map(|(transform, velocity, speed)| SuspendedBall { translation: transform.translation, velocity: velocity.0, speed: speed.0 }).collect(),
+        player_paddle,
+        opponent_paddle: paddles.iter().find(|(_, player)| !player).map(|(transform, _)| transform.translation),
+        clock_elapsed: clock.remaining.elapsed_secs(),
+        tie_break: clock.tie_break,
+        survival_seconds: survival.current,
+    };
+    // written straight away, since a quitting game won't be around to do it later
+    storage::save(SUSPENDED_PATH, &Some(&suspended));
+    slot.0 = Some(suspended);
+    if suspending {
+        next_state.set(GameState::Menu);
+    }
+}
+
+fn continue_actions(mut commands: Commands, query: Query<&Interaction, (Changed<Interaction>, With<ContinueButton>)>, mut slot: ResMut<SuspendSlot>, (mut mode, mut difficulty, mut personality, mut rules, mut arena): (ResMut<GameMode>, ResMut<Difficulty>, ResMut<AiPersonality>, ResMut<MatchRules>, ResMut<ArenaConfig>), mut next_state: ResMut<NextState<GameState>>) {
+    for interaction in query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // once picked up it's gone, the same as any other match
+        let suspended = if let Some(suspended) = slot.0.take() { suspended } else { continue; };
+        storage::save(SUSPENDED_PATH, &slot.0);
+
+        *mode = suspended.mode;
+        *difficulty = suspended.difficulty;
+        *personality = suspended.personality;
+        *rules = suspended.rules.clone();
+        *arena = suspended.arena;
+        commands.insert_resource(Resuming(suspended));
+        next_state.set(GameState::Playing);
+    }
+}
+
+// after everything leaving the menu has set up a fresh match, which this then moves on to where the old one was
+fn resume_match(mut commands: Commands, resuming: Res<Resuming>, asset_server: Res<AssetServer>, (mut scoreboard, mut serve, mut clock, mut survival): (ResMut<Scoreboard>, ResMut<Serve>, ResMut<MatchClock>, ResMut<SurvivalRecord>), mut balls: Query<(Entity, &mut Transform, &mut Velocity, &mut BallSpeed), With<Ball>>, mut paddles: Query<(&mut Transform, Has<Player>), (With<Paddle>, Without<Ball>)>) {
+    let suspended = &resuming.0;
+    *scoreboard = suspended.scoreboard.clone();
+    serve.direction = suspended.serve_direction;
+    serve.countdown.set_elapsed(Duration::from_secs_f32(suspended.serve_elapsed));
+    clock.remaining.set_elapsed(Duration::from_secs_f32(suspended.clock_elapsed));
+    clock.tie_break = suspended.tie_break;
+    survival.current = suspended.survival_seconds;
+
+    // the fresh match's ball is reused, any more there were come back as new ones
+    let mut existing = balls.iter_mut();
+    for ball in suspended.balls.iter() {
+        if let Some((_, mut transform, mut velocity, mut speed)) = existing.next() {
+            transform.translation = ball.translation;
+            velocity.0 = ball.velocity;
+            speed.0 = ball.speed;
+        } else {
+            commands.spawn(ball_bundle(&asset_server, ball.translation, ball.velocity)).insert(BallSpeed(ball.speed));
+        }
+    }
+    for (entity, ..) in existing {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for (mut transform, player) in paddles.iter_mut() {
+        let saved = if player { Some(suspended.player_paddle) } else { suspended.opponent_paddle };
+        if let Some(saved) = saved {
+            transform.translation = saved;
+        }
+    }
+    commands.remove_resource::<Resuming>();
+}
@@ -0,0 +1,397 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, ecs::system::RunSystemOnce};
+
+use crate::{ArenaConfig, Collider, GameMode, GameState, MatchRules, Velocity, ball::{BALL_SPEED_LIMIT, Ball, BallCollided, BallSpeed, MAX_BOUNCE_ANGLE, MIN_BALL_SPEED, Serve, SweepHit, ball_collision, bounce, limit_ball_velocity, penetration_depth, sweep_aabb}, gameplay::GameplayConfig, headless::headless_app, paddle::Paddle, rng::GameRng, score::Scoreboard, streaks::streak_multiplier};
+#[cfg(feature = "dynamic_mods")]
+use crate::mods::{ModAppExt, ModMutators, MutatorMod, load_library};
+
+const SEED: u64 = 7;
+// a frame's travel at serving speed is well under this, so a ball placed this far out is past the goal line before anything can stop it
+const PAST_GOAL: f32 = 40.;
+
+// a match against the computer in the headless app, moved on a frame at a time
+struct TestMatch {
+    app: App,
+}
+
+impl TestMatch {
+    fn new() -> Self {
+        let mut app = headless_app(GameRng::new(Some(SEED)));
+        app.insert_resource(GameMode::VersusComputer);
+        app.world.resource_mut::<NextState<GameState>>().set(GameState::Playing);
+        app.update();
+        Self { app }
+    }
+
+    // the opening serve already gone, so from here the ball only goes where the test puts it
+    fn served() -> Self {
+        let mut game = Self::new();
+        game.app.world.resource_mut::<Serve>().hurry();
+        game.step(1);
+        game
+    }
+
+    fn step(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    fn ball(&mut self) -> Entity {
+        self.app.world.query_filtered::<Entity, With<Ball>>().single(&self.app.world)
+    }
+
+    fn place_ball(&mut self, position: Vec2, velocity: Vec2) {
+        let ball = self.ball();
+        let mut ball = self.app.world.entity_mut(ball);
+        ball.get_mut::<Transform>().unwrap().translation = position.extend(0.);
+        ball.get_mut::<Velocity>().unwrap().0 = velocity;
+    }
+
+    fn ball_position(&mut self) -> Vec2 {
+        let ball = self.ball();
+        self.app.world.get::<Transform>(ball).unwrap().translation.truncate()
+    }
+
+    fn ball_velocity(&mut self) -> Vec2 {
+        let ball = self.ball();
+        self.app.world.get::<Velocity>(ball).unwrap().0
+    }
+
+    fn ball_speed(&mut self) -> f32 {
+        let ball = self.ball();
+        self.app.world.get::<BallSpeed>(ball).unwrap().0
+    }
+
+    fn scoreboard(&self) -> &Scoreboard {
+        self.app.world.resource::<Scoreboard>()
+    }
+
+    fn config(&self) -> &GameplayConfig {
+        self.app.world.resource::<GameplayConfig>()
+    }
+
+    fn state(&self) -> GameState {
+        *self.app.world.resource::<State<GameState>>().get()
+    }
+
+    // just outside the goal on that side, still heading into it
+    fn goal(&self, side: f32) -> (Vec2, Vec2) {
+        let goal_line = self.app.world.resource::<ArenaConfig>().half_width();
+        (Vec2::new(side * (goal_line + PAST_GOAL), 0.), Vec2::new(side * self.config().ball_speed, 0.))
+    }
+}
+
+#[test]
+fn ball_crossing_right_goal_scores_for_the_computer() {
+    let mut game = TestMatch::served();
+    let (position, velocity) = game.goal(1.);
+    game.place_ball(position, velocity);
+    game.step(1);
+
+    assert_eq!((game.scoreboard().player, game.scoreboard().computer), (0, 1));
+}
+
+#[test]
+fn ball_crossing_left_goal_scores_for_the_player() {
+    let mut game = TestMatch::served();
+    let (position, velocity) = game.goal(-1.);
+    game.place_ball(position, velocity);
+    game.step(1);
+
+    assert_eq!((game.scoreboard().player, game.scoreboard().computer), (1, 0));
+}
+
+#[test]
+fn goals_follow_the_players_when_they_switch_ends() {
+    let mut game = TestMatch::served();
+    game.app.world.resource_mut::<Scoreboard>().sides_swapped = true;
+    let (position, velocity) = game.goal(1.);
+    game.place_ball(position, velocity);
+    game.step(1);
+
+    assert_eq!((game.scoreboard().player, game.scoreboard().computer), (1, 0));
+}
+
+#[test]
+fn streaks_are_worth_more_when_the_rules_reward_them() {
+    let rules = MatchRules { streaks: true, ..default() };
+    let mut scoreboard = Scoreboard::default();
+    let mut expected = 0;
+    for _ in 0..10 {
+        expected += streak_multiplier(scoreboard.player_streak);
+        scoreboard.award(true, &rules);
+    }
+    assert_eq!(scoreboard.player, expected);
+    assert!(expected > 10);
+
+    scoreboard.award(false, &rules);
+    assert_eq!((scoreboard.computer, scoreboard.player_streak, scoreboard.computer_streak), (1, 0, 1));
+}
+
+#[test]
+fn overtime_waits_for_match_point_in_the_deciding_set() {
+    let rules = MatchRules { best_of: 3, ..default() };
+    let set_point = rules.point_target - 1;
+    let mut scoreboard = Scoreboard { player: set_point, computer: set_point, ..default() };
+    assert!(!scoreboard.both_at_match_point(&rules));
+
+    scoreboard.player_sets = 1;
+    assert!(!scoreboard.both_at_match_point(&rules));
+
+    scoreboard.computer_sets = 1;
+    assert!(scoreboard.both_at_match_point(&rules));
+    assert!(Scoreboard { player: set_point, computer: set_point, ..default() }.both_at_match_point(&MatchRules::default()));
+}
+
+#[test]
+fn goal_recenters_the_ball_to_serve_at_the_side_that_conceded() {
+    let mut game = TestMatch::served();
+    let (position, velocity) = game.goal(1.);
+    game.place_ball(position, velocity);
+    game.step(1);
+
+    assert_eq!(game.ball_position(), Vec2::ZERO);
+    assert_eq!(game.ball_velocity(), Vec2::ZERO);
+    let serve = game.app.world.resource::<Serve>();
+    assert!(serve.is_counting_down());
+    // the computer scored, so the player's side has the serve coming at it
+    assert_eq!(serve.direction, game.scoreboard().player_side());
+}
+
+#[test]
+fn serve_launches_the_ball_at_serving_speed_once_the_countdown_runs_out() {
+    let mut game = TestMatch::new();
+    let countdown = game.app.world.resource::<Serve>().countdown.duration().as_secs_f32();
+    let direction = game.app.world.resource::<Serve>().direction;
+
+    // a frame short of the countdown it's still waiting
+    game.step((countdown * 60.) as u32 - 2);
+    assert_eq!(game.ball_velocity(), Vec2::ZERO);
+
+    game.step(3);
+    let velocity = game.ball_velocity();
+    assert_eq!(velocity.x.signum(), direction);
+    assert!((velocity.length() - game.config().ball_speed).abs() < 0.01);
+    assert_eq!(game.ball_speed(), game.config().ball_speed);
+}
+
+#[test]
+fn winning_the_last_point_of_the_match_ends_it() {
+    let mut game = TestMatch::served();
+    let point_target = game.app.world.resource::<MatchRules>().point_target;
+    game.app.world.resource_mut::<Scoreboard>().player = point_target - 1;
+    let (position, velocity) = game.goal(-1.);
+    game.place_ball(position, velocity);
+    game.step(2);
+
+    assert_eq!(game.scoreboard().player_sets, 1);
+    assert_eq!(game.scoreboard().sets, vec![(point_target, 0)]);
+    assert_eq!(game.state(), GameState::GameOver);
+}
+
+// the rapier backend does its own bouncing
+#[cfg(not(feature = "rapier"))]
+#[test]
+fn ball_bounces_off_the_top_wall() {
+    let mut game = TestMatch::served();
+    let top = game.app.world.resource::<ArenaConfig>().half_height();
+    game.place_ball(Vec2::new(0., top - 20.), Vec2::new(500., 300.));
+    game.step(3);
+
+    let velocity = game.ball_velocity();
+    assert_eq!(velocity, Vec2::new(500., -300.));
+    assert!(game.ball_position().y < top);
+}
+
+#[cfg(not(feature = "rapier"))]
+#[test]
+fn paddle_returns_the_ball_faster() {
+    let mut game = TestMatch::served();
+    let paddle_x = game.app.world.resource::<ArenaConfig>().paddle_x();
+    let speed = game.ball_speed();
+    game.place_ball(Vec2::new(paddle_x - 50., 0.), Vec2::new(speed, 0.));
+    game.step(3);
+
+    let velocity = game.ball_velocity();
+    let expected = speed + game.config().ball_speed_increment;
+    // straight back off the middle of the paddle
+    assert!(velocity.x < 0. && velocity.y.abs() < 0.01);
+    assert!((velocity.length() - expected).abs() < 0.01);
+    assert_eq!(game.ball_speed(), expected);
+}
+
+#[test]
+fn sweep_finds_the_face_a_ball_runs_into() {
+    let hit = sweep_aabb(Vec2::ZERO, Vec2::splat(30.), Vec2::new(100., 0.), Vec2::new(80., 0.), Vec2::new(20., 120.)).unwrap();
+    // the faces meet after 80 - 10 - 15 of the 100 travelled
+    assert!((hit.time - 0.55).abs() < 1e-5);
+    assert_eq!(hit.normal, Vec2::NEG_X);
+
+    assert!(sweep_aabb(Vec2::ZERO, Vec2::splat(30.), Vec2::new(100., 0.), Vec2::new(80., 200.), Vec2::new(20., 120.)).is_none());
+    assert!(sweep_aabb(Vec2::ZERO, Vec2::splat(30.), Vec2::new(-100., 0.), Vec2::new(80., 0.), Vec2::new(20., 120.)).is_none());
+}
+
+#[cfg(not(feature = "rapier"))]
+#[test]
+fn ball_heading_away_from_a_wall_end_is_not_held_there() {
+    let mut game = TestMatch::served();
+    let (left, top) = {
+        let arena = game.app.world.resource::<ArenaConfig>();
+        (-arena.half_width(), arena.half_height())
+    };
+    // tucked under the end of the top wall, moving out past it into the goal
+    game.place_ball(Vec2::new(left - 5., top - 10.), Vec2::new(-720., 130.));
+    game.step(2);
+
+    assert_eq!(game.scoreboard().player, 1);
+}
+
+#[test]
+fn a_ball_fast_enough_to_pass_a_paddle_in_one_frame_still_hits_it() {
+    // a 20 wide paddle, with the ball covering fifty times that in the frame
+    let hit = sweep_aabb(Vec2::ZERO, Vec2::splat(30.), Vec2::new(1000., 0.), Vec2::new(200., 0.), Vec2::new(20., 120.)).unwrap();
+    assert_eq!(hit.normal, Vec2::NEG_X);
+    assert!((hit.time - 0.175).abs() < 1e-6);
+
+    // and one that only gets as far as the paddle next frame doesn't touch it yet
+    assert!(sweep_aabb(Vec2::ZERO, Vec2::splat(30.), Vec2::new(100., 0.), Vec2::new(200., 0.), Vec2::new(20., 120.)).is_none());
+}
+
+#[test]
+fn ball_velocity_stays_within_the_speed_limits_and_bounce_angle() {
+    let mut world = World::new();
+    let fast = world.spawn((Ball, Velocity(Vec2::new(-4000., 0.)), BallSpeed(4000.))).id();
+    let slow = world.spawn((Ball, Velocity(Vec2::new(100., 0.)), BallSpeed(100.))).id();
+    let steep = world.spawn((Ball, Velocity(Vec2::new(100., 700.)), BallSpeed(707.))).id();
+    let waiting = world.spawn((Ball, Velocity(Vec2::ZERO), BallSpeed(700.))).id();
+    world.run_system_once(limit_ball_velocity);
+
+    let velocity = |entity: Entity| world.get::<Velocity>(entity).unwrap().0;
+    assert!((velocity(fast) - Vec2::new(-BALL_SPEED_LIMIT, 0.)).length() < 1e-2);
+    assert_eq!(world.get::<BallSpeed>(fast).unwrap().0, BALL_SPEED_LIMIT);
+    assert!((velocity(slow) - Vec2::new(MIN_BALL_SPEED, 0.)).length() < 1e-2);
+    assert_eq!(world.get::<BallSpeed>(slow).unwrap().0, MIN_BALL_SPEED);
+    // turned back to the steepest a bounce can send it, still heading the same way at the same speed
+    let angle = velocity(steep).y.atan2(velocity(steep).x);
+    assert!((angle - MAX_BOUNCE_ANGLE).abs() < 1e-4);
+    assert!((velocity(steep).length() - Vec2::new(100., 700.).length()).abs() < 1e-2);
+    // a ball still waiting for its serve is left alone
+    assert_eq!(velocity(waiting), Vec2::ZERO);
+}
+
+#[test]
+fn a_corner_only_turns_a_ball_moving_into_it() {
+    let (wall, wall_size) = (Vec2::new(0., 550.), Vec2::new(1920., 10.));
+    // just under the end of the wall and level with its face, drifting up as it heads out past the end
+    let hit = sweep_aabb(Vec2::new(-965., 530.), Vec2::splat(30.), Vec2::new(-12., 2.), wall, wall_size).unwrap();
+    assert!(!hit.is_corner());
+    assert_eq!(hit.normal, Vec2::NEG_Y);
+
+    // coming in at the corner from outside it, the corner is what it hits
+    let hit = sweep_aabb(Vec2::new(-990., 520.), Vec2::splat(30.), Vec2::new(20., 20.), wall, wall_size).unwrap();
+    assert!(hit.is_corner());
+}
+
+#[test]
+fn a_ball_a_paddle_has_moved_into_is_pushed_just_clear_of_it() {
+    let (paddle, paddle_size) = (Vec2::new(20., 0.), Vec2::new(20., 120.));
+    // five into the paddle's face
+    assert_eq!(penetration_depth(Vec2::ZERO, Vec2::splat(30.), paddle, paddle_size, Vec2::NEG_X), 5.);
+    // along a corner normal only as far as it takes to clear one axis
+    let normal = Vec2::new(-1., -1.).normalize();
+    let depth = penetration_depth(Vec2::new(0., 70.), Vec2::splat(30.), paddle, paddle_size, normal);
+    assert!((depth - 5. * 2f32.sqrt()).abs() < 1e-4);
+    // already clear
+    assert_eq!(penetration_depth(Vec2::new(-10., 0.), Vec2::splat(30.), paddle, paddle_size, Vec2::NEG_X), 0.);
+}
+
+// a ball arriving flat at the paddle face, `height` above the paddle's middle, and how it comes away
+fn paddle_return(height: f32) -> (Vec2, f32) {
+    let mut world = World::new();
+    let mut time = Time::<()>::default();
+    time.advance_by(Duration::from_secs_f32(1. / 60.));
+    world.insert_resource(time);
+    world.init_resource::<Events<BallCollided>>();
+    world.init_resource::<GameplayConfig>();
+    world.spawn((Paddle, Collider(Vec2::new(20., 120.)), Transform::default()));
+    let ball = world.spawn((Ball, Collider(Vec2::splat(30.)), Transform::from_xyz(-30., height, 0.), Velocity(Vec2::new(700., 0.)), BallSpeed(700.))).id();
+    world.run_system_once(ball_collision);
+
+    (world.get::<Velocity>(ball).unwrap().0, world.get::<BallSpeed>(ball).unwrap().0)
+}
+
+#[test]
+fn the_outer_fifth_of_a_paddle_smashes_the_ball_back_steep_and_fast() {
+    let (velocity, speed) = paddle_return(50.);
+    assert!(velocity.x < 0.);
+    assert!((velocity.y.atan2(-velocity.x) - MAX_BOUNCE_ANGLE).abs() < 1e-4);
+    assert!(velocity.length() > speed);
+    // the rally speed itself only goes up by the usual step
+    assert_eq!(speed, 735.);
+
+    let (velocity, speed) = paddle_return(30.);
+    assert!(velocity.y.atan2(-velocity.x) < MAX_BOUNCE_ANGLE);
+    assert!((velocity.length() - speed).abs() < 1e-2);
+}
+
+#[test]
+fn walls_turn_only_the_way_the_ball_was_heading_into_them() {
+    let config = GameplayConfig::default();
+    let mut speed = BallSpeed(config.ball_speed);
+    let wall = SweepHit { time: 0.5, normal: Vec2::NEG_Y };
+    assert_eq!(bounce(Vec2::new(300., 600.), &mut speed, &wall, None, Vec2::ZERO, &config), Vec2::new(300., -600.));
+    assert_eq!(speed.0, config.ball_speed);
+}
+
+#[test]
+fn paddle_edges_smash_the_ball_back_steeper_and_faster() {
+    let config = GameplayConfig::default();
+    let face = SweepHit { time: 0.5, normal: Vec2::NEG_X };
+
+    let mut speed = BallSpeed(config.ball_speed);
+    let middle = bounce(Vec2::new(700., 0.), &mut speed, &face, Some(0.), Vec2::ZERO, &config);
+    let mut speed = BallSpeed(config.ball_speed);
+    let edge = bounce(Vec2::new(700., 0.), &mut speed, &face, Some(1.), Vec2::ZERO, &config);
+
+    assert!(middle.x < 0. && edge.x < 0.);
+    assert!(edge.y > 0. && edge.y.atan2(-edge.x) > middle.y.atan2(-middle.x));
+    assert!(edge.length() > middle.length());
+    // the smash is for that one return, the rally speed only goes up the usual step
+    assert_eq!(speed.0, config.ball_speed + config.ball_speed_increment);
+}
+
+#[test]
+fn a_moving_paddle_puts_spin_on_the_return() {
+    let config = GameplayConfig::default();
+    let face = SweepHit { time: 0.5, normal: Vec2::X };
+    let mut speed = BallSpeed(config.ball_speed);
+    let still = bounce(Vec2::new(-700., 0.), &mut speed, &face, Some(0.), Vec2::ZERO, &config);
+    let mut speed = BallSpeed(config.ball_speed);
+    let moving = bounce(Vec2::new(-700., 0.), &mut speed, &face, Some(0.), Vec2::new(0., -500.), &config);
+
+    assert_eq!(still.y, 0.);
+    assert!(moving.x > 0. && moving.y < 0.);
+}
+
+// cargo test builds the example mod too, into the examples folder next to the one the test runs from
+#[cfg(feature = "dynamic_mods")]
+#[test]
+fn a_library_mod_loads_and_plays_its_part_once_switched_on() {
+    let examples = std::env::current_exe().unwrap().parent().unwrap().parent().unwrap().join("examples");
+    let path = examples.join(format!("{}updraft.{}", std::env::consts::DLL_PREFIX, std::env::consts::DLL_EXTENSION));
+    let mutator = unsafe { load_library(&path) }.unwrap();
+    assert_eq!(mutator.name(), "Updraft");
+
+    let mut game = TestMatch::served();
+    game.app.add_mod_mutator(mutator);
+    game.place_ball(Vec2::ZERO, Vec2::new(500., 0.));
+    game.step(1);
+    assert_eq!(game.ball_velocity().y, 0.);
+
+    let index = game.app.world.resource::<ModMutators>().names().iter().position(|name| *name == "Updraft").unwrap();
+    game.app.world.resource_mut::<ModMutators>().toggle(index);
+    game.step(1);
+    assert!(game.ball_velocity().y > 0.);
+}
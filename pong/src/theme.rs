@@ -0,0 +1,206 @@
+use bevy::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ball::Ball, replay::ReplayBall, ui::LetterboxBar};
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Theme>()
+        .add_systems(Update, apply_background.run_if(resource_changed::<Theme>()))
+        // balls come and go all match long, so they pick up the theme as they're spawned
+        .add_systems(PostUpdate, tint_balls);
+    }
+}
+
+// the sprites are white so each theme can tint them however it likes
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Classic,
+    HighContrast,
+    // Okabe-Ito colors, which stay apart for every common kind of color blindness
+    ColorblindSafe,
+    // white on black, like the original cabinet
+    Monochrome,
+    Neon,
+    Vaporwave,
+    // dark ink on a light page
+    Paper,
+}
+
+// every color the game draws with that isn't a fixed effect color, so a theme changes all of it at once
+pub struct Palette {
+    pub player: Color,
+    pub opponent: Color,
+    pub ball: Color,
+    pub divider: Color,
+    pub text: Color,
+    // lines under the main ones, like the match summary
+    pub muted_text: Color,
+    // behind the arena and the menus
+    pub background: Color,
+    pub button: Color,
+    pub button_hovered: Color,
+    pub button_pressed: Color,
+    // dims the match under the game over screen
+    pub overlay: Color,
+    // sudden death and anything else the players mustn't miss
+    pub alert: Color,
+    // survival's wall on the far end
+    pub wall: Color,
+}
+
+impl Theme {
+    pub fn palette(&self) -> Palette {
+        match self {
+            Theme::Classic => Palette {
+                player: Color::rgb(0.84, 0.47, 0.32),
+                opponent: Color::rgb(0.32, 0.49, 0.84),
+                ball: Color::rgb(0.78, 0.12, 0.58),
+                divider: Color::WHITE,
+                text: Color::WHITE,
+                muted_text: Color::rgb(0.8, 0.8, 0.8),
+                background: Color::BLACK,
+                button: Color::rgb(0.1, 0.1, 0.1),
+                button_hovered: Color::rgb(0.25, 0.25, 0.25),
+                button_pressed: Color::rgb(0.4, 0.4, 0.4),
+                overlay: Color::rgba(0., 0., 0., 0.8),
+                alert: Color::rgb(1., 0.3, 0.3),
+                wall: Color::rgb(0.6, 0.6, 0.6),
+            },
+            Theme::HighContrast => Palette {
+                player: Color::rgb(1., 1., 0.),
+                opponent: Color::rgb(0., 1., 1.),
+                ball: Color::WHITE,
+                divider: Color::rgb(0.5, 0.5, 0.5),
+                text: Color::WHITE,
+                muted_text: Color::WHITE,
+                background: Color::BLACK,
+                button: Color::BLACK,
+                button_hovered: Color::rgb(0.3, 0.3, 0.3),
+                button_pressed: Color::rgb(0.5, 0.5, 0.5),
+                overlay: Color::rgba(0., 0., 0., 0.9),
+                alert: Color::rgb(1., 0.2, 0.2),
+                wall: Color::rgb(0.8, 0.8, 0.8),
+            },
+            Theme::ColorblindSafe => Palette {
+                player: Color::rgb(0.9, 0.62, 0.),
+                opponent: Color::rgb(0., 0.45, 0.7),
+                ball: Color::rgb(0.94, 0.89, 0.26),
+                divider: Color::rgb(0.8, 0.8, 0.8),
+                text: Color::WHITE,
+                muted_text: Color::rgb(0.8, 0.8, 0.8),
+                background: Color::BLACK,
+                button: Color::rgb(0.1, 0.1, 0.1),
+                button_hovered: Color::rgb(0.25, 0.25, 0.25),
+                button_pressed: Color::rgb(0.4, 0.4, 0.4),
+                overlay: Color::rgba(0., 0., 0., 0.8),
+                alert: Color::rgb(0.84, 0.37, 0.),
+                wall: Color::rgb(0.6, 0.6, 0.6),
+            },
+            Theme::Monochrome => Palette {
+                player: Color::WHITE,
+                opponent: Color::WHITE,
+                ball: Color::WHITE,
+                divider: Color::rgb(0.6, 0.6, 0.6),
+                text: Color::WHITE,
+                muted_text: Color::rgb(0.7, 0.7, 0.7),
+                background: Color::BLACK,
+                button: Color::BLACK,
+                button_hovered: Color::rgb(0.2, 0.2, 0.2),
+                button_pressed: Color::rgb(0.35, 0.35, 0.35),
+                overlay: Color::rgba(0., 0., 0., 0.85),
+                alert: Color::WHITE,
+                wall: Color::rgb(0.6, 0.6, 0.6),
+            },
+            Theme::Neon => Palette {
+                player: Color::rgb(0., 1., 0.6),
+                opponent: Color::rgb(1., 0.1, 0.6),
+                ball: Color::rgb(1., 1., 0.3),
+                divider: Color::rgb(0.35, 0.15, 0.7),
+                text: Color::rgb(0.6, 1., 1.),
+                muted_text: Color::rgb(0.5, 0.75, 0.85),
+                background: Color::rgb(0.02, 0., 0.06),
+                button: Color::rgb(0.08, 0.02, 0.15),
+                button_hovered: Color::rgb(0.2, 0.05, 0.35),
+                button_pressed: Color::rgb(0.35, 0.1, 0.55),
+                overlay: Color::rgba(0.02, 0., 0.06, 0.85),
+                alert: Color::rgb(1., 0.15, 0.3),
+                wall: Color::rgb(0.45, 0.2, 0.85),
+            },
+            Theme::Vaporwave => Palette {
+                player: Color::rgb(1., 0.44, 0.81),
+                opponent: Color::rgb(0., 0.8, 0.9),
+                ball: Color::rgb(1., 0.9, 0.55),
+                divider: Color::rgb(0.73, 0.48, 0.98),
+                text: Color::rgb(0.98, 0.9, 1.),
+                muted_text: Color::rgb(0.8, 0.7, 0.9),
+                background: Color::rgb(0.16, 0.06, 0.26),
+                button: Color::rgb(0.25, 0.1, 0.38),
+                button_hovered: Color::rgb(0.38, 0.16, 0.52),
+                button_pressed: Color::rgb(0.5, 0.24, 0.66),
+                overlay: Color::rgba(0.16, 0.06, 0.26, 0.85),
+                alert: Color::rgb(1., 0.35, 0.45),
+                wall: Color::rgb(0.55, 0.35, 0.75),
+            },
+            Theme::Paper => Palette {
+                player: Color::rgb(0.75, 0.22, 0.17),
+                opponent: Color::rgb(0.16, 0.32, 0.55),
+                ball: Color::rgb(0.15, 0.15, 0.15),
+                divider: Color::rgb(0.55, 0.52, 0.46),
+                text: Color::rgb(0.12, 0.12, 0.12),
+                muted_text: Color::rgb(0.35, 0.33, 0.3),
+                background: Color::rgb(0.95, 0.92, 0.84),
+                button: Color::rgb(0.88, 0.84, 0.74),
+                button_hovered: Color::rgb(0.8, 0.75, 0.64),
+                button_pressed: Color::rgb(0.7, 0.65, 0.54),
+                overlay: Color::rgba(0.95, 0.92, 0.84, 0.85),
+                alert: Color::rgb(0.75, 0.1, 0.1),
+                wall: Color::rgb(0.45, 0.42, 0.37),
+            },
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Theme::Classic => Theme::HighContrast,
+            Theme::HighContrast => Theme::ColorblindSafe,
+            Theme::ColorblindSafe => Theme::Monochrome,
+            Theme::Monochrome => Theme::Neon,
+            Theme::Neon => Theme::Vaporwave,
+            Theme::Vaporwave => Theme::Paper,
+            Theme::Paper => Theme::Classic,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Theme::Classic => "Classic",
+            Theme::HighContrast => "High Contrast",
+            Theme::ColorblindSafe => "Colorblind Safe",
+            Theme::Monochrome => "Monochrome",
+            Theme::Neon => "Neon",
+            Theme::Vaporwave => "Vaporwave",
+            Theme::Paper => "Paper",
+        }
+    }
+}
+
+// the bars past the arena's edges match it too, so the letterboxing doesn't show
+fn apply_background(theme: Res<Theme>, mut clear_color: ResMut<ClearColor>, mut bars: Query<&mut Sprite, With<LetterboxBar>>) {
+    let background = theme.palette().background;
+    clear_color.0 = background;
+    for mut sprite in bars.iter_mut() {
+        sprite.color = background;
+    }
+}
+
+fn tint_balls(theme: Res<Theme>, mut balls: Query<&mut Sprite, Or<(Added<Ball>, Added<ReplayBall>)>>) {
+    for mut sprite in balls.iter_mut() {
+        sprite.color = theme.palette().ball;
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::{GameMode, GameState, MatchEntity, MatchRules, Velocity, ball::{Ball, BallSpeed, Serve, center_ball}, rng::GameRng, score::Scoreboard, theme::Theme};
+
+pub struct TimedMatchPlugin;
+
+impl Plugin for TimedMatchPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<MatchClock>()
+        .add_systems(OnExit(GameState::Menu), (reset_clock, spawn_clock_hud.run_if(timed_match)))
+        .add_systems(OnTransition { from: GameState::GameOver, to: GameState::Playing }, reset_clock)
+        .add_systems(Update, (tick_clock, end_timed_match).chain().run_if(in_state(GameState::Playing)).run_if(timed_match))
+        .add_systems(Update, update_clock_hud.run_if(timed_match));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct MatchClock {
+    pub remaining: Timer,
+    // time ran out with the scores level, so the next point wins
+    pub tie_break: bool,
+}
+
+#[derive(Component)]
+pub struct MatchClockText;
+
+pub fn timed_match(rules: Res<MatchRules>, mode: Res<GameMode>) -> bool {
+    rules.time_limit.is_some() && mode.is_match()
+}
+
+fn reset_clock(mut clock: ResMut<MatchClock>, rules: Res<MatchRules>) {
+    let minutes = rules.time_limit.unwrap_or_default();
+    *clock = MatchClock {
+        remaining: Timer::from_seconds(minutes as f32 * 60., TimerMode::Once),
+        tie_break: false,
+    };
+}
+
+fn spawn_clock_hud(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        MatchEntity,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                top: Val::Px(20.),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn((
+            MatchClockText,
+            TextBundle::from_section("", TextStyle {
+                font_size: 64.,
+                color: theme.palette().text,
+                ..default()
+            }),
+        ));
+    });
+}
+
+// the clock stops between points
+fn tick_clock(mut clock: ResMut<MatchClock>, serve: Res<Serve>, time: Res<Time>) {
+    if serve.is_counting_down() {
+        return;
+    }
+
+    clock.remaining.tick(time.delta());
+}
+
+fn end_timed_match(mut commands: Commands, mut clock: ResMut<MatchClock>, mut scoreboard: ResMut<Scoreboard>, (mut serve, mut random): (ResMut<Serve>, ResMut<GameRng>), mode: Res<GameMode>, mut next_state: ResMut<NextState<GameState>>, mut balls: Query<(Entity, &mut Transform, &mut Velocity, &mut BallSpeed), With<Ball>>) {
+    if !clock.remaining.finished() {
+        return;
+    }
+
+    if scoreboard.player != scoreboard.computer {
+        scoreboard.finish_set();
+        next_state.set(if *mode == GameMode::Demo { GameState::Menu } else { GameState::GameOver });
+        return;
+    }
+
+    if clock.tie_break {
+        return;
+    }
+
+    // level at the buzzer, so clear the field down to one deciding ball
+    clock.tie_break = true;
+    for (index, (ball, mut transform, mut velocity, mut speed)) in balls.iter_mut().enumerate() {
+        if index == 0 {
+            center_ball(&mut transform, &mut velocity, &mut speed);
+        } else {
+            commands.entity(ball).despawn_recursive();
+        }
+    }
+    serve.start(random.coin_flip());
+}
+
+fn update_clock_hud(clock: Res<MatchClock>, mut query: Query<&mut Text, With<MatchClockText>>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = if clock.tie_break {
+            "TIE-BREAK".to_string()
+        } else {
+            let seconds = clock.remaining.remaining_secs().ceil() as u32;
+            format!("{}:{:02}", seconds / 60, seconds % 60)
+        };
+    }
+}
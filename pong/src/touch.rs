@@ -0,0 +1,114 @@
+use bevy::{prelude::*, input::touch::Touches, utils::HashMap};
+
+use crate::{GameMode, GameSet, GameState, MatchEntity, ball::Serve, net::in_charge, paddle::{PaddleMotion, Player, human_movement_control}, score::Scoreboard, ui::MainCamera};
+
+// a touch let go this soon without moving far is a tap; anything longer or further was steering the paddle
+const TAP_SECONDS: f32 = 0.25;
+const TAP_DISTANCE: f32 = 20.;
+
+pub struct TouchPlugin;
+
+impl Plugin for TouchPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(OnExit(GameState::Menu), spawn_pause_button.run_if(not(resource_equals(GameMode::Demo))))
+        .add_systems(FixedUpdate, touch_paddle.after(human_movement_control).in_set(GameSet::Input))
+        // taps are only seen the frame they happen, which might not have a tick in it
+        .add_systems(Update, tap_to_serve.run_if(in_state(GameState::Playing)))
+        .add_systems(Update, (reveal_pause_button, pause_button_actions.run_if(in_charge)));
+    }
+}
+
+#[derive(Component)]
+pub struct PauseButton;
+
+fn spawn_pause_button(mut commands: Commands) {
+    commands.spawn((
+        MatchEntity,
+        PauseButton,
+        ButtonBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(20.),
+                right: Val::Px(20.),
+                width: Val::Px(80.),
+                height: Val::Px(80.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(2.)),
+                ..default()
+            },
+            border_color: Color::WHITE.into(),
+            background_color: Color::rgba(0.1, 0.1, 0.1, 0.6).into(),
+            // only shown once someone actually touches the screen
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn(TextBundle::from_section("II", TextStyle {
+            font_size: 48.,
+            color: Color::WHITE,
+            ..default()
+        }));
+    });
+}
+
+fn reveal_pause_button(touches: Res<Touches>, mut buttons: Query<&mut Visibility, With<PauseButton>>) {
+    if touches.iter_just_pressed().next().is_none() {
+        return;
+    }
+
+    for mut visibility in buttons.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn pause_button_actions(buttons: Query<&Interaction, (Changed<Interaction>, With<PauseButton>)>, state: Res<State<GameState>>, mut next_state: ResMut<NextState<GameState>>) {
+    for interaction in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match state.get() {
+            GameState::Playing => next_state.set(GameState::Paused),
+            GameState::Paused => next_state.set(GameState::Playing),
+            _ => (),
+        }
+    }
+}
+
+// a finger anywhere on the player's half pulls their paddle toward it
+pub fn touch_paddle(touches: Res<Touches>, cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>, scoreboard: Res<Scoreboard>, mut paddles: Query<(&mut PaddleMotion, &Transform), With<Player>>) {
+    let (camera, camera_transform) = if let Ok(camera) = cameras.get_single() { camera } else { return; };
+
+    let finger = touches.iter()
+        .filter_map(|touch| camera.viewport_to_world_2d(camera_transform, touch.position()))
+        .find(|position| position.x * scoreboard.player_side() > 0.);
+    let finger = if let Some(finger) = finger { finger } else { return; };
+
+    for (mut motion, transform) in paddles.iter_mut() {
+        motion.follow(finger.y - transform.translation.y);
+    }
+}
+
+// a tap serves straight away; when each touch still down went down is kept to tell taps from drags
+fn tap_to_serve(touches: Res<Touches>, time: Res<Time<Real>>, mode: Res<GameMode>, mut serve: ResMut<Serve>, mut pressed: Local<HashMap<u64, f32>>) {
+    let now = time.elapsed_seconds();
+    for touch in touches.iter_just_pressed() {
+        pressed.insert(touch.id(), now);
+    }
+    for touch in touches.iter_just_canceled() {
+        pressed.remove(&touch.id());
+    }
+
+    let mut tapped = false;
+    for touch in touches.iter_just_released() {
+        let quick = pressed.remove(&touch.id()).is_some_and(|started| now - started <= TAP_SECONDS);
+        tapped |= quick && touch.distance().length() <= TAP_DISTANCE;
+    }
+
+    // both machines have to serve on the same tick online
+    if tapped && !matches!(*mode, GameMode::Demo | GameMode::Online) {
+        serve.hurry();
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+use crate::{GameState, MatchEntity, Velocity, ball::Ball, gameplay::GameplayConfig, settings::ball_trail_enabled};
+
+// how long a ghost lingers for a ball at serve speed; faster balls leave longer trails
+const GHOST_LIFETIME: f32 = 0.12;
+const GHOST_ALPHA: f32 = 0.4;
+
+pub struct TrailPlugin;
+
+impl Plugin for TrailPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, spawn_ghosts.run_if(in_state(GameState::Playing)).run_if(ball_trail_enabled))
+        .add_systems(Update, fade_ghosts.run_if(not(in_state(GameState::Paused))));
+    }
+}
+
+#[derive(Component)]
+pub struct TrailGhost(Timer);
+
+fn spawn_ghosts(mut commands: Commands, config: Res<GameplayConfig>, balls: Query<(&Transform, &Velocity, &Handle<Image>, &Sprite), With<Ball>>) {
+    for (transform, velocity, texture, ball_sprite) in balls.iter() {
+        let speed = velocity.0.length();
+        if speed == 0. {
+            continue;
+        }
+
+        commands.spawn((
+            MatchEntity,
+            TrailGhost(Timer::from_seconds(GHOST_LIFETIME * speed / config.ball_speed, TimerMode::Once)),
+            SpriteBundle {
+                texture: texture.clone(),
+                sprite: Sprite {
+                    color: ball_sprite.color.with_a(GHOST_ALPHA),
+                    ..default()
+                },
+                transform: Transform::from_translation(transform.translation.truncate().extend(-0.1)),
+                ..default()
+            },
+        ));
+    }
+}
+
+fn fade_ghosts(mut commands: Commands, mut ghosts: Query<(Entity, &mut TrailGhost, &mut Sprite, &mut Transform)>, time: Res<Time>) {
+    for (entity, mut ghost, mut sprite, mut transform) in ghosts.iter_mut() {
+        if ghost.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let left = 1. - ghost.0.percent();
+        sprite.color.set_a(GHOST_ALPHA * left);
+        transform.scale = Vec3::splat(0.4 + 0.6 * left);
+    }
+}
@@ -0,0 +1,309 @@
+use bevy::{prelude::*, render::camera::ScalingMode};
+
+use crate::{ArenaConfig, GameMode, GameSet, GameState, MatchEntity, MatchRules, QuitConfirm, ball::Serve, bindings::{Bindings, key_name}, daily::DailyChallenge, demos::DemoPlayer, score::{Rally, Scoreboard}, shake::CameraShake, suspend::can_suspend, theme::Theme};
+
+// far bigger than any arena, so the bars cover whatever a wide or tall window shows past it
+const LETTERBOX_EXTENT: f32 = 10000.;
+
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Startup, spawn_camera)
+        .add_systems(OnExit(GameState::Menu), spawn_hud)
+        .add_systems(OnEnter(GameState::Paused), spawn_pause_overlay)
+        .add_systems(Update, (despawn_overlay, spawn_pause_overlay).chain().run_if(in_state(GameState::Paused)).run_if(resource_changed::<QuitConfirm>()))
+        .add_systems(OnExit(GameState::Paused), (despawn_overlay, cancel_quit))
+        .add_systems(Update, (fit_camera_to_arena.run_if(resource_changed::<ArenaConfig>()), update_scores, update_set_score, update_side_rows, update_serve_countdown, update_rally_text).in_set(GameSet::Presentation));
+    }
+}
+
+#[derive(Component)]
+pub struct StateOverlay;
+
+// the camera looking at the arena, as opposed to any that only composite its output
+#[derive(Component)]
+pub struct MainCamera;
+
+// one of the four black bars around the arena, pointing away from it
+#[derive(Component)]
+pub struct LetterboxBar(Vec2);
+
+#[derive(Component)]
+pub struct Score(bool);
+
+// a row of ui laid out [left side, right side] that flips when the players switch ends
+#[derive(Component)]
+pub struct SideRow;
+
+#[derive(Component)]
+pub struct SetScore;
+
+#[derive(Component)]
+pub struct ServeCountdown;
+
+#[derive(Component)]
+pub struct RallyText;
+
+fn spawn_overlay(commands: &mut Commands, message: &str, color: Color) {
+    commands.spawn((
+        StateOverlay,
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(message, TextStyle {
+            font_size: 64.,
+            color,
+            ..default()
+        }));
+    });
+}
+
+fn spawn_pause_overlay(mut commands: Commands, bindings: Res<Bindings>, theme: Res<Theme>, quit: Res<QuitConfirm>, gamepads: Res<Gamepads>, (mode, daily, demo): (Res<GameMode>, Res<DailyChallenge>, Res<DemoPlayer>)) {
+    let suspend = if can_suspend(&mode, &daily, &demo) { ", S to suspend" } else { "" };
+    let message = if quit.0 {
+        "Quit the game? Y to quit, any other key to stay".to_string()
+    } else if gamepads.iter().next().is_some() {
+        "Paused - Start to resume, Select to end the match".to_string()
+    } else if cfg!(target_arch = "wasm32") {
+        format!("Paused - {} to resume{suspend}, Q to end the match", key_name(bindings.pause))
+    } else {
+        format!("Paused - {} to resume{suspend}, Q to end the match, X to quit", key_name(bindings.pause))
+    };
+    spawn_overlay(&mut commands, &message, theme.palette().text);
+}
+
+// the touch pause button can resume without going through `state_input`
+fn cancel_quit(mut quit: ResMut<QuitConfirm>) {
+    quit.0 = false;
+}
+
+fn despawn_overlay(mut commands: Commands, query: Query<Entity, With<StateOverlay>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn((MainCamera, Camera2dBundle::default(), CameraShake::default()));
+
+    for direction in [Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y] {
+        commands.spawn((
+            LetterboxBar(direction),
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::BLACK,
+                    custom_size: Some(Vec2::splat(LETTERBOX_EXTENT)),
+                    ..default()
+                },
+                ..default()
+            },
+        ));
+    }
+}
+
+// the arena always fills as much of the window as it can, with bars over the rest
+fn fit_camera_to_arena(arena: Res<ArenaConfig>, mut cameras: Query<&mut OrthographicProjection, With<MainCamera>>, mut bars: Query<(&mut Transform, &LetterboxBar)>) {
+    for mut projection in cameras.iter_mut() {
+        projection.scaling_mode = ScalingMode::AutoMin { min_width: arena.width, min_height: arena.height };
+    }
+
+    let half_size = Vec2::new(arena.half_width(), arena.half_height());
+    for (mut transform, bar) in bars.iter_mut() {
+        let offset = bar.0 * (half_size + LETTERBOX_EXTENT / 2.);
+        transform.translation = offset.extend(100.);
+    }
+}
+
+// the scores, rally counter and serve countdown over the arena
+fn spawn_hud(mut commands: Commands, mode: Res<GameMode>, theme: Res<Theme>) {
+    let palette = theme.palette();
+    let score_visibility = if mode.is_match() { Visibility::Inherited } else { Visibility::Hidden };
+
+    // score contianer
+    let container = commands.spawn((
+        MatchEntity,
+        SideRow,
+        NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::SpaceAround,
+                width: Val::Percent(100.),
+                ..default()
+            },
+            visibility: score_visibility,
+            ..default()
+        },
+    )).id();
+
+    // player score
+    let player_score = commands.spawn((
+        Score(true),
+        TextBundle::from_section("0", TextStyle {
+            font_size: 96.,
+            color: palette.text,
+            ..default()
+        })
+    )).id();
+
+    // computer score
+    let computer_score = commands.spawn((
+        Score(false),
+        TextBundle::from_section("0", TextStyle {
+            font_size: 96.,
+            color: palette.text,
+            ..default()
+        })
+    )).id();
+
+    commands.entity(container).push_children(&[computer_score, player_score]);
+
+    // set score
+    commands.spawn((
+        MatchEntity,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                top: Val::Px(110.),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            visibility: score_visibility,
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn((
+            SetScore,
+            TextBundle::from_section("", TextStyle {
+                font_size: 32.,
+                color: palette.text,
+                ..default()
+            }),
+        ));
+    });
+
+    // rally counter
+    commands.spawn((
+        MatchEntity,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                bottom: Val::Px(100.),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn((
+            RallyText,
+            TextBundle::from_section("", TextStyle {
+                font_size: 40.,
+                color: palette.text,
+                ..default()
+            }),
+        ));
+    });
+
+    // serve countdown
+    commands.spawn((
+        MatchEntity,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                top: Val::Percent(30.),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn((
+            ServeCountdown,
+            TextBundle::from_section("", TextStyle {
+                font_size: 128.,
+                color: palette.text,
+                ..default()
+            }),
+        ));
+    });
+}
+
+fn update_set_score(scoreboard: Res<Scoreboard>, rules: Res<MatchRules>, mut query: Query<&mut Text, With<SetScore>>) {
+    if !scoreboard.is_changed() {
+        return;
+    }
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = if rules.uses_sets() {
+            let (left, right) = if scoreboard.sides_swapped {
+                (scoreboard.player_sets, scoreboard.computer_sets)
+            } else {
+                (scoreboard.computer_sets, scoreboard.player_sets)
+            };
+            format!("Sets {left} - {right}")
+        } else {
+            String::new()
+        };
+    }
+}
+
+fn update_side_rows(scoreboard: Res<Scoreboard>, mut rows: Query<&mut Style, With<SideRow>>) {
+    for mut style in rows.iter_mut() {
+        let direction = if scoreboard.sides_swapped { FlexDirection::RowReverse } else { FlexDirection::Row };
+        if style.flex_direction != direction {
+            style.flex_direction = direction;
+        }
+    }
+}
+
+// new score texts are filled in too, since a head start can mean they don't open at zero
+fn update_scores(scoreboard: Res<Scoreboard>, mut query: Query<(&mut Text, Ref<Score>)>) {
+    for (mut text, score) in query.iter_mut() {
+        if !scoreboard.is_changed() && !score.is_added() {
+            continue;
+        }
+
+        text.sections[0].value = if score.0 { scoreboard.player } else { scoreboard.computer }.to_string();
+    }
+}
+
+fn update_serve_countdown(serve: Res<Serve>, mut query: Query<(&mut Text, &mut Visibility), With<ServeCountdown>>) {
+    for (mut text, mut visibility) in query.iter_mut() {
+        if serve.is_counting_down() {
+            let remaining = serve.countdown.remaining_secs().ceil() as u32;
+            text.sections[0].value = remaining.to_string();
+            *visibility = Visibility::Visible;
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+fn update_rally_text(rally: Res<Rally>, theme: Res<Theme>, mut query: Query<&mut Text, With<RallyText>>) {
+    if !rally.is_changed() {
+        return;
+    }
+
+    for mut text in query.iter_mut() {
+        let section = &mut text.sections[0];
+        section.value = match rally.hits {
+            0 => String::new(),
+            hits if rally.new_best => format!("Rally {hits}  NEW BEST!"),
+            hits => format!("Rally {hits}"),
+        };
+        section.style.color = if rally.new_best { Color::GOLD } else { theme.palette().text };
+    }
+}
@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+
+use crate::{Collider, GameMode, GameSet, Velocity, ball::{Ball, BallSpeed, MIN_BALL_SPEED, Serve, center_ball, despawn_ball, limit_ball_velocity}, paddle::Paddle, rng::GameRng};
+
+// how long each kind of stuck ball is left alone before it's put back in play
+const IN_PADDLE_SECONDS: f32 = 0.5;
+const STALLED_SECONDS: f32 = 1.;
+const VERTICAL_SECONDS: f32 = 10.;
+// slower than this it's barely getting anywhere
+const STALLED_SPEED: f32 = MIN_BALL_SPEED / 2.;
+// the share of its speed left going sideways, below which it's just going up and down
+const VERTICAL_SHARE: f32 = 0.25;
+
+pub struct WatchdogPlugin;
+
+impl Plugin for WatchdogPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<BallRescued>()
+        .add_systems(FixedUpdate, (watch_new_balls, rescue_stuck_balls).chain().after(limit_ball_velocity).before(despawn_ball).run_if(not(resource_equals(GameMode::Practice))).in_set(GameSet::Physics));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StuckBall {
+    InPaddle,
+    Stalled,
+    Vertical,
+}
+
+#[derive(Event)]
+pub struct BallRescued {
+    pub reason: StuckBall,
+}
+
+// seconds the ball has spent in each bad way, each one cleared the moment the ball gets out of it
+#[derive(Component, Default, Clone)]
+pub struct StuckTimers {
+    in_paddle: f32,
+    stalled: f32,
+    vertical: f32,
+}
+
+impl StuckTimers {
+    fn stuck(&self) -> Option<StuckBall> {
+        if self.in_paddle > IN_PADDLE_SECONDS {
+            Some(StuckBall::InPaddle)
+        } else if self.stalled > STALLED_SECONDS {
+            Some(StuckBall::Stalled)
+        } else if self.vertical > VERTICAL_SECONDS {
+            Some(StuckBall::Vertical)
+        } else {
+            None
+        }
+    }
+}
+
+fn watch_new_balls(mut commands: Commands, balls: Query<Entity, (With<Ball>, Without<StuckTimers>)>) {
+    for ball in balls.iter() {
+        commands.entity(ball).insert(StuckTimers::default());
+    }
+}
+
+fn add_while(timer: &mut f32, condition: bool, seconds: f32) {
+    *timer = if condition { *timer + seconds } else { 0. };
+}
+
+// another ball still in play just loses the stuck one, otherwise the point is served again
+fn rescue_stuck_balls(mut commands: Commands, mut rescued: EventWriter<BallRescued>, mut serve: ResMut<Serve>, mut random: ResMut<GameRng>, time: Res<Time>, mut balls: Query<(Entity, &mut StuckTimers, &mut Transform, &mut Velocity, &mut BallSpeed, &Collider), With<Ball>>, paddles: Query<(&Transform, &Collider), (With<Paddle>, Without<Ball>)>) {
+    // still and centered, waiting to go
+    if serve.is_counting_down() {
+        return;
+    }
+
+    let mut in_play = balls.iter().count();
+    for (ball, mut timers, mut transform, mut velocity, mut speed, collider) in balls.iter_mut() {
+        let position = transform.translation.truncate();
+        let in_paddle = paddles.iter().any(|(paddle_transform, paddle_collider)| {
+            let gap = (position - paddle_transform.translation.truncate()).abs();
+            gap.cmplt((collider.0 + paddle_collider.0) / 2.).all()
+        });
+        let length = velocity.0.length();
+
+        add_while(&mut timers.in_paddle, in_paddle, time.delta_seconds());
+        add_while(&mut timers.stalled, length < STALLED_SPEED, time.delta_seconds());
+        add_while(&mut timers.vertical, length > 0. && velocity.0.x.abs() < length * VERTICAL_SHARE, time.delta_seconds());
+
+        let reason = if let Some(reason) = timers.stuck() { reason } else { continue; };
+        rescued.send(BallRescued { reason });
+        *timers = StuckTimers::default();
+
+        if in_play > 1 {
+            commands.entity(ball).despawn_recursive();
+            in_play -= 1;
+        } else {
+            center_ball(&mut transform, &mut velocity, &mut speed);
+            serve.start(random.coin_flip());
+        }
+    }
+}
@@ -0,0 +1,33 @@
+// Browsers keep every AudioContext suspended until the page gets a user gesture,
+// so track the contexts the game creates and resume them on the first interaction.
+(function () {
+    const contexts = [];
+    const Original = window.AudioContext || window.webkitAudioContext;
+    if (!Original) {
+        return;
+    }
+
+    function Tracked(...args) {
+        const context = new Original(...args);
+        contexts.push(context);
+        return context;
+    }
+    Tracked.prototype = Original.prototype;
+    window.AudioContext = Tracked;
+    window.webkitAudioContext = Tracked;
+
+    const events = ["click", "contextmenu", "auxclick", "dblclick", "mousedown", "mouseup", "pointerup", "touchend", "keydown", "keyup"];
+    function unlock() {
+        let running = true;
+        for (const context of contexts) {
+            if (context.state !== "running") {
+                context.resume();
+                running = false;
+            }
+        }
+        if (running && contexts.length > 0) {
+            events.forEach((event) => document.removeEventListener(event, unlock));
+        }
+    }
+    events.forEach((event) => document.addEventListener(event, unlock));
+})();